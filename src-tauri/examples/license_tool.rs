@@ -0,0 +1,128 @@
+//! Vendor-side tooling for offline licenses.
+//!
+//!   cargo run --example license_tool -- keygen <secret-out>
+//!   cargo run --example license_tool -- issue <secret> <license_id> <licensee> <tier> <seats> <expires|never> <device>...
+//!   cargo run --example license_tool -- stub-server <port> <active|revoked|expired> [token-file]
+//!
+//! `keygen` prints the public key to embed via `NIGHTCORE_LICENSE_PUBKEY`.
+//! `stub-server` answers refresh requests locally so `pro_refresh_license`
+//! can be exercised without the licensing service.
+
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    process,
+};
+
+use base64::{engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD}, Engine as _};
+use chrono::Utc;
+use ed25519_dalek::{Signer, SigningKey};
+use rand::rngs::OsRng;
+
+const TOKEN_PREFIX: &str = "NCL1";
+
+fn fail(msg: &str) -> ! {
+    eprintln!("{msg}");
+    process::exit(2);
+}
+
+fn keygen(out: &str) {
+    let key = SigningKey::generate(&mut OsRng);
+
+    fs::write(out, STANDARD.encode(key.to_bytes())).unwrap_or_else(|e| fail(&format!("writing {out}: {e}")));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(out, fs::Permissions::from_mode(0o600));
+    }
+
+    println!("{}", STANDARD.encode(key.verifying_key().to_bytes()));
+}
+
+fn issue(args: &[String]) {
+    let [secret, license_id, licensee, tier, seats, expires, devices @ ..] = args else {
+        fail("issue <secret> <license_id> <licensee> <tier> <seats> <expires|never> <device>...");
+    };
+
+    let raw = fs::read_to_string(secret).unwrap_or_else(|e| fail(&format!("reading {secret}: {e}")));
+    let bytes: [u8; 32] = STANDARD
+        .decode(raw.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .unwrap_or_else(|| fail("secret key must be base64 of 32 bytes"));
+    let key = SigningKey::from_bytes(&bytes);
+
+    let seats: u32 = seats.parse().unwrap_or_else(|_| fail("seats must be a number"));
+    if devices.is_empty() || devices.len() > seats as usize {
+        fail("need between 1 and <seats> device fingerprints");
+    }
+
+    let claims = serde_json::json!({
+        "license_id": license_id,
+        "licensee": licensee,
+        "tier": tier,
+        "issued_at": Utc::now().to_rfc3339(),
+        "expires_at": (expires != "never").then_some(expires),
+        "seats": seats,
+        "devices": devices,
+    });
+
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims).unwrap());
+    let signed = format!("{TOKEN_PREFIX}.{payload}");
+    let sig = URL_SAFE_NO_PAD.encode(key.sign(signed.as_bytes()).to_bytes());
+
+    println!("{signed}.{sig}");
+}
+
+fn stub_server(args: &[String]) {
+    let [port, status, rest @ ..] = args else {
+        fail("stub-server <port> <active|revoked|expired> [token-file]");
+    };
+    let token = rest.first().map(|p| {
+        fs::read_to_string(p).unwrap_or_else(|e| fail(&format!("reading {p}: {e}"))).trim().to_string()
+    });
+
+    let listener = TcpListener::bind(("127.0.0.1", port.parse::<u16>().unwrap_or_else(|_| fail("bad port"))))
+        .unwrap_or_else(|e| fail(&format!("bind: {e}")));
+    eprintln!("refresh stub on http://127.0.0.1:{port}/ answering '{status}'");
+
+    for stream in listener.incoming().flatten() {
+        let mut reader = BufReader::new(&stream);
+
+        let mut len = 0usize;
+        let mut line = String::new();
+        while reader.read_line(&mut line).is_ok_and(|n| n > 0) && line != "\r\n" {
+            if let Some((k, v)) = line.split_once(':') {
+                if k.eq_ignore_ascii_case("content-length") {
+                    len = v.trim().parse().unwrap_or(0);
+                }
+            }
+            line.clear();
+        }
+
+        let mut body = vec![0u8; len];
+        if reader.read_exact(&mut body).is_err() {
+            continue;
+        }
+        eprintln!("request: {}", String::from_utf8_lossy(&body));
+
+        let reply = serde_json::json!({ "status": status, "token": token }).to_string();
+        let _ = write!(
+            &stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+            reply.len()
+        );
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("keygen") => keygen(args.get(1).map(String::as_str).unwrap_or_else(|| fail("keygen <secret-out>"))),
+        Some("issue") => issue(&args[1..]),
+        Some("stub-server") => stub_server(&args[1..]),
+        _ => fail("usage: license_tool keygen | issue | stub-server"),
+    }
+}
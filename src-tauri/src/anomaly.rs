@@ -0,0 +1,385 @@
+use serde::{Serialize, Deserialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+
+use crate::atomic_fs::{write_atomic, Expect};
+use crate::decisions;
+use crate::GuardianDecisionLite;
+
+/// Version of `logs/anomaly_drift.json`. Readers refuse other versions.
+pub const REPORT_VERSION: u32 = 1;
+
+/// |z| at or above this is flagged.
+const Z_THRESHOLD: f64 = 3.0;
+
+/// Prior decisions needed before a tenant has a usable baseline.
+const MIN_SAMPLES: u64 = 5;
+
+const METRICS: [&str; 4] = ["runtime_request_ms", "memory_request_mb", "wasm_size_bytes", "threat_score"];
+
+/// Module hash change on a tenant between two consecutive decisions.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DriftRecord {
+    pub tenant: String,
+    pub old_sha: String,
+    pub new_sha: String,
+    pub timestamp: String,
+}
+
+/// Running mean and standard deviation (Welford).
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Baseline {
+    pub samples: u64,
+    pub mean: f64,
+    pub stddev: f64,
+    #[serde(skip)]
+    m2: f64,
+}
+
+impl Baseline {
+    fn push(&mut self, x: f64) {
+        self.samples += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.samples as f64;
+        self.m2 += delta * (x - self.mean);
+        self.stddev = if self.samples > 1 {
+            (self.m2 / (self.samples - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+    }
+}
+
+/// Baselines for one tenant (`scope` "tenant") or one module hash
+/// (`scope` "sha"), keyed by metric name.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BaselineSet {
+    pub scope: String,
+    pub key: String,
+    pub metrics: BTreeMap<String, Baseline>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// A metric far outside the tenant's baseline.
+    ZScore,
+    /// A metric off a baseline that had never varied.
+    ConstantBreak,
+    /// A WASI import the tenant had never requested before.
+    NewWasiImport,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Anomaly {
+    pub kind: AnomalyKind,
+    pub tenant: String,
+    pub sha256: String,
+    pub timestamp: String,
+    #[serde(default)]
+    pub metric: Option<String>,
+    #[serde(default)]
+    pub value: Option<f64>,
+    #[serde(default)]
+    pub baseline_mean: Option<f64>,
+    #[serde(default)]
+    pub z_score: Option<f64>,
+    #[serde(default)]
+    pub imports: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnomalyReport {
+    pub version: u32,
+    pub generated_at: String,
+    pub decisions_analyzed: usize,
+    pub drift: Vec<DriftRecord>,
+    pub anomalies: Vec<Anomaly>,
+    pub baselines: Vec<BaselineSet>,
+}
+
+pub fn report_path(worker_root: &Path) -> PathBuf {
+    worker_root.join("logs").join("anomaly_drift.json")
+}
+
+fn metric(d: &GuardianDecisionLite, name: &str) -> f64 {
+    match name {
+        "runtime_request_ms" => d.runtime_request_ms as f64,
+        "memory_request_mb" => d.memory_request_mb as f64,
+        "wasm_size_bytes" => d.wasm_size_bytes as f64,
+        _ => d.threat_score as f64,
+    }
+}
+
+#[derive(Default)]
+struct TenantHistory {
+    decisions: u64,
+    metrics: BTreeMap<String, Baseline>,
+    imports: BTreeSet<String>,
+    last_sha: Option<String>,
+}
+
+fn parse_ts(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Walk the decision history in time order, log order breaking ties.
+/// Each decision is compared with what its tenant looked like before
+/// it, then folded into the baseline.
+pub fn analyze(history: &[GuardianDecisionLite]) -> AnomalyReport {
+    let mut ordered: Vec<&GuardianDecisionLite> = history.iter().collect();
+    ordered.sort_by_key(|d| parse_ts(&d.timestamp));
+
+    let mut tenants: BTreeMap<String, TenantHistory> = BTreeMap::new();
+    let mut shas: BTreeMap<String, BTreeMap<String, Baseline>> = BTreeMap::new();
+    let mut drift = vec![];
+    let mut anomalies = vec![];
+
+    for d in ordered.iter().copied() {
+        let sha = d.sha256.to_lowercase();
+        let t = tenants.entry(d.tenant.clone()).or_default();
+
+        if let Some(prev) = &t.last_sha {
+            if *prev != sha {
+                drift.push(DriftRecord {
+                    tenant: d.tenant.clone(),
+                    old_sha: prev.clone(),
+                    new_sha: sha.clone(),
+                    timestamp: d.timestamp.clone(),
+                });
+            }
+        }
+
+        let known = t.decisions >= MIN_SAMPLES;
+
+        for name in METRICS {
+            let x = metric(d, name);
+            let base = t.metrics.entry(name.into()).or_default();
+
+            if known {
+                let flagged = if base.stddev > 0.0 {
+                    let z = (x - base.mean) / base.stddev;
+                    (z.abs() >= Z_THRESHOLD).then_some((AnomalyKind::ZScore, Some(z)))
+                } else if x != base.mean {
+                    Some((AnomalyKind::ConstantBreak, None))
+                } else {
+                    None
+                };
+
+                if let Some((kind, z_score)) = flagged {
+                    anomalies.push(Anomaly {
+                        kind,
+                        tenant: d.tenant.clone(),
+                        sha256: sha.clone(),
+                        timestamp: d.timestamp.clone(),
+                        metric: Some(name.into()),
+                        value: Some(x),
+                        baseline_mean: Some(base.mean),
+                        z_score,
+                        imports: vec![],
+                    });
+                }
+            }
+
+            base.push(x);
+            shas.entry(sha.clone()).or_default().entry(name.into()).or_default().push(x);
+        }
+
+        let new_imports: Vec<String> = d
+            .wasi_imports
+            .iter()
+            .filter(|i| !t.imports.contains(*i))
+            .cloned()
+            .collect();
+
+        // Only a tenant with history can start requesting something new.
+        if t.last_sha.is_some() && !new_imports.is_empty() {
+            anomalies.push(Anomaly {
+                kind: AnomalyKind::NewWasiImport,
+                tenant: d.tenant.clone(),
+                sha256: sha.clone(),
+                timestamp: d.timestamp.clone(),
+                metric: None,
+                value: None,
+                baseline_mean: None,
+                z_score: None,
+                imports: new_imports.clone(),
+            });
+        }
+
+        t.decisions += 1;
+        t.imports.extend(new_imports);
+        t.last_sha = Some(sha);
+    }
+
+    let baselines = tenants
+        .into_iter()
+        .map(|(key, h)| BaselineSet { scope: "tenant".into(), key, metrics: h.metrics })
+        .chain(
+            shas.into_iter()
+                .map(|(key, metrics)| BaselineSet { scope: "sha".into(), key, metrics }),
+        )
+        .collect();
+
+    AnomalyReport {
+        version: REPORT_VERSION,
+        generated_at: Utc::now().to_rfc3339(),
+        decisions_analyzed: history.len(),
+        drift,
+        anomalies,
+        baselines,
+    }
+}
+
+/// Analyze the full decision history and write the report.
+pub fn run(worker_root: &Path) -> Result<AnomalyReport> {
+    let report = analyze(&decisions::load_all(worker_root));
+
+    write_atomic(
+        &report_path(worker_root),
+        serde_json::to_string_pretty(&report)?.as_bytes(),
+        Expect::Any,
+    )?;
+
+    Ok(report)
+}
+
+/// Read and validate the report on disk.
+pub fn load(worker_root: &Path) -> Result<AnomalyReport> {
+    let p = report_path(worker_root);
+    let raw = fs::read_to_string(&p).with_context(|| format!("reading {}", p.display()))?;
+
+    let v: serde_json::Value = serde_json::from_str(&raw).with_context(|| format!("parsing {}", p.display()))?;
+    match v.get("version").and_then(|x| x.as_u64()) {
+        Some(n) if n == REPORT_VERSION as u64 => {}
+        Some(n) => bail!("anomaly report version {n} is not supported (expected {REPORT_VERSION})"),
+        None => bail!("anomaly report has no version"),
+    }
+
+    serde_json::from_value(v).with_context(|| format!("validating {}", p.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision(minute: u32, tenant: &str, sha: &str, runtime_ms: u64, imports: &[&str]) -> GuardianDecisionLite {
+        serde_json::from_value(decisions::decision_json(serde_json::json!({
+            "timestamp": format!("2026-03-01T10:{minute:02}:00Z"), "tenant": tenant, "sha256": sha,
+            "threat_score": 10, "wasm_size_bytes": 1000, "runtime_request_ms": runtime_ms, "wasi_imports": imports,
+        })))
+        .unwrap()
+    }
+
+    fn kinds(r: &AnomalyReport) -> Vec<(String, Option<String>)> {
+        r.anomalies
+            .iter()
+            .map(|a| (serde_json::to_value(&a.kind).unwrap().as_str().unwrap().to_string(), a.metric.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn baseline_matches_sample_statistics() {
+        let mut b = Baseline::default();
+        for x in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+            b.push(x);
+        }
+        assert_eq!(b.samples, 8);
+        assert!((b.mean - 5.0).abs() < 1e-9);
+        assert!((b.stddev - (32.0f64 / 7.0).sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn no_verdicts_before_a_baseline() {
+        let history: Vec<_> = (0..MIN_SAMPLES as u32)
+            .map(|i| decision(i, "t", "aa", 100 + 1000 * i as u64, &[]))
+            .collect();
+        assert!(analyze(&history).anomalies.is_empty());
+    }
+
+    #[test]
+    fn flags_outliers_and_constant_breaks() {
+        let mut history: Vec<_> = [100, 110, 90, 105, 95]
+            .iter()
+            .enumerate()
+            .map(|(i, ms)| decision(i as u32, "t", "aa", *ms, &[]))
+            .collect();
+        history.push(decision(10, "t", "aa", 101, &[]));
+        assert!(analyze(&history).anomalies.is_empty());
+
+        history.push(decision(11, "t", "aa", 5000, &[]));
+        let mut last = decision(12, "t", "aa", 100, &[]);
+        last.memory_request_mb = 512;
+        history.push(last);
+
+        let r = analyze(&history);
+        assert_eq!(
+            kinds(&r),
+            vec![
+                ("z_score".into(), Some("runtime_request_ms".into())),
+                ("constant_break".into(), Some("memory_request_mb".into())),
+            ]
+        );
+        assert!(r.anomalies[0].z_score.unwrap() >= Z_THRESHOLD);
+    }
+
+    #[test]
+    fn drift_and_new_imports_per_tenant_in_time_order() {
+        // Deliberately out of order; analysis sorts by timestamp.
+        let history = vec![
+            decision(2, "t", "BB", 100, &["fd_read", "sock_send"]),
+            decision(0, "t", "aa", 100, &["fd_read"]),
+            decision(1, "other", "cc", 100, &["sock_send"]),
+            decision(3, "t", "bb", 100, &["fd_read"]),
+        ];
+
+        let r = analyze(&history);
+        assert_eq!(r.decisions_analyzed, 4);
+        assert_eq!(r.drift.len(), 1);
+        assert_eq!((r.drift[0].old_sha.as_str(), r.drift[0].new_sha.as_str()), ("aa", "bb"));
+
+        assert_eq!(kinds(&r), vec![("new_wasi_import".into(), None)]);
+        assert_eq!(r.anomalies[0].imports, vec!["sock_send".to_string()]);
+
+        let scopes: Vec<_> = r.baselines.iter().map(|b| (b.scope.as_str(), b.key.as_str())).collect();
+        assert_eq!(scopes, vec![("tenant", "other"), ("tenant", "t"), ("sha", "aa"), ("sha", "bb"), ("sha", "cc")]);
+    }
+
+    #[test]
+    fn orders_by_instant_then_log_order() {
+        // 10:30+01:00 is 09:30Z, before the others; the two at 10:05Z
+        // keep their log order.
+        let mut early = decision(0, "t", "bb", 100, &[]);
+        early.timestamp = "2026-03-01T10:30:00+01:00".into();
+        let history = vec![
+            decision(5, "t", "cc", 100, &[]),
+            early,
+            decision(5, "t", "dd", 100, &[]),
+        ];
+
+        let r = analyze(&history);
+        let drift: Vec<_> = r.drift.iter().map(|d| (d.old_sha.as_str(), d.new_sha.as_str())).collect();
+        assert_eq!(drift, vec![("bb", "cc"), ("cc", "dd")]);
+    }
+
+    #[test]
+    fn load_refuses_other_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        assert!(load(root).is_err());
+
+        let mut report = analyze(&[decision(0, "t", "aa", 100, &[])]);
+        fs::create_dir_all(root.join("logs")).unwrap();
+        fs::write(report_path(root), serde_json::to_string(&report).unwrap()).unwrap();
+        assert_eq!(load(root).unwrap().decisions_analyzed, 1);
+
+        report.version = REPORT_VERSION + 1;
+        fs::write(report_path(root), serde_json::to_string(&report).unwrap()).unwrap();
+        assert!(load(root).unwrap_err().to_string().contains("not supported"));
+    }
+}
@@ -0,0 +1,399 @@
+use serde::{Serialize, Deserialize};
+use std::{fs, path::Path};
+use serde_json::Value;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, TimeDelta, Utc};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+use crate::atomic_fs;
+use crate::submission::sha256_hex;
+
+/// "N of these maintainers" as declared in a Guardian policy:
+///
+/// ```json
+/// "approval": { "required": 2, "maintainers": ["alice", "bob", "carol"] }
+/// ```
+///
+/// Maintainer public keys live in `keys/maintainers/<name>.b64`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuorumRequirement {
+    pub required: usize,
+    pub maintainers: Vec<String>,
+}
+
+/// How far ahead of the console's clock `signed_at` may be.
+const MAX_CLOCK_SKEW: TimeDelta = TimeDelta::minutes(5);
+
+/// One maintainer's signed approval, persisted in the manifest.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApprovalRecord {
+    pub approver: String,
+    pub key_fingerprint: String,
+    pub sha256: String,
+    pub signed_at: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuorumStatus {
+    pub tenant: String,
+    pub sha256: String,
+    pub required: usize,
+    pub maintainers: Vec<String>,
+    /// Maintainers whose approvals count towards clearing the tenant now.
+    pub approvers: Vec<String>,
+    pub met: bool,
+}
+
+pub fn key_fingerprint(pubkey: &[u8]) -> String {
+    sha256_hex(pubkey)[..16].to_string()
+}
+
+/// Bytes each maintainer signs. The module hash pins the approval to
+/// exactly one build of the tenant.
+pub fn canonical_approval_string(tenant: &str, sha256: &str, approver: &str, signed_at: &str) -> String {
+    format!(
+        "nightcore-approval:v1\n\
+tenant={}\n\
+sha256={}\n\
+approver={}\n\
+signed_at={}\n",
+        tenant.trim(),
+        sha256.trim(),
+        approver.trim(),
+        signed_at.trim(),
+    )
+}
+
+fn read_json(path: &Path) -> Result<Value> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("reading {}", path.display()))?;
+    serde_json::from_str(&raw)
+        .with_context(|| format!("parsing {}", path.display()))
+}
+
+/// The Guardian policy that applies to a tenant: its own file first,
+/// then `_default`.
+pub fn load_policy(worker_root: &Path, tenant: &str) -> Result<Option<(std::path::PathBuf, Value)>> {
+    let dir = worker_root.join("guardian").join("policies");
+
+    for name in [format!("{tenant}.json"), "_default.json".to_string()] {
+        let p = dir.join(name);
+        if p.exists() {
+            let v = read_json(&p)?;
+            return Ok(Some((p, v)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// No `approval` block means a single operator approval is enough.
+pub fn load_quorum(worker_root: &Path, tenant: &str) -> Result<Option<QuorumRequirement>> {
+    let Some((p, v)) = load_policy(worker_root, tenant)? else {
+        return Ok(None);
+    };
+    let Some(q) = v.get("approval") else {
+        return Ok(None);
+    };
+
+    let q: QuorumRequirement = serde_json::from_value(q.clone())
+        .with_context(|| format!("parsing approval block in {}", p.display()))?;
+
+    if q.required == 0 || q.required > q.maintainers.len() {
+        bail!(
+            "invalid approval quorum in {}: {} of {}",
+            p.display(),
+            q.required,
+            q.maintainers.len()
+        );
+    }
+
+    Ok(Some(q))
+}
+
+pub fn maintainer_key(worker_root: &Path, name: &str) -> Result<VerifyingKey> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        bail!("invalid maintainer name '{name}'");
+    }
+
+    let p = worker_root
+        .join("keys")
+        .join("maintainers")
+        .join(format!("{name}.b64"));
+
+    let raw = fs::read_to_string(&p)
+        .with_context(|| format!("no key for maintainer '{name}'"))?;
+
+    let bytes: [u8; 32] = STANDARD
+        .decode(raw.trim())
+        .context("decoding maintainer key")?
+        .try_into()
+        .map_err(|_| anyhow!("maintainer key for '{name}' must be 32 bytes"))?;
+
+    VerifyingKey::from_bytes(&bytes)
+        .map_err(|e| anyhow!("invalid maintainer key for '{name}': {e}"))
+}
+
+pub fn current_module_sha(worker_root: &Path, tenant: &str) -> Result<String> {
+    let p = worker_root.join("modules").join(tenant).join("module.wasm");
+    let bytes = fs::read(&p).with_context(|| format!("reading {}", p.display()))?;
+    Ok(sha256_hex(&bytes))
+}
+
+fn manifest_path(worker_root: &Path, tenant: &str) -> std::path::PathBuf {
+    worker_root.join("modules").join(tenant).join("manifest.json")
+}
+
+fn recorded_approvals(manifest: &Value) -> Vec<ApprovalRecord> {
+    manifest
+        .get("approvals")
+        .and_then(|a| serde_json::from_value(a.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn parse_time(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts.trim()).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Approvals that can still clear the tenant: over the module as it is
+/// now, and signed after the tenant was last cleared. Each clearance uses
+/// up the approvals before it, so trust that lapses is only restored by
+/// new signatures, never by replaying old ones.
+fn fresh_approvals(manifest: &Value, sha256: &str) -> Vec<ApprovalRecord> {
+    let cleared_at = manifest.get("approved_at").and_then(|t| t.as_str()).and_then(parse_time);
+
+    recorded_approvals(manifest)
+        .into_iter()
+        .filter(|r| r.sha256 == sha256)
+        .filter(|r| match (parse_time(&r.signed_at), cleared_at) {
+            (Some(signed), Some(cleared)) => signed > cleared,
+            (Some(_), None) => true,
+            (None, _) => false,
+        })
+        .collect()
+}
+
+fn status_for(tenant: &str, sha256: &str, q: &QuorumRequirement, manifest: &Value) -> QuorumStatus {
+    let approvers: Vec<String> = fresh_approvals(manifest, sha256)
+        .into_iter()
+        .filter(|r| q.maintainers.contains(&r.approver))
+        .map(|r| r.approver)
+        .collect();
+
+    QuorumStatus {
+        tenant: tenant.into(),
+        sha256: sha256.into(),
+        required: q.required,
+        maintainers: q.maintainers.clone(),
+        met: approvers.len() >= q.required,
+        approvers,
+    }
+}
+
+pub fn quorum_status(worker_root: &Path, tenant: &str) -> Result<Option<QuorumStatus>> {
+    let Some(q) = load_quorum(worker_root, tenant)? else {
+        return Ok(None);
+    };
+
+    let sha256 = current_module_sha(worker_root, tenant)?;
+    let manifest = read_json(&manifest_path(worker_root, tenant))?;
+
+    Ok(Some(status_for(tenant, &sha256, &q, &manifest)))
+}
+
+/// Verify and record one maintainer approval. Returns the quorum state
+/// after recording; the caller clears the tenant once `met` is true.
+pub fn add_approval(
+    worker_root: &Path,
+    tenant: &str,
+    approver: &str,
+    signed_at: &str,
+    signature_b64: &str,
+) -> Result<QuorumStatus> {
+    let q = load_quorum(worker_root, tenant)?
+        .ok_or_else(|| anyhow!("tenant {tenant} does not require quorum approval"))?;
+
+    if !q.maintainers.iter().any(|m| m == approver) {
+        bail!("'{approver}' is not a maintainer for tenant {tenant}");
+    }
+
+    let signed = parse_time(signed_at)
+        .ok_or_else(|| anyhow!("signed_at '{}' is not an RFC 3339 timestamp", signed_at.trim()))?;
+    if signed > Utc::now() + MAX_CLOCK_SKEW {
+        bail!("signed_at '{}' is in the future", signed_at.trim());
+    }
+
+    let key = maintainer_key(worker_root, approver)?;
+    let sha256 = current_module_sha(worker_root, tenant)?;
+
+    let sig: [u8; 64] = STANDARD
+        .decode(signature_b64.trim())
+        .context("decoding approval signature")?
+        .try_into()
+        .map_err(|_| anyhow!("approval signature must be 64 bytes"))?;
+
+    key.verify(
+        canonical_approval_string(tenant, &sha256, approver, signed_at).as_bytes(),
+        &Signature::from_bytes(&sig),
+    )
+    .map_err(|_| anyhow!("approval signature from '{approver}' does not verify for sha256 {sha256}"))?;
+
+    let record = ApprovalRecord {
+        approver: approver.into(),
+        key_fingerprint: key_fingerprint(key.as_bytes()),
+        sha256: sha256.clone(),
+        signed_at: signed_at.trim().into(),
+        signature: signature_b64.trim().into(),
+    };
+
+    let manifest = atomic_fs::update_json(&manifest_path(worker_root, tenant), |m| {
+        let mut records = recorded_approvals(m);
+        records.retain(|r| r.approver != approver);
+        records.push(record.clone());
+        m["approvals"] = serde_json::to_value(&records)?;
+        Ok(())
+    })?;
+
+    Ok(status_for(tenant, &sha256, &q, &manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const SIGNED_AT: &str = "2026-01-01T00:00:00Z";
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn setup(required: usize) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        fs::create_dir_all(root.join("guardian/policies")).unwrap();
+        fs::write(
+            root.join("guardian/policies/_default.json"),
+            serde_json::json!({
+                "approval": { "required": required, "maintainers": ["alice", "bob", "carol"] }
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        fs::create_dir_all(root.join("keys/maintainers")).unwrap();
+        for (name, seed) in [("alice", 1), ("bob", 2), ("carol", 3)] {
+            fs::write(
+                root.join(format!("keys/maintainers/{name}.b64")),
+                STANDARD.encode(key(seed).verifying_key().as_bytes()),
+            )
+            .unwrap();
+        }
+
+        fs::create_dir_all(root.join("modules/t1")).unwrap();
+        fs::write(root.join("modules/t1/module.wasm"), b"\0asm\x01\0\0\0").unwrap();
+        fs::write(root.join("modules/t1/manifest.json"), "{}").unwrap();
+
+        dir
+    }
+
+    fn sign(root: &Path, name: &str, seed: u8) -> String {
+        let sha = current_module_sha(root, "t1").unwrap();
+        let msg = canonical_approval_string("t1", &sha, name, SIGNED_AT);
+        STANDARD.encode(key(seed).sign(msg.as_bytes()).to_bytes())
+    }
+
+    #[test]
+    fn quorum_met_after_required_approvals() {
+        let dir = setup(2);
+        let root = dir.path();
+
+        let s = add_approval(root, "t1", "alice", SIGNED_AT, &sign(root, "alice", 1)).unwrap();
+        assert!(!s.met);
+
+        // Re-approving replaces rather than double counts.
+        let s = add_approval(root, "t1", "alice", SIGNED_AT, &sign(root, "alice", 1)).unwrap();
+        assert_eq!(s.approvers, vec!["alice"]);
+
+        let s = add_approval(root, "t1", "bob", SIGNED_AT, &sign(root, "bob", 2)).unwrap();
+        assert!(s.met);
+        assert_eq!(s.approvers, vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn rejects_bad_signatures_and_outsiders() {
+        let dir = setup(2);
+        let root = dir.path();
+
+        // bob's signature presented as alice's
+        assert!(add_approval(root, "t1", "alice", SIGNED_AT, &sign(root, "alice", 2)).is_err());
+        // signed_at is covered by the signature
+        assert!(add_approval(root, "t1", "alice", "2027-01-01T00:00:00Z", &sign(root, "alice", 1)).is_err());
+        assert!(add_approval(root, "t1", "mallory", SIGNED_AT, &sign(root, "mallory", 9)).is_err());
+    }
+
+    #[test]
+    fn approvals_of_an_older_module_no_longer_count() {
+        let dir = setup(1);
+        let root = dir.path();
+
+        let s = add_approval(root, "t1", "alice", SIGNED_AT, &sign(root, "alice", 1)).unwrap();
+        assert!(s.met);
+
+        fs::write(root.join("modules/t1/module.wasm"), b"\0asm\x01\0\0\0\0").unwrap();
+        let s = quorum_status(root, "t1").unwrap().unwrap();
+        assert!(!s.met);
+        assert!(s.approvers.is_empty());
+    }
+
+    #[test]
+    fn lapsed_trust_needs_fresh_signatures() {
+        let dir = setup(1);
+        let root = dir.path();
+        let alice = sign(root, "alice", 1);
+
+        assert!(add_approval(root, "t1", "alice", SIGNED_AT, &alice).unwrap().met);
+        crate::tenant_state::mark_authorized(root, "t1", crate::tenant_state::ApprovedBy::Quorum(&["alice".into()]))
+            .unwrap();
+        assert!(!quorum_status(root, "t1").unwrap().unwrap().met);
+
+        // Trust lapses; replaying the stored signature does not restore it.
+        atomic_fs::update_json(&root.join("modules/t1/manifest.json"), |m| {
+            m["expires_at"] = Value::String("2026-01-02T00:00:00Z".into());
+            Ok(())
+        })
+        .unwrap();
+        let s = add_approval(root, "t1", "alice", SIGNED_AT, &alice).unwrap();
+        assert!(!s.met);
+        assert!(s.approvers.is_empty());
+
+        // A new signature does.
+        let signed_at = (Utc::now() + TimeDelta::seconds(1)).to_rfc3339();
+        let sha = current_module_sha(root, "t1").unwrap();
+        let msg = canonical_approval_string("t1", &sha, "alice", &signed_at);
+        let fresh = STANDARD.encode(key(1).sign(msg.as_bytes()).to_bytes());
+        assert!(add_approval(root, "t1", "alice", &signed_at, &fresh).unwrap().met);
+    }
+
+    #[test]
+    fn signed_at_must_be_a_past_timestamp() {
+        let dir = setup(1);
+        let root = dir.path();
+        let sha = current_module_sha(root, "t1").unwrap();
+
+        for signed_at in ["yesterday", "2999-01-01T00:00:00Z"] {
+            let msg = canonical_approval_string("t1", &sha, "alice", signed_at);
+            let sig = STANDARD.encode(key(1).sign(msg.as_bytes()).to_bytes());
+            assert!(add_approval(root, "t1", "alice", signed_at, &sig).is_err(), "{signed_at}");
+        }
+    }
+
+    #[test]
+    fn invalid_quorum_is_an_error() {
+        let dir = setup(4);
+        assert!(load_quorum(dir.path(), "t1").is_err());
+    }
+}
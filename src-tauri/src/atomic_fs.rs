@@ -0,0 +1,470 @@
+use std::{
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::submission::sha256_hex;
+
+/// What the caller believes is on disk before the write.
+pub enum Expect<'a> {
+    /// Overwrite whatever is there.
+    Any,
+    /// The file must not exist yet.
+    Absent,
+    /// The file must still hash to this value.
+    Sha256(&'a str),
+}
+
+impl<'a> Expect<'a> {
+    /// `Absent` for a file that did not exist when read, else its hash.
+    pub fn from_prev(prev: Option<&'a str>) -> Self {
+        match prev {
+            Some(sha) => Expect::Sha256(sha),
+            None => Expect::Absent,
+        }
+    }
+}
+
+/// Another writer (usually the worker) changed the file between our read
+/// and our write. Callers re-read and retry, or report it.
+#[derive(Debug)]
+pub struct WriteConflict {
+    pub path: PathBuf,
+}
+
+impl fmt::Display for WriteConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "write conflict: {} changed on disk since it was read", self.path.display())
+    }
+}
+
+impl std::error::Error for WriteConflict {}
+
+fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+/// Exclusive advisory lock on `<file>.lock`, released on drop. A sidecar
+/// is used because the rename below replaces the target inode.
+fn lock(path: &Path) -> Result<File> {
+    let lp = lock_path(path);
+    let f = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lp)
+        .with_context(|| format!("opening {}", lp.display()))?;
+
+    f.lock().with_context(|| format!("locking {}", lp.display()))?;
+    Ok(f)
+}
+
+fn current_sha(path: &Path) -> Result<Option<String>> {
+    match fs::read(path) {
+        Ok(b) => Ok(Some(sha256_hex(&b))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("reading {}", path.display())),
+    }
+}
+
+fn sync_dir(dir: &Path) {
+    // Directory fsync makes the rename durable on Unix; Windows has no
+    // equivalent for directory handles.
+    #[cfg(unix)]
+    if let Ok(d) = File::open(dir) {
+        let _ = d.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+}
+
+/// Temp file, fsync, rename, under the file's lock. The expectation is
+/// checked after the lock is taken so concurrent console writers and a
+/// cooperating worker cannot interleave.
+pub fn write_atomic(path: &Path, bytes: &[u8], expect: Expect) -> Result<()> {
+    write_with(path, bytes, expect, false)
+}
+
+/// [`write_atomic`] for secrets: the temp file is owner-only (0600 on
+/// Unix) before anything is written to it, so the file is never readable
+/// by others, and failing to restrict it fails the write.
+pub fn write_private(path: &Path, bytes: &[u8], expect: Expect) -> Result<()> {
+    write_with(path, bytes, expect, true)
+}
+
+fn create_temp(tmp: &Path, private: bool) -> Result<File> {
+    let mut opts = OpenOptions::new();
+    opts.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        opts.mode(0o600);
+        let f = opts.open(tmp).with_context(|| format!("creating {}", tmp.display()))?;
+        // A leftover temp file keeps its old mode; `mode` only applies on
+        // creation.
+        f.set_permissions(fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("restricting permissions on {}", tmp.display()))?;
+        return Ok(f);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+
+    opts.open(tmp).with_context(|| format!("creating {}", tmp.display()))
+}
+
+fn write_with(path: &Path, bytes: &[u8], expect: Expect, private: bool) -> Result<()> {
+    let dir = path
+        .parent()
+        .with_context(|| format!("{} has no parent directory", path.display()))?;
+    fs::create_dir_all(dir)
+        .with_context(|| format!("creating {}", dir.display()))?;
+
+    let _guard = lock(path)?;
+
+    let on_disk = current_sha(path)?;
+    let ok = match expect {
+        Expect::Any => true,
+        Expect::Absent => on_disk.is_none(),
+        Expect::Sha256(want) => on_disk.as_deref() == Some(want),
+    };
+    if !ok {
+        return Err(WriteConflict { path: path.to_path_buf() }.into());
+    }
+
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(path.file_name().unwrap_or_default());
+    tmp_name.push(format!(".tmp-{}", std::process::id()));
+    let tmp = dir.join(tmp_name);
+
+    let result = (|| -> Result<()> {
+        let mut f = create_temp(&tmp, private)?;
+        f.write_all(bytes)?;
+        f.sync_all()?;
+        fs::rename(&tmp, path)
+            .with_context(|| format!("replacing {}", path.display()))
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result?;
+
+    sync_dir(dir);
+    Ok(())
+}
+
+/// Contents plus hash, for callers that parse first and write later.
+pub fn read_versioned(path: &Path) -> Result<Option<(Vec<u8>, String)>> {
+    match fs::read(path) {
+        Ok(b) => {
+            let sha = sha256_hex(&b);
+            Ok(Some((b, sha)))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("reading {}", path.display())),
+    }
+}
+
+/// How often a read-modify-write is retried when another writer keeps
+/// changing the file underneath it.
+const UPDATE_ATTEMPTS: usize = 5;
+
+/// Run `attempt` until it does not fail with [`WriteConflict`], at most
+/// [`UPDATE_ATTEMPTS`] times. The last conflict is returned if all fail.
+fn retry_conflicts<T>(mut attempt: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut last = None;
+    for _ in 0..UPDATE_ATTEMPTS {
+        match attempt() {
+            Err(e) if e.is::<WriteConflict>() => last = Some(e),
+            other => return other,
+        }
+    }
+
+    Err(last.expect("at least one attempt"))
+}
+
+/// Read-modify-write of a JSON file, re-read and retried when the file
+/// changed between the read and the write. `f` may run more than once
+/// and may refuse the change; nothing is written then.
+pub fn update_json<F>(path: &Path, mut f: F) -> Result<Value>
+where
+    F: FnMut(&mut Value) -> Result<()>,
+{
+    retry_conflicts(|| {
+        let raw = fs::read(path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        let prev = sha256_hex(&raw);
+
+        let mut v: Value = serde_json::from_slice(&raw)
+            .with_context(|| format!("parsing {}", path.display()))?;
+
+        f(&mut v)?;
+
+        write_atomic(path, serde_json::to_string_pretty(&v)?.as_bytes(), Expect::Sha256(&prev))?;
+        Ok(v)
+    })
+}
+
+/// A JSON file parsed as `T`, plus the hash it was read at. A missing
+/// file reads as `T::default()` with no hash.
+pub fn load_versioned<T: DeserializeOwned + Default>(path: &Path) -> Result<(T, Option<String>)> {
+    let Some((raw, sha)) = read_versioned(path)? else {
+        return Ok((T::default(), None));
+    };
+
+    let v = serde_json::from_slice(&raw).with_context(|| format!("parsing {}", path.display()))?;
+    Ok((v, Some(sha)))
+}
+
+/// Read-modify-write of a JSON file holding a `T`, retried like
+/// [`update_json`]. A missing file starts from `T::default()`.
+pub fn update_versioned<T, F, R>(path: &Path, mut f: F) -> Result<R>
+where
+    T: Serialize + DeserializeOwned + Default,
+    F: FnMut(&mut T) -> Result<R>,
+{
+    retry_conflicts(|| {
+        let (mut v, prev) = load_versioned::<T>(path)?;
+        let out = f(&mut v)?;
+
+        write_atomic(path, serde_json::to_string_pretty(&v)?.as_bytes(), Expect::from_prev(prev.as_deref()))?;
+        Ok(out)
+    })
+}
+
+/// Append one line to a JSONL log under the same lock discipline.
+pub fn append_line(path: &Path, line: &str) -> Result<()> {
+    append_bytes(path, format!("{line}\n").as_bytes())
+}
+
+/// Append raw bytes under the file's lock, creating the file if needed.
+pub fn append_bytes(path: &Path, bytes: &[u8]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let _guard = lock(path)?;
+
+    let mut f = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("opening {}", path.display()))?;
+
+    f.write_all(bytes)?;
+    f.sync_data()?;
+    Ok(())
+}
+
+/// Rename a log to `to` under its lock and leave an empty file in its
+/// place. Writers that take the lock, or open the log for each append,
+/// carry on in the new file; nothing they write is lost. Returns false,
+/// moving nothing, if the log is missing or empty.
+pub fn move_aside(path: &Path, to: &Path) -> Result<bool> {
+    // Nothing to move, and perhaps no directory to hold the lock.
+    if !path.exists() {
+        return Ok(false);
+    }
+    let _guard = lock(path)?;
+
+    match fs::metadata(path) {
+        Ok(m) if m.len() > 0 => {}
+        Ok(_) => return Ok(false),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    }
+    if to.exists() {
+        anyhow::bail!("{} already exists", to.display());
+    }
+
+    fs::rename(path, to)
+        .with_context(|| format!("moving {} to {}", path.display(), to.display()))?;
+    File::create(path).with_context(|| format!("recreating {}", path.display()))?;
+
+    if let Some(dir) = path.parent() {
+        sync_dir(dir);
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_conflict(r: Result<()>) -> bool {
+        r.unwrap_err().downcast_ref::<WriteConflict>().is_some()
+    }
+
+    #[test]
+    fn absent_only_creates() {
+        let dir = tempfile::tempdir().unwrap();
+        let p = dir.path().join("sub/state.json");
+
+        write_atomic(&p, b"one", Expect::Absent).unwrap();
+        assert_eq!(fs::read(&p).unwrap(), b"one");
+        assert!(is_conflict(write_atomic(&p, b"two", Expect::Absent)));
+        assert_eq!(fs::read(&p).unwrap(), b"one");
+    }
+
+    #[test]
+    fn sha_expectation_detects_concurrent_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let p = dir.path().join("state.json");
+        write_atomic(&p, b"v1", Expect::Any).unwrap();
+
+        let (_, sha) = read_versioned(&p).unwrap().unwrap();
+
+        // another writer gets in first
+        fs::write(&p, b"v1 by worker").unwrap();
+        assert!(is_conflict(write_atomic(&p, b"v2", Expect::Sha256(&sha))));
+
+        let (_, sha) = read_versioned(&p).unwrap().unwrap();
+        write_atomic(&p, b"v2", Expect::from_prev(Some(&sha))).unwrap();
+        assert_eq!(fs::read(&p).unwrap(), b"v2");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn private_files_are_never_readable_by_others() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let p = dir.path().join("keys/op.key");
+
+        // A world-readable temp file left by an earlier crash is tightened.
+        fs::create_dir_all(p.parent().unwrap()).unwrap();
+        let stale = dir.path().join(format!("keys/.op.key.tmp-{}", std::process::id()));
+        fs::write(&stale, b"old").unwrap();
+        fs::set_permissions(&stale, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_private(&p, b"secret", Expect::Absent).unwrap();
+        assert_eq!(fs::read(&p).unwrap(), b"secret");
+        assert_eq!(fs::metadata(&p).unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(!stale.exists());
+    }
+
+    #[test]
+    fn no_temp_files_left_behind() {
+        let dir = tempfile::tempdir().unwrap();
+        let p = dir.path().join("state.json");
+        write_atomic(&p, b"x", Expect::Any).unwrap();
+
+        let names: Vec<String> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert!(names.iter().all(|n| !n.contains(".tmp-")), "{names:?}");
+    }
+
+    #[test]
+    fn update_json_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let p = dir.path().join("manifest.json");
+        fs::write(&p, r#"{"a":1}"#).unwrap();
+
+        let v = update_json(&p, |v| {
+            v["b"] = Value::from(2);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(v, serde_json::json!({ "a": 1, "b": 2 }));
+        let on_disk: Value = serde_json::from_slice(&fs::read(&p).unwrap()).unwrap();
+        assert_eq!(on_disk, v);
+
+        // an error from the closure leaves the file alone
+        assert!(update_json(&p, |_| anyhow::bail!("no")).is_err());
+        assert_eq!(serde_json::from_slice::<Value>(&fs::read(&p).unwrap()).unwrap(), v);
+    }
+
+    #[test]
+    fn updates_retry_after_a_concurrent_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let p = dir.path().join("manifest.json");
+        fs::write(&p, r#"{"a":1}"#).unwrap();
+
+        // Another writer changes the file while the first attempt runs.
+        let mut calls = 0;
+        let v = update_json(&p, |v| {
+            calls += 1;
+            if calls == 1 {
+                fs::write(&p, r#"{"a":1,"c":3}"#).unwrap();
+            }
+            v["b"] = Value::from(2);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(calls, 2);
+        assert_eq!(v, serde_json::json!({ "a": 1, "b": 2, "c": 3 }));
+
+        // A writer that never lets up wins; the conflict is reported.
+        let n = dir.path().join("counter.json");
+        let err = update_versioned(&n, |count: &mut u64| {
+            *count += 1;
+            fs::write(&n, (*count * 10).to_string()).unwrap();
+            Ok(())
+        })
+        .unwrap_err();
+        assert!(err.is::<WriteConflict>());
+
+        fs::remove_file(&n).unwrap();
+        let out = update_versioned(&n, |count: &mut u64| {
+            *count += 1;
+            Ok(*count)
+        })
+        .unwrap();
+        assert_eq!(out, 1);
+        assert_eq!(load_versioned::<u64>(&n).unwrap().0, 1);
+    }
+
+    #[test]
+    fn move_aside_leaves_an_empty_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let p = dir.path().join("log.jsonl");
+        let to = dir.path().join("log.jsonl.rotating");
+
+        assert!(!move_aside(&p, &to).unwrap());
+        fs::write(&p, "").unwrap();
+        assert!(!move_aside(&p, &to).unwrap());
+
+        fs::write(&p, "a\n").unwrap();
+        assert!(move_aside(&p, &to).unwrap());
+        assert_eq!(fs::read_to_string(&to).unwrap(), "a\n");
+        assert_eq!(fs::read_to_string(&p).unwrap(), "");
+
+        append_line(&p, "b").unwrap();
+        assert!(move_aside(&p, &to).is_err());
+        assert_eq!(fs::read_to_string(&p).unwrap(), "b\n");
+    }
+
+    #[test]
+    fn concurrent_appends_keep_every_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let p = dir.path().join("log.jsonl");
+
+        let handles: Vec<_> = (0..8)
+            .map(|t| {
+                let p = p.clone();
+                std::thread::spawn(move || {
+                    for i in 0..25 {
+                        append_line(&p, &format!("{t}-{i}")).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&p).unwrap().lines().count(), 200);
+    }
+}
@@ -0,0 +1,59 @@
+use serde::{Serialize, Deserialize};
+use std::path::Path;
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::atomic_fs;
+use crate::operator::Operator;
+use crate::retention;
+
+/// One console-side action, appended to `logs/console_audit.jsonl`.
+/// Guardian decisions stay in the worker's own log.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditEntry {
+    pub timestamp: String,
+    pub action: String,
+    #[serde(default)]
+    pub tenant: Option<String>,
+    pub actor: String,
+    #[serde(default)]
+    pub actor_fingerprint: Option<String>,
+    #[serde(default)]
+    pub detail: serde_json::Value,
+
+    /// Operator signature over this entry serialized with `signature`
+    /// set to null. Every entry is written through [`record_signed`];
+    /// only entries from before signing lack one.
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+pub fn audit_log_path(worker_root: &Path) -> std::path::PathBuf {
+    retention::active_path(worker_root, retention::CONSOLE_AUDIT)
+}
+
+fn append(worker_root: &Path, entry: &AuditEntry) -> Result<()> {
+    atomic_fs::append_line(&audit_log_path(worker_root), &serde_json::to_string(entry)?)
+}
+
+/// Entry attributed to, and signed by, the active operator.
+pub fn record_signed(
+    worker_root: &Path,
+    op: &Operator,
+    action: &str,
+    tenant: Option<&str>,
+    detail: serde_json::Value,
+) -> Result<()> {
+    let mut entry = AuditEntry {
+        timestamp: Utc::now().to_rfc3339(),
+        action: action.into(),
+        tenant: tenant.map(|t| t.to_string()),
+        actor: op.name.clone(),
+        actor_fingerprint: Some(op.fingerprint.clone()),
+        detail,
+        signature: None,
+    };
+
+    entry.signature = Some(op.sign(serde_json::to_string(&entry)?.as_bytes()));
+    append(worker_root, &entry)
+}
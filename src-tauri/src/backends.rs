@@ -0,0 +1,293 @@
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use serde_json::Value;
+
+use crate::diagnostics::{self, DiagnosticCheck};
+
+/// Key in a Guardian policy holding per-backend settings:
+/// `"backend_options": { "<backend>": { "<knob>": value } }`.
+pub const POLICY_OPTIONS_KEY: &str = "backend_options";
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KnobKind {
+    Bool,
+    Integer,
+}
+
+/// A policy setting a backend understands.
+#[derive(Debug, Serialize, Clone, Copy)]
+pub struct PolicyKnob {
+    pub name: &'static str,
+    pub kind: KnobKind,
+    pub description: &'static str,
+}
+
+/// Executable a backend's processes run as (`<image>.exe` on Windows).
+/// Only shown on the Backends page; nothing matches processes by it.
+#[derive(Debug, Serialize, Clone, Copy)]
+pub struct ProcessSignature {
+    pub image: &'static str,
+}
+
+pub struct HealthContext<'a> {
+    pub worker_root: &'a Path,
+    pub worker_bin: Result<PathBuf, String>,
+}
+
+/// An execution backend the worker can run tenants on. Adding one means
+/// implementing this and listing it in [`registry`].
+pub trait Backend: Sync {
+    /// Value used in `--backend`, decisions and `allowed_backends`.
+    fn name(&self) -> &'static str;
+    fn label(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+
+    /// Cheap presence check for scans.
+    fn detect(&self, worker_root: &Path) -> bool;
+
+    /// Full diagnostics, each check pass, fail or skip.
+    fn health_check(&self, ctx: HealthContext) -> Vec<DiagnosticCheck>;
+
+    /// Shown to the operator; not used to find or signal processes.
+    fn process_signature(&self) -> ProcessSignature;
+
+    /// `backend_options` the worker applies for this backend. None yet:
+    /// the worker does not read `backend_options`, so every option is
+    /// reported as unknown rather than stored and ignored.
+    fn policy_knobs(&self) -> &'static [PolicyKnob] {
+        &[]
+    }
+}
+
+struct Wasmtime;
+
+impl Backend for Wasmtime {
+    fn name(&self) -> &'static str {
+        "wasmtime"
+    }
+
+    fn label(&self) -> &'static str {
+        "Wasmtime"
+    }
+
+    fn description(&self) -> &'static str {
+        "Local WASM runtime"
+    }
+
+    fn detect(&self, _worker_root: &Path) -> bool {
+        which::which("wasmtime").is_ok()
+    }
+
+    fn health_check(&self, ctx: HealthContext) -> Vec<DiagnosticCheck> {
+        diagnostics::wasmtime_checks(ctx.worker_root, ctx.worker_bin)
+    }
+
+    fn process_signature(&self) -> ProcessSignature {
+        ProcessSignature { image: "wasmtime" }
+    }
+}
+
+struct Firecracker;
+
+impl Backend for Firecracker {
+    fn name(&self) -> &'static str {
+        "firecracker"
+    }
+
+    fn label(&self) -> &'static str {
+        "Firecracker"
+    }
+
+    fn description(&self) -> &'static str {
+        "MicroVM sandbox"
+    }
+
+    fn detect(&self, worker_root: &Path) -> bool {
+        cfg!(target_os = "linux")
+            && diagnostics::load_firecracker_config(worker_root)
+                .ok()
+                .and_then(|c| c.binary)
+                .map(|b| b.is_file())
+                .unwrap_or_else(|| which::which("firecracker").is_ok())
+    }
+
+    fn health_check(&self, ctx: HealthContext) -> Vec<DiagnosticCheck> {
+        diagnostics::firecracker_checks(ctx.worker_root)
+    }
+
+    fn process_signature(&self) -> ProcessSignature {
+        ProcessSignature { image: "firecracker" }
+    }
+}
+
+static REGISTRY: [&dyn Backend; 2] = [&Wasmtime, &Firecracker];
+
+pub fn registry() -> &'static [&'static dyn Backend] {
+    &REGISTRY
+}
+
+pub fn get(name: &str) -> Option<&'static dyn Backend> {
+    registry().iter().copied().find(|b| b.name() == name)
+}
+
+pub fn names() -> Vec<&'static str> {
+    registry().iter().map(|b| b.name()).collect()
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BackendInfo {
+    pub name: String,
+    pub label: String,
+    pub description: String,
+    pub installed: bool,
+    pub process: ProcessSignature,
+    pub knobs: Vec<PolicyKnob>,
+}
+
+pub fn describe_all(worker_root: &Path) -> Vec<BackendInfo> {
+    registry()
+        .iter()
+        .map(|b| BackendInfo {
+            name: b.name().into(),
+            label: b.label().into(),
+            description: b.description().into(),
+            installed: b.detect(worker_root),
+            process: b.process_signature(),
+            knobs: b.policy_knobs().to_vec(),
+        })
+        .collect()
+}
+
+/// Problems in one Guardian policy: backends nobody registered, and
+/// options a backend does not understand or of the wrong type.
+pub fn check_policy(policy: &Value) -> Vec<String> {
+    let mut problems = vec![];
+
+    if let Some(list) = policy.get("allowed_backends").and_then(|v| v.as_array()) {
+        for name in list {
+            match name.as_str() {
+                Some(n) if get(n).is_some() => {}
+                Some(n) => problems.push(format!("allowed_backends: unknown backend '{n}'")),
+                None => problems.push("allowed_backends: entries must be strings".into()),
+            }
+        }
+    }
+
+    let Some(options) = policy.get(POLICY_OPTIONS_KEY).and_then(|v| v.as_object()) else {
+        return problems;
+    };
+
+    for (backend, knobs) in options {
+        let Some(b) = get(backend) else {
+            problems.push(format!("{POLICY_OPTIONS_KEY}: unknown backend '{backend}'"));
+            continue;
+        };
+        let Some(knobs) = knobs.as_object() else {
+            problems.push(format!("{POLICY_OPTIONS_KEY}.{backend}: must be an object"));
+            continue;
+        };
+
+        for (k, v) in knobs {
+            let Some(knob) = b.policy_knobs().iter().find(|x| x.name == k) else {
+                problems.push(format!("{POLICY_OPTIONS_KEY}.{backend}: '{k}' is not a {} option", b.name()));
+                continue;
+            };
+            let (fits, expected) = match knob.kind {
+                KnobKind::Bool => (v.is_boolean(), "a boolean"),
+                KnobKind::Integer => (v.is_u64(), "a non-negative integer"),
+            };
+            if !fits {
+                problems.push(format!("{POLICY_OPTIONS_KEY}.{backend}.{k}: expected {expected}"));
+            }
+        }
+    }
+
+    problems
+}
+
+/// Every policy file under `guardian/policies/` checked against the
+/// registry, as `<file>: <problem>`.
+pub fn policy_warnings(worker_root: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(worker_root.join("guardian").join("policies")) else {
+        return vec![];
+    };
+
+    let mut out = vec![];
+    for e in entries.flatten() {
+        let file = e.file_name().to_string_lossy().to_string();
+        if !file.ends_with(".json") {
+            continue;
+        }
+
+        match fs::read_to_string(e.path()).ok().and_then(|s| serde_json::from_str::<Value>(&s).ok()) {
+            Some(p) => out.extend(check_policy(&p).into_iter().map(|w| format!("{file}: {w}"))),
+            None => out.push(format!("{file}: unreadable or not JSON")),
+        }
+    }
+
+    out.sort();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn registry_names_are_unique() {
+        let mut names = names();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), registry().len());
+        assert!(get("wasmtime").is_some());
+        assert!(get("qemu").is_none());
+    }
+
+    #[test]
+    fn policy_checked_against_registry() {
+        let policy = json!({
+            "allowed_backends": ["wasmtime", "qemu", 3],
+            "backend_options": {
+                "firecracker": { "vcpu_count": 2, "gpu": true },
+                "qemu": {},
+                "wasmtime": 1,
+            },
+        });
+
+        let mut problems = check_policy(&policy);
+        problems.sort();
+        assert_eq!(
+            problems,
+            vec![
+                "allowed_backends: entries must be strings",
+                "allowed_backends: unknown backend 'qemu'",
+                "backend_options.firecracker: 'gpu' is not a firecracker option",
+                "backend_options.firecracker: 'vcpu_count' is not a firecracker option",
+                "backend_options.wasmtime: must be an object",
+                "backend_options: unknown backend 'qemu'",
+            ]
+        );
+
+        assert!(check_policy(&json!({ "allowed_backends": ["wasmtime", "firecracker"] })).is_empty());
+    }
+
+    #[test]
+    fn policy_warnings_name_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let policies = dir.path().join("guardian/policies");
+        fs::create_dir_all(&policies).unwrap();
+        fs::write(policies.join("a.json"), r#"{"allowed_backends":["qemu"]}"#).unwrap();
+        fs::write(policies.join("b.json"), "not json").unwrap();
+        fs::write(policies.join("notes.txt"), "ignored").unwrap();
+
+        assert_eq!(
+            policy_warnings(dir.path()),
+            vec!["a.json: allowed_backends: unknown backend 'qemu'", "b.json: unreadable or not JSON"]
+        );
+    }
+}
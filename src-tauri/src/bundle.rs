@@ -0,0 +1,399 @@
+use serde::{Serialize, Deserialize};
+use std::{fs, io::{Cursor, Write}, path::{Path, PathBuf}};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::Utc;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use zip::{write::FileOptions, ZipWriter};
+
+use crate::atomic_fs::{write_atomic, Expect};
+use crate::decisions;
+use crate::operator::{self, Operator};
+use crate::submission::sha256_hex;
+
+pub const BUNDLE_FORMAT: &str = "nightcore-tenant-bundle/v1";
+pub const INDEX_FILE: &str = "bundle_index.json";
+pub const INDEX_SIG_FILE: &str = "bundle_index.sig";
+
+/// Where bundle-only files land inside an imported tenant.
+pub const IMPORTED_DIR: &str = "bundle";
+
+const TENANT_FILES: [&str; 5] = [
+    "module.wasm",
+    "module.sig",
+    "module.sha256",
+    "pubkey.b64",
+    "manifest.json",
+];
+const DECISIONS_FILE: &str = "guardian_decisions.jsonl";
+const APPROVALS_FILE: &str = "approvals.json";
+
+/// Manifest keys holding trust, approval, provenance, revision and
+/// suspension state of the machine that wrote them. They are exported
+/// for the record but never re-applied on import.
+const LOCAL_STATE_KEYS: [&str; 20] = [
+    "approved",
+    "approved_at",
+    "approved_by",
+    "approved_by_fingerprint",
+    "approval_signature",
+    "approved_sha256",
+    "approvals",
+    "renewals",
+    "expires_at",
+    "max_executions",
+    "rejected",
+    "rejected_at",
+    "rejected_by",
+    "rejected_by_fingerprint",
+    "rejection_signature",
+    "provenance",
+    "active_revision",
+    "revision_history",
+    "suspended",
+    "bundle",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundleFile {
+    pub name: String,
+    pub sha256: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundleIndex {
+    pub format: String,
+    pub tenant: String,
+    pub exported_at: String,
+    pub exported_by: String,
+    pub signer_fingerprint: String,
+    pub signer_public_key: String,
+    pub files: Vec<BundleFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundleVerification {
+    pub tenant: String,
+    pub exported_by: String,
+    pub exported_at: String,
+    pub signer_fingerprint: String,
+    /// Signer is a local operator profile or a registered maintainer.
+    /// Reported only: an unknown signer does not stop the import, and the
+    /// imported tenant still needs approval before it runs.
+    pub trusted_signer: bool,
+}
+
+fn tenant_files(worker_root: &Path, tenant: &str) -> Result<Vec<(String, Vec<u8>)>> {
+    let dir = worker_root.join("modules").join(tenant);
+    let mut out = vec![];
+
+    for name in TENANT_FILES {
+        match fs::read(dir.join(name)) {
+            Ok(b) => out.push((name.to_string(), b)),
+            Err(_) if name == "module.wasm" || name == "manifest.json" => {
+                bail!("tenant {tenant} has no {name}")
+            }
+            Err(_) => {}
+        }
+    }
+
+    let decisions: String = decisions::load_all(worker_root)
+        .into_iter()
+        .filter(|d| d.tenant == tenant)
+        .filter_map(|d| serde_json::to_string(&d).ok())
+        .map(|l| l + "\n")
+        .collect();
+    out.push((DECISIONS_FILE.into(), decisions.into_bytes()));
+
+    let slot = out
+        .iter_mut()
+        .find(|(n, _)| n == "manifest.json")
+        .expect("manifest.json is required above");
+    let mut manifest: serde_json::Value = serde_json::from_slice(&slot.1).context("parsing manifest.json")?;
+
+    // Local state travels only as a record in approvals.json.
+    let approvals: serde_json::Map<String, serde_json::Value> = LOCAL_STATE_KEYS
+        .iter()
+        .filter_map(|k| manifest.get(*k).map(|v| (k.to_string(), v.clone())))
+        .collect();
+    strip_local_state(&mut manifest);
+    slot.1 = serde_json::to_vec_pretty(&manifest)?;
+
+    out.push((APPROVALS_FILE.into(), serde_json::to_vec_pretty(&approvals)?));
+
+    Ok(out)
+}
+
+/// Zip of the tenant's files, its decisions and approval records, plus an
+/// index of file hashes signed by the active operator.
+pub fn export_tenant(worker_root: &Path, tenant: &str, op: &Operator, dest: &Path) -> Result<PathBuf> {
+    let files = tenant_files(worker_root, tenant)?;
+
+    let index = BundleIndex {
+        format: BUNDLE_FORMAT.into(),
+        tenant: tenant.into(),
+        exported_at: Utc::now().to_rfc3339(),
+        exported_by: op.name.clone(),
+        signer_fingerprint: op.fingerprint.clone(),
+        signer_public_key: op.public_key_b64(),
+        files: file_entries(&files),
+    };
+
+    let index_bytes = serde_json::to_vec_pretty(&index)?;
+    write_signed_zip(dest, &files, (INDEX_FILE, INDEX_SIG_FILE), &index_bytes, op)?;
+
+    Ok(dest.to_path_buf())
+}
+
+pub fn file_entries(files: &[(String, Vec<u8>)]) -> Vec<BundleFile> {
+    files
+        .iter()
+        .map(|(name, b)| BundleFile {
+            name: name.clone(),
+            sha256: sha256_hex(b),
+            size_bytes: b.len() as u64,
+        })
+        .collect()
+}
+
+/// Zip `files` plus an index and the operator's detached signature over
+/// it, written to `dest` only if nothing is there yet.
+pub fn write_signed_zip(
+    dest: &Path,
+    files: &[(String, Vec<u8>)],
+    (index_name, sig_name): (&str, &str),
+    index_bytes: &[u8],
+    op: &Operator,
+) -> Result<()> {
+    let index_sig = op.sign(index_bytes);
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let opts = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    for (name, bytes) in files
+        .iter()
+        .map(|(n, b)| (n.as_str(), b.as_slice()))
+        .chain([(index_name, index_bytes), (sig_name, index_sig.as_bytes())])
+    {
+        zip.start_file(name, opts)?;
+        zip.write_all(bytes)?;
+    }
+
+    let buf = zip.finish()?.into_inner();
+    write_atomic(dest, &buf, Expect::Absent)
+}
+
+fn known_signer(worker_root: &Path, public_key_b64: &str) -> bool {
+    let pk = public_key_b64.trim();
+
+    let operator_match = operator::load_registry(worker_root)
+        .map(|r| r.profiles.iter().any(|p| p.public_key == pk))
+        .unwrap_or(false);
+
+    let maintainer_match = fs::read_dir(worker_root.join("keys").join("maintainers"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| fs::read_to_string(e.path()).ok())
+                .any(|k| k.trim() == pk)
+        })
+        .unwrap_or(false);
+
+    operator_match || maintainer_match
+}
+
+/// A listed file must be one plain name inside the bundle directory,
+/// under the same rules as tenant names.
+fn check_file_name(name: &str) -> Result<()> {
+    let ok = !name.is_empty()
+        && name.len() <= 128
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.');
+
+    if !ok {
+        bail!("bundle lists an invalid file name '{name}'");
+    }
+    Ok(())
+}
+
+/// Verify an extracted bundle in `dir`: index signature, every listed
+/// hash, and no unlisted files. Bundle-only files are then moved under
+/// `bundle/` so the directory looks like a plain tenant.
+pub fn verify_extracted(worker_root: &Path, dir: &Path) -> Result<BundleVerification> {
+    let index_bytes = fs::read(dir.join(INDEX_FILE)).context("reading bundle index")?;
+    let sig_b64 = fs::read_to_string(dir.join(INDEX_SIG_FILE)).context("bundle index is not signed")?;
+
+    let index: BundleIndex = serde_json::from_slice(&index_bytes).context("parsing bundle index")?;
+    if index.format != BUNDLE_FORMAT {
+        bail!("unsupported bundle format '{}'", index.format);
+    }
+
+    let pk: [u8; 32] = STANDARD
+        .decode(index.signer_public_key.trim())
+        .context("decoding signer key")?
+        .try_into()
+        .map_err(|_| anyhow!("signer key must be 32 bytes"))?;
+    let sig: [u8; 64] = STANDARD
+        .decode(sig_b64.trim())
+        .context("decoding index signature")?
+        .try_into()
+        .map_err(|_| anyhow!("index signature must be 64 bytes"))?;
+
+    VerifyingKey::from_bytes(&pk)
+        .map_err(|e| anyhow!("invalid signer key: {e}"))?
+        .verify(&index_bytes, &Signature::from_bytes(&sig))
+        .map_err(|_| anyhow!("bundle index signature does not verify"))?;
+
+    for f in &index.files {
+        check_file_name(&f.name)?;
+        let bytes = fs::read(dir.join(&f.name))
+            .with_context(|| format!("bundle is missing {}", f.name))?;
+        if sha256_hex(&bytes) != f.sha256 {
+            bail!("{} does not match its hash in the bundle index", f.name);
+        }
+    }
+
+    for entry in fs::read_dir(dir)?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let listed = index.files.iter().any(|f| f.name == name);
+        if !listed && name != INDEX_FILE && name != INDEX_SIG_FILE {
+            bail!("bundle contains unlisted file {name}");
+        }
+    }
+
+    let imported = dir.join(IMPORTED_DIR);
+    fs::create_dir_all(&imported)?;
+    for name in [DECISIONS_FILE, APPROVALS_FILE, INDEX_FILE, INDEX_SIG_FILE] {
+        if dir.join(name).exists() {
+            fs::rename(dir.join(name), imported.join(name))?;
+        }
+    }
+
+    Ok(BundleVerification {
+        trusted_signer: known_signer(worker_root, &index.signer_public_key),
+        tenant: index.tenant,
+        exported_by: index.exported_by,
+        exported_at: index.exported_at,
+        signer_fingerprint: index.signer_fingerprint,
+    })
+}
+
+/// Drop state granted on the exporting machine from an imported manifest.
+pub fn strip_local_state(manifest: &mut serde_json::Value) {
+    if let Some(m) = manifest.as_object_mut() {
+        for k in LOCAL_STATE_KEYS {
+            m.remove(k);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zip::ZipArchive;
+
+    fn setup() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let t = dir.path().join("modules/t1");
+        fs::create_dir_all(&t).unwrap();
+        fs::write(t.join("module.wasm"), b"\0asm\x01\0\0\0").unwrap();
+        fs::write(
+            t.join("manifest.json"),
+            serde_json::json!({
+                "tenant": "t1",
+                "display_name": "Demo",
+                "approved": true,
+                "rejected_by": "bob",
+                "provenance": { "signature_verified": true },
+                "active_revision": "a".repeat(64),
+                "revision_history": [],
+                "suspended": { "suspended_by": "bob" },
+            })
+            .to_string(),
+        )
+        .unwrap();
+        dir
+    }
+
+    fn export_and_extract(root: &Path) -> PathBuf {
+        let op = Operator::ephemeral("alice");
+        let zip = export_tenant(root, "t1", &op, &root.join("exports/t1.zip")).unwrap();
+
+        let out = root.join("staging");
+        ZipArchive::new(fs::File::open(zip).unwrap()).unwrap().extract(&out).unwrap();
+        out
+    }
+
+    fn json(p: &Path) -> serde_json::Value {
+        serde_json::from_slice(&fs::read(p).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn exported_manifest_carries_no_local_state() {
+        let dir = setup();
+        let out = export_and_extract(dir.path());
+
+        let m = json(&out.join("manifest.json"));
+        assert_eq!(m["display_name"], "Demo");
+        for k in LOCAL_STATE_KEYS {
+            assert!(m.get(k).is_none(), "{k} exported in manifest");
+        }
+
+        let record = json(&out.join(APPROVALS_FILE));
+        assert_eq!(record["approved"], true);
+        assert_eq!(record["suspended"]["suspended_by"], "bob");
+    }
+
+    #[test]
+    fn verifies_untampered_bundle() {
+        let dir = setup();
+        let out = export_and_extract(dir.path());
+
+        let v = verify_extracted(dir.path(), &out).unwrap();
+        assert_eq!(v.tenant, "t1");
+        assert_eq!(v.exported_by, "alice");
+        assert!(!v.trusted_signer);
+        assert!(out.join(IMPORTED_DIR).join(INDEX_FILE).exists());
+    }
+
+    #[test]
+    fn rejects_modified_or_extra_files() {
+        let dir = setup();
+
+        let out = export_and_extract(dir.path());
+        fs::write(out.join("module.wasm"), b"\0asm\x01\0\0\0\0\x01\0").unwrap();
+        assert!(verify_extracted(dir.path(), &out).is_err());
+
+        fs::remove_dir_all(&out).unwrap();
+        fs::remove_file(dir.path().join("exports/t1.zip")).unwrap();
+        let out = export_and_extract(dir.path());
+        fs::write(out.join("module.sig"), b"smuggled").unwrap();
+        assert!(verify_extracted(dir.path(), &out).is_err());
+    }
+
+    #[test]
+    fn rejects_file_names_outside_the_bundle() {
+        let dir = setup();
+        let out = export_and_extract(dir.path());
+        fs::write(dir.path().join("outside"), b"x").unwrap();
+
+        let op = Operator::ephemeral("mallory");
+        let mut index: BundleIndex = serde_json::from_slice(&fs::read(out.join(INDEX_FILE)).unwrap()).unwrap();
+        index.signer_public_key = op.public_key_b64();
+        index.files.push(BundleFile { name: "../outside".into(), sha256: sha256_hex(b"x"), size_bytes: 1 });
+        let bytes = serde_json::to_vec_pretty(&index).unwrap();
+        fs::write(out.join(INDEX_FILE), &bytes).unwrap();
+        fs::write(out.join(INDEX_SIG_FILE), op.sign(&bytes)).unwrap();
+
+        let err = verify_extracted(dir.path(), &out).unwrap_err();
+        assert!(err.to_string().contains("invalid file name"), "{err}");
+    }
+
+    #[test]
+    fn strips_local_state() {
+        let mut m = serde_json::json!({ "tenant": "t1", "approved": true, "bundle": {} });
+        strip_local_state(&mut m);
+        assert_eq!(m, serde_json::json!({ "tenant": "t1" }));
+    }
+}
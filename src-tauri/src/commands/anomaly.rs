@@ -0,0 +1,19 @@
+use tauri::AppHandle;
+
+use crate::anomaly::{self, AnomalyReport};
+use crate::resolve_worker_root;
+
+/// Recompute baselines and drift from the decision history and rewrite
+/// `logs/anomaly_drift.json`.
+#[tauri::command]
+pub fn run_anomaly_engine(app: AppHandle) -> Result<AnomalyReport, String> {
+    let root = resolve_worker_root(&app)?;
+    anomaly::run(&root).map_err(|e| e.to_string())
+}
+
+/// The report on disk, validated against the current version.
+#[tauri::command]
+pub fn get_anomaly_report(app: AppHandle) -> Result<AnomalyReport, String> {
+    let root = resolve_worker_root(&app)?;
+    anomaly::load(&root).map_err(|e| e.to_string())
+}
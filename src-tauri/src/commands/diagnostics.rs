@@ -0,0 +1,44 @@
+use tauri::AppHandle;
+
+use crate::backends::{self, BackendInfo, HealthContext};
+use crate::diagnostics::{self, BackendDiagnostics};
+use crate::{ensure_worker_runtime_dirs, resolve_bundled_worker_bin};
+
+/// Every registered backend with its detection result and policy knobs.
+#[tauri::command]
+pub async fn list_backends(app: AppHandle) -> Result<Vec<BackendInfo>, String> {
+    let root = ensure_worker_runtime_dirs(&app)?;
+    Ok(backends::describe_all(&root))
+}
+
+/// Run a registered backend's health check.
+#[tauri::command]
+pub async fn test_backend(app: AppHandle, name: String) -> Result<BackendDiagnostics, String> {
+    let backend = backends::get(&name).ok_or_else(|| {
+        format!("Unknown backend '{name}' (registered: {})", backends::names().join(", "))
+    })?;
+
+    let root = ensure_worker_runtime_dirs(&app)?;
+    let worker_bin = resolve_bundled_worker_bin(&app);
+
+    // The probe waits on the worker; keep it off the async runtime.
+    tauri::async_runtime::spawn_blocking(move || {
+        let checks = backend.health_check(HealthContext { worker_root: &root, worker_bin });
+        diagnostics::summarize(backend.name(), checks)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Wasmtime on PATH, worker present, and a known-good probe module run
+/// through the worker in a scratch runtime.
+#[tauri::command]
+pub async fn test_wasmtime_backend(app: AppHandle) -> Result<BackendDiagnostics, String> {
+    test_backend(app, "wasmtime".into()).await
+}
+
+/// Firecracker binary, KVM access, kernel and rootfs, and the jailer.
+#[tauri::command]
+pub async fn test_firecracker_backend(app: AppHandle) -> Result<BackendDiagnostics, String> {
+    test_backend(app, "firecracker".into()).await
+}
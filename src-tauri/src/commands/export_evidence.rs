@@ -0,0 +1,86 @@
+use tauri::AppHandle;
+
+use chrono::Utc;
+
+use crate::audit;
+use crate::evidence::{self, EvidenceScope};
+use crate::operator;
+use crate::resolve_worker_root;
+use crate::runtime_path;
+
+/// Collect forensic evidence for a window and tenant set into a signed
+/// zip. Without `dest` it lands in `exports/evidence-<timestamp>.zip`.
+#[tauri::command]
+pub fn export_evidence_bundle(
+    app: AppHandle,
+    from: Option<String>,
+    to: Option<String>,
+    tenants: Option<Vec<String>>,
+    dest: Option<String>,
+) -> Result<String, String> {
+    let root = resolve_worker_root(&app)?;
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+
+    let scope = EvidenceScope {
+        from,
+        to,
+        tenants: tenants.unwrap_or_default(),
+    };
+
+    let dest = match dest {
+        Some(d) => runtime_path::external_output(&root, &d).map_err(|e| e.to_string())?,
+        None => root
+            .join("exports")
+            .join(format!("evidence-{}.zip", Utc::now().format("%Y%m%dT%H%M%SZ"))),
+    };
+
+    evidence::export(&root, &scope, &op, &dest)
+        .map_err(|e| format!("Evidence export failed: {e}"))?;
+
+    audit::record_signed(
+        &root,
+        &op,
+        "evidence_exported",
+        None,
+        serde_json::json!({
+            "path": dest.to_string_lossy(),
+            "scope": scope,
+        }),
+    )
+    .map_err(|e| format!("Evidence written but audit write failed: {e}"))?;
+
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Zip the worker reports at `paths` (`worker://` paths from the Proof
+/// Logs page) with a signed manifest. Without `dest` it lands in
+/// `exports/reports-<timestamp>.zip`.
+#[tauri::command]
+pub fn export_zip(app: AppHandle, paths: Vec<String>, dest: Option<String>) -> Result<String, String> {
+    let root = resolve_worker_root(&app)?;
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+
+    let dest = match dest {
+        Some(d) => runtime_path::external_output(&root, &d).map_err(|e| e.to_string())?,
+        None => root
+            .join("exports")
+            .join(format!("reports-{}.zip", Utc::now().format("%Y%m%dT%H%M%SZ"))),
+    };
+
+    evidence::export_reports(&root, &paths, &op, &dest)
+        .map_err(|e| format!("Export failed: {e}"))?;
+
+    audit::record_signed(
+        &root,
+        &op,
+        "reports_exported",
+        None,
+        serde_json::json!({
+            "path": dest.to_string_lossy(),
+            "files": paths,
+        }),
+    )
+    .map_err(|e| format!("Reports written but audit write failed: {e}"))?;
+
+    Ok(dest.to_string_lossy().to_string())
+}
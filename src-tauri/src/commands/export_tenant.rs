@@ -0,0 +1,39 @@
+use tauri::AppHandle;
+
+use chrono::Utc;
+
+use crate::audit;
+use crate::bundle;
+use crate::operator;
+use crate::resolve_worker_root;
+use crate::runtime_path;
+
+/// Write a signed tenant bundle. Without `dest` it lands in
+/// `exports/<tenant>-<timestamp>.zip` under the runtime root.
+#[tauri::command]
+pub fn export_tenant_bundle(app: AppHandle, tenant: String, dest: Option<String>) -> Result<String, String> {
+    let root = resolve_worker_root(&app)?;
+    runtime_path::check_tenant(&tenant).map_err(|e| e.to_string())?;
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+
+    let dest = match dest {
+        Some(d) => runtime_path::external_output(&root, &d).map_err(|e| e.to_string())?,
+        None => root
+            .join("exports")
+            .join(format!("{tenant}-{}.zip", Utc::now().format("%Y%m%dT%H%M%SZ"))),
+    };
+
+    let written = bundle::export_tenant(&root, &tenant, &op, &dest)
+        .map_err(|e| format!("Export failed: {e}"))?;
+
+    audit::record_signed(
+        &root,
+        &op,
+        "tenant_exported",
+        Some(&tenant),
+        serde_json::json!({ "path": written.to_string_lossy() }),
+    )
+    .map_err(|e| format!("Bundle written but audit write failed: {e}"))?;
+
+    Ok(written.to_string_lossy().to_string())
+}
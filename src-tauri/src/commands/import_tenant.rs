@@ -1,18 +1,48 @@
 use tauri::AppHandle;
 
+use serde::Serialize;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use chrono::Utc;
 use zip::ZipArchive;
 
+use crate::atomic_fs::{self, write_atomic, Expect};
+use crate::bundle::{self, BundleVerification};
 use crate::resolve_worker_root;
+use crate::runtime_path::{self, MAX_IMPORT_BYTES};
+use crate::submission::sha256_hex;
+use crate::tenant_id;
+
+#[derive(Debug, Serialize)]
+pub struct ImportOutcome {
+    /// created | reused | duplicate
+    pub status: String,
+    /// New tenant, reused tenant, or the existing holder of the same hash.
+    pub tenant: String,
+    pub sha256: String,
+    pub display_name: Option<String>,
+    /// Set when the zip was a signed tenant bundle. Its `trusted_signer`
+    /// is informational; the import goes ahead either way.
+    pub bundle: Option<BundleVerification>,
+}
+
+fn staging_dir(worker_root: &Path) -> PathBuf {
+    // Outside modules/ so half-extracted zips never show up in scans.
+    worker_root
+        .join("state")
+        .join("import-staging")
+        .join(format!("{}-{}", std::process::id(), Utc::now().timestamp_nanos_opt().unwrap_or(0)))
+}
 
 #[tauri::command]
-pub fn import_tenant_from_file(app: AppHandle, path: String) -> Result<String, String> {
-    let src = PathBuf::from(&path);
-    if !src.exists() {
-        return Err("File not found".into());
-    }
+pub fn import_tenant_from_file(
+    app: AppHandle,
+    path: String,
+    display_name: Option<String>,
+    reuse_existing: Option<bool>,
+) -> Result<ImportOutcome, String> {
+    let src = runtime_path::external_input(&path, MAX_IMPORT_BYTES)
+        .map_err(|e| format!("Cannot import {path}: {e}"))?;
 
     // ------------------------------------------------------------
     // Beta behavior: import must create a tenant visible immediately
@@ -34,46 +64,237 @@ pub fn import_tenant_from_file(app: AppHandle, path: String) -> Result<String, S
         return Err("Unsupported file type. Only .wasm or .zip allowed.".into());
     }
 
-    // Create tenant directory
-    let tenant = format!("tenant-{}", Utc::now().timestamp());
-    let tenant_dir = modules.join(&tenant);
+    let staging = staging_dir(&worker_root);
+    fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create staging dir: {e}"))?;
 
-    fs::create_dir_all(&tenant_dir)
-        .map_err(|e| format!("Failed to create tenant dir: {e}"))?;
+    let result = stage_and_place(&src, &ext, &staging, &worker_root, display_name, reuse_existing);
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
 
+fn stage_and_place(
+    src: &Path,
+    ext: &str,
+    staging: &Path,
+    worker_root: &Path,
+    display_name: Option<String>,
+    reuse_existing: Option<bool>,
+) -> Result<ImportOutcome, String> {
     // Handle WASM
     if ext == "wasm" {
-        fs::copy(&src, tenant_dir.join("module.wasm"))
+        let bytes = fs::read(src)
+            .map_err(|e| format!("Failed to read wasm: {e}"))?;
+        write_atomic(&staging.join("module.wasm"), &bytes, Expect::Absent)
             .map_err(|e| format!("Failed to copy wasm: {e}"))?;
     }
 
     // Handle ZIP
     if ext == "zip" {
-        let file = fs::File::open(&src)
+        let file = fs::File::open(src)
             .map_err(|e| format!("Failed to open zip: {e}"))?;
         let mut archive =
             ZipArchive::new(file).map_err(|e| format!("Invalid zip: {e}"))?;
 
+        // Declared sizes are checked before anything is written.
+        let mut expanded: u64 = 0;
+        for i in 0..archive.len() {
+            let entry = archive
+                .by_index(i)
+                .map_err(|e| format!("Invalid zip: {e}"))?;
+            expanded = expanded.saturating_add(entry.size());
+        }
+        if expanded > MAX_IMPORT_BYTES {
+            return Err(format!("Zip expands to {expanded} bytes, over the {MAX_IMPORT_BYTES} byte limit"));
+        }
+
         archive
-            .extract(&tenant_dir)
+            .extract(staging)
             .map_err(|e| format!("Failed to extract zip: {e}"))?;
     }
 
-    // Mandatory ingestion metadata (manual channel)
-    let manifest = serde_json::json!({
-        "tenant": tenant,
-        "ingestion": {
-            "channel": "manual",
-            "source": "gui",
-            "timestamp": Utc::now().to_rfc3339()
+    // A signed export must verify in full; a plain tenant zip has no index.
+    let verified = if staging.join(bundle::INDEX_FILE).exists() {
+        Some(
+            bundle::verify_extracted(worker_root, staging)
+                .map_err(|e| format!("Bundle rejected: {e}"))?,
+        )
+    } else {
+        None
+    };
+
+    let wasm = fs::read(staging.join("module.wasm"))
+        .map_err(|_| "Import does not contain module.wasm".to_string())?;
+    let sha256 = sha256_hex(&wasm);
+
+    let display_name = display_name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty());
+
+    if let Some(existing) = tenant_id::find_by_sha(worker_root, &sha256) {
+        match reuse_existing {
+            Some(true) => {
+                return Ok(ImportOutcome {
+                    status: "reused".into(),
+                    tenant: existing,
+                    sha256,
+                    display_name,
+                    bundle: verified,
+                })
+            }
+            // Caller has not decided yet: report the match and stop.
+            None => {
+                return Ok(ImportOutcome {
+                    status: "duplicate".into(),
+                    tenant: existing,
+                    sha256,
+                    display_name,
+                    bundle: verified,
+                })
+            }
+            Some(false) => {}
         }
+    }
+
+    // Bundles keep their original name, without the old hash suffix.
+    let bundle_hint = verified.as_ref().map(|v| {
+        v.tenant
+            .strip_suffix(&format!("-{}", &sha256[..12]))
+            .unwrap_or(&v.tenant)
+            .to_string()
+    });
+
+    let hint = display_name.clone().or(bundle_hint).unwrap_or_else(|| {
+        src.file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()
+    });
+
+    // A second copy of the same module ("import anyway") gets a suffix.
+    let tenant = tenant_id::unused_tenant_id(&worker_root.join("modules"), &hint, &sha256);
+    let tenant_dir = worker_root.join("modules").join(&tenant);
+
+    // Mandatory ingestion metadata (manual channel)
+    let ingestion = serde_json::json!({
+        "channel": "manual",
+        "source": if verified.is_some() { "bundle" } else { "gui" },
+        "timestamp": Utc::now().to_rfc3339()
     });
 
-    fs::write(
-        tenant_dir.join("manifest.json"),
-        serde_json::to_string_pretty(&manifest).unwrap(),
-    )
-    .map_err(|e| format!("Failed to write manifest: {e}"))?;
+    let manifest_path = staging.join("manifest.json");
+    if let (Some(v), true) = (&verified, manifest_path.exists()) {
+        // A verified bundle keeps its manifest, minus any state granted
+        // on the exporting machine.
+        atomic_fs::update_json(&manifest_path, |m| {
+            bundle::strip_local_state(m);
+            m["tenant"] = tenant.clone().into();
+            if display_name.is_some() {
+                m["display_name"] = display_name.clone().into();
+            }
+            m["ingestion"] = ingestion.clone();
+            m["bundle"] = serde_json::to_value(v)?;
+            Ok(())
+        })
+        .map_err(|e| format!("Failed to write manifest: {e}"))?;
+    } else {
+        // Anything a plain zip says about itself is ignored; the console
+        // owns the manifest.
+        let manifest = serde_json::json!({
+            "tenant": tenant,
+            "display_name": display_name,
+            "ingestion": ingestion
+        });
 
-    Ok(tenant)
+        write_atomic(
+            &manifest_path,
+            serde_json::to_string_pretty(&manifest).unwrap().as_bytes(),
+            Expect::Any,
+        )
+        .map_err(|e| format!("Failed to write manifest: {e}"))?;
+    }
+
+    fs::rename(staging, &tenant_dir)
+        .map_err(|e| format!("Failed to create tenant dir: {e}"))?;
+
+    Ok(ImportOutcome {
+        status: "created".into(),
+        tenant,
+        sha256,
+        display_name,
+        bundle: verified,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::{write::FileOptions, ZipWriter};
+
+    fn plain_zip(dir: &Path, manifest: serde_json::Value) -> PathBuf {
+        let p = dir.join("demo.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&p).unwrap());
+        zip.start_file("module.wasm", FileOptions::default()).unwrap();
+        zip.write_all(b"\0asm\x01\0\0\0").unwrap();
+        zip.start_file("manifest.json", FileOptions::default()).unwrap();
+        zip.write_all(manifest.to_string().as_bytes()).unwrap();
+        zip.finish().unwrap();
+        p
+    }
+
+    fn import(root: &Path, src: &Path, reuse: Option<bool>) -> ImportOutcome {
+        fs::create_dir_all(root.join("modules")).unwrap();
+        let staging = staging_dir(root);
+        fs::create_dir_all(&staging).unwrap();
+        let out = stage_and_place(src, "zip", &staging, root, None, reuse);
+        let _ = fs::remove_dir_all(&staging);
+        out.unwrap()
+    }
+
+    fn manifest(root: &Path, tenant: &str) -> serde_json::Value {
+        serde_json::from_slice(&fs::read(root.join("modules").join(tenant).join("manifest.json")).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn plain_zip_cannot_carry_trust() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let src = plain_zip(
+            root,
+            serde_json::json!({
+                "approved": true,
+                "approved_sha256": "f".repeat(64),
+                "approvals": [{ "approver": "alice" }],
+                "active_revision": "f".repeat(64),
+                "provenance": { "signature_verified": true },
+                "ingestion": { "channel": "automated" },
+            }),
+        );
+
+        let out = import(root, &src, None);
+        assert_eq!(out.status, "created");
+
+        let m = manifest(root, &out.tenant);
+        for k in ["approved", "approved_sha256", "approvals", "active_revision", "provenance"] {
+            assert!(m.get(k).is_none(), "{k} survived import: {m}");
+        }
+        assert_eq!(m["ingestion"]["channel"], "manual");
+    }
+
+    #[test]
+    fn duplicate_import_reports_then_reuses_or_copies() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let src = plain_zip(root, serde_json::json!({}));
+
+        let first = import(root, &src, None);
+        assert_eq!(import(root, &src, None).status, "duplicate");
+
+        let reused = import(root, &src, Some(true));
+        assert_eq!((reused.status.as_str(), reused.tenant.as_str()), ("reused", first.tenant.as_str()));
+
+        let copy = import(root, &src, Some(false));
+        assert_eq!(copy.status, "created");
+        assert_eq!(copy.tenant, format!("{}-2", first.tenant));
+    }
 }
@@ -0,0 +1,62 @@
+use tauri::AppHandle;
+
+use crate::index_db::{self, BackendStats, ShaAnalytics, ShaEvent, SyncReport, TenantHistory, TimeBucket};
+use crate::resolve_worker_root;
+
+/// `bucket` is "hour" or "day" (default). Bounds are RFC 3339 timestamps.
+#[tauri::command]
+pub fn index_decisions_over_time(
+    app: AppHandle,
+    tenant: Option<String>,
+    bucket: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<TimeBucket>, String> {
+    let by_day = match bucket.as_deref() {
+        None | Some("day") => true,
+        Some("hour") => false,
+        Some(other) => return Err(format!("Unknown bucket '{other}'")),
+    };
+
+    let root = resolve_worker_root(&app)?;
+    let conn = index_db::open_synced(&root).map_err(|e| e.to_string())?;
+    index_db::decisions_over_time(&conn, tenant.as_deref(), by_day, from.as_deref(), to.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn index_sha_history(app: AppHandle, sha256: String) -> Result<Vec<ShaEvent>, String> {
+    let root = resolve_worker_root(&app)?;
+    let conn = index_db::open_synced(&root).map_err(|e| e.to_string())?;
+    index_db::sha_history(&conn, &sha256).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn index_backend_stats(app: AppHandle) -> Result<Vec<BackendStats>, String> {
+    let root = resolve_worker_root(&app)?;
+    let conn = index_db::open_synced(&root).map_err(|e| e.to_string())?;
+    index_db::backend_stats(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn index_sha_analytics(app: AppHandle, tenant: Option<String>) -> Result<Vec<ShaAnalytics>, String> {
+    let root = resolve_worker_root(&app)?;
+    let conn = index_db::open_synced(&root).map_err(|e| e.to_string())?;
+    index_db::sha_analytics(&conn, tenant.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Drop the index and rebuild it from the JSONL logs and manifests.
+#[tauri::command]
+pub fn rebuild_index(app: AppHandle) -> Result<SyncReport, String> {
+    let root = resolve_worker_root(&app)?;
+    index_db::rebuild(&root).map_err(|e| e.to_string())
+}
+
+/// Approval, revision and score history of one tenant, including one
+/// that has since been deleted.
+#[tauri::command]
+pub fn index_tenant_history(app: AppHandle, tenant: String) -> Result<Option<TenantHistory>, String> {
+    let root = resolve_worker_root(&app)?;
+    let conn = index_db::open_synced(&root).map_err(|e| e.to_string())?;
+    index_db::tenant_history(&conn, &tenant).map_err(|e| e.to_string())
+}
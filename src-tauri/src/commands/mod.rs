@@ -1 +1,14 @@
+pub mod anomaly;
+pub mod diagnostics;
+pub mod export_evidence;
+pub mod export_tenant;
 pub mod import_tenant;
+pub mod index;
+pub mod submit_agent;
+pub mod operator;
+pub mod retention;
+pub mod revisions;
+pub mod runs;
+pub mod schedules;
+pub mod tenant_control;
+pub mod timeline;
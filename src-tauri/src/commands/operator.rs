@@ -0,0 +1,24 @@
+use tauri::AppHandle;
+
+use crate::operator::{self, OperatorProfile, OperatorRegistry};
+use crate::resolve_worker_root;
+
+#[tauri::command]
+pub fn list_operator_profiles(app: AppHandle) -> Result<OperatorRegistry, String> {
+    let root = resolve_worker_root(&app)?;
+    operator::load_registry(&root).map_err(|e| e.to_string())
+}
+
+/// New identity with a fresh Ed25519 key; it becomes the active operator.
+#[tauri::command]
+pub fn create_operator_profile(app: AppHandle, name: String) -> Result<OperatorProfile, String> {
+    let root = resolve_worker_root(&app)?;
+    operator::create_profile(&root, name.trim()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_active_operator(app: AppHandle, name: String) -> Result<bool, String> {
+    let root = resolve_worker_root(&app)?;
+    operator::set_active(&root, &name).map_err(|e| e.to_string())?;
+    Ok(true)
+}
@@ -0,0 +1,30 @@
+use tauri::AppHandle;
+
+use crate::resolve_worker_root;
+use crate::retention::{self, RetentionConfig, SegmentCheck, SegmentSeal};
+
+#[tauri::command]
+pub fn get_retention_config(app: AppHandle) -> Result<RetentionConfig, String> {
+    let root = resolve_worker_root(&app)?;
+    retention::load_config(&root).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_retention_config(app: AppHandle, config: RetentionConfig) -> Result<bool, String> {
+    let root = resolve_worker_root(&app)?;
+    retention::save_config(&root, &config).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// Rotate logs that are due; `force` rotates every non-empty log.
+#[tauri::command]
+pub fn rotate_logs(app: AppHandle, force: Option<bool>) -> Result<Vec<SegmentSeal>, String> {
+    let root = resolve_worker_root(&app)?;
+    retention::run(&root, force.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn verify_log_seals(app: AppHandle) -> Result<Vec<SegmentCheck>, String> {
+    let root = resolve_worker_root(&app)?;
+    Ok(retention::verify(&root))
+}
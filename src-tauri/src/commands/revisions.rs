@@ -0,0 +1,93 @@
+use tauri::AppHandle;
+
+use std::fs;
+
+use crate::audit;
+use crate::operator;
+use crate::resolve_worker_root;
+use crate::revisions::{self, RevisionDiff, RevisionInfo};
+use crate::runtime_path::{self, MAX_IMPORT_BYTES};
+
+#[tauri::command]
+pub fn list_tenant_revisions(app: AppHandle, tenant: String) -> Result<Vec<RevisionInfo>, String> {
+    let root = resolve_worker_root(&app)?;
+    runtime_path::check_tenant(&tenant).map_err(|e| e.to_string())?;
+    revisions::list_revisions(&root, &tenant).map_err(|e| e.to_string())
+}
+
+/// Add a module as a new, inactive revision of an existing tenant.
+#[tauri::command]
+pub fn import_tenant_revision(app: AppHandle, tenant: String, path: String) -> Result<String, String> {
+    let root = resolve_worker_root(&app)?;
+    runtime_path::check_tenant(&tenant).map_err(|e| e.to_string())?;
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+
+    let src = runtime_path::external_input(&path, MAX_IMPORT_BYTES).map_err(|e| e.to_string())?;
+    let wasm = fs::read(src)
+        .map_err(|e| format!("Failed to read wasm: {e}"))?;
+
+    let record = revisions::add_revision(&root, &tenant, &wasm, &op.name)
+        .map_err(|e| e.to_string())?;
+
+    audit::record_signed(
+        &root,
+        &op,
+        "revision_added",
+        Some(&tenant),
+        serde_json::json!({ "sha256": record.sha256 }),
+    )
+    .map_err(|e| format!("Revision added but audit write failed: {e}"))?;
+
+    Ok(record.sha256)
+}
+
+fn switch_revision(app: &AppHandle, tenant: &str, sha: &str, action: &str) -> Result<bool, String> {
+    let root = resolve_worker_root(app)?;
+    runtime_path::check_tenant(tenant).map_err(|e| e.to_string())?;
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+
+    revisions::promote(&root, tenant, sha, &op.name).map_err(|e| e.to_string())?;
+
+    audit::record_signed(&root, &op, action, Some(tenant), serde_json::json!({ "sha256": sha }))
+        .map_err(|e| format!("Revision switched but audit write failed: {e}"))?;
+
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn promote_tenant_revision(app: AppHandle, tenant: String, sha256: String) -> Result<bool, String> {
+    switch_revision(&app, &tenant, &sha256, "revision_promoted")
+}
+
+/// Without `sha256`, returns to the revision that was active before the
+/// last promotion.
+#[tauri::command]
+pub fn rollback_tenant_revision(
+    app: AppHandle,
+    tenant: String,
+    sha256: Option<String>,
+) -> Result<bool, String> {
+    runtime_path::check_tenant(&tenant).map_err(|e| e.to_string())?;
+
+    let target = match sha256 {
+        Some(s) => s,
+        None => {
+            let root = resolve_worker_root(&app)?;
+            revisions::previous_revision(&root, &tenant).map_err(|e| e.to_string())?
+        }
+    };
+
+    switch_revision(&app, &tenant, &target, "revision_rolled_back")
+}
+
+#[tauri::command]
+pub fn compare_tenant_revisions(
+    app: AppHandle,
+    tenant: String,
+    from: String,
+    to: String,
+) -> Result<RevisionDiff, String> {
+    let root = resolve_worker_root(&app)?;
+    runtime_path::check_tenant(&tenant).map_err(|e| e.to_string())?;
+    revisions::compare(&root, &tenant, &from, &to).map_err(|e| e.to_string())
+}
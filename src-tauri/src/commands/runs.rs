@@ -0,0 +1,28 @@
+use tauri::AppHandle;
+
+use crate::resolve_worker_root;
+use crate::runs::{self, RunPage, RunRecord};
+use crate::runtime_path;
+
+/// Recorded worker runs, newest first, optionally for one tenant.
+#[tauri::command]
+pub fn list_runs(
+    app: AppHandle,
+    tenant: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<RunPage, String> {
+    let root = resolve_worker_root(&app)?;
+
+    if let Some(t) = &tenant {
+        runtime_path::check_tenant(t).map_err(|e| e.to_string())?;
+    }
+
+    Ok(runs::query(&root, tenant.as_deref(), offset.unwrap_or(0), limit))
+}
+
+#[tauri::command]
+pub fn get_run(app: AppHandle, run_id: String) -> Result<RunRecord, String> {
+    let root = resolve_worker_root(&app)?;
+    runs::get(&root, &run_id).ok_or_else(|| format!("no run {run_id}"))
+}
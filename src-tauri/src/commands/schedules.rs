@@ -0,0 +1,75 @@
+use tauri::AppHandle;
+
+use crate::audit;
+use crate::operator;
+use crate::resolve_worker_root;
+use crate::scheduler::{self, Schedule};
+
+#[tauri::command]
+pub fn list_schedules(app: AppHandle) -> Result<Vec<Schedule>, String> {
+    let root = resolve_worker_root(&app)?;
+    scheduler::list(&root).map_err(|e| e.to_string())
+}
+
+/// `cron` is a five-field UTC expression, e.g. `0 3 * * mon-fri`.
+#[tauri::command]
+pub fn create_schedule(
+    app: AppHandle,
+    tenant: String,
+    cron: String,
+    backend: String,
+) -> Result<Schedule, String> {
+    let root = resolve_worker_root(&app)?;
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+
+    let s = scheduler::add(&root, &tenant, &cron, &backend, &op.name).map_err(|e| format!("{e:#}"))?;
+
+    audit::record_signed(
+        &root,
+        &op,
+        "schedule_created",
+        Some(&s.tenant),
+        serde_json::json!({ "id": s.id, "cron": s.cron, "backend": s.backend }),
+    )
+    .map_err(|e| format!("Schedule created but audit write failed: {e}"))?;
+
+    Ok(s)
+}
+
+#[tauri::command]
+pub fn delete_schedule(app: AppHandle, id: String) -> Result<bool, String> {
+    let root = resolve_worker_root(&app)?;
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+
+    let s = scheduler::remove(&root, &id).map_err(|e| e.to_string())?;
+
+    audit::record_signed(
+        &root,
+        &op,
+        "schedule_deleted",
+        Some(&s.tenant),
+        serde_json::json!({ "id": s.id, "cron": s.cron, "backend": s.backend }),
+    )
+    .map_err(|e| format!("Schedule deleted but audit write failed: {e}"))?;
+
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn set_schedule_enabled(app: AppHandle, id: String, enabled: bool) -> Result<Schedule, String> {
+    let root = resolve_worker_root(&app)?;
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+
+    let s = scheduler::set_enabled(&root, &id, enabled).map_err(|e| e.to_string())?;
+
+    audit::record_signed(
+        &root,
+        &op,
+        if enabled { "schedule_enabled" } else { "schedule_disabled" },
+        Some(&s.tenant),
+        serde_json::json!({ "id": s.id }),
+    )
+    .map_err(|e| format!("Schedule updated but audit write failed: {e}"))?;
+
+    Ok(s)
+}
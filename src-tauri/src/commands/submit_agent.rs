@@ -0,0 +1,32 @@
+use tauri::AppHandle;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+use crate::resolve_worker_root;
+use crate::submission::{self, AgentMetadata};
+
+/// Agent ingestion entry point. The module arrives base64-encoded so the
+/// call works the same from the webview and from local agent bridges.
+#[tauri::command]
+pub fn submit_agent_module(
+    app: AppHandle,
+    wasm_b64: String,
+    metadata: AgentMetadata,
+    signature: Option<String>,
+    agent_pubkey: Option<String>,
+) -> Result<String, String> {
+    let wasm = STANDARD
+        .decode(wasm_b64.trim())
+        .map_err(|e| format!("Invalid module encoding: {e}"))?;
+
+    let worker_root = resolve_worker_root(&app)?;
+
+    submission::submit_agent_module(
+        &worker_root,
+        &wasm,
+        &metadata,
+        signature.as_deref(),
+        agent_pubkey.as_deref(),
+    )
+    .map_err(|e| format!("Agent submission rejected: {e}"))
+}
@@ -0,0 +1,105 @@
+use serde::{Serialize, Deserialize};
+use tauri::AppHandle;
+
+use crate::audit;
+use crate::operator;
+use crate::process_registry::{self, KillReport, TrackedProcess};
+use crate::tenant_state::{self, Suspension};
+use crate::{resolve_worker_root, runtime_path};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StopOutcome {
+    pub tenant: String,
+    pub stopped: Vec<KillReport>,
+    /// Runs covering every tenant (`run --all`). They cannot be narrowed
+    /// to one tenant, so they are left running; use the kill switch.
+    pub shared_runs: Vec<TrackedProcess>,
+}
+
+/// Stop the console-started runs of one tenant.
+#[tauri::command]
+pub async fn stop_tenant(app: AppHandle, tenant: String) -> Result<StopOutcome, String> {
+    let root = resolve_worker_root(&app)?;
+    runtime_path::check_tenant(&tenant).map_err(|e| e.to_string())?;
+
+    // Waits out the grace period; keep it off the async runtime.
+    let stopped = {
+        let (root, tenant) = (root.clone(), tenant.clone());
+        tauri::async_runtime::spawn_blocking(move || {
+            process_registry::terminate(&root, |p| p.tenant.as_deref() == Some(tenant.as_str()))
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?
+    };
+
+    let shared_runs = process_registry::list(&root)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|p| p.tenant.is_none())
+        .collect();
+
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+    audit::record_signed(
+        &root,
+        &op,
+        "tenant_stopped",
+        Some(&tenant),
+        serde_json::json!({ "processes": stopped }),
+    )
+    .map_err(|e| format!("Stopped but audit write failed: {e}"))?;
+
+    Ok(StopOutcome { tenant, stopped, shared_runs })
+}
+
+/// Hold a tenant: no approvals, renewals or runs until resumed.
+#[tauri::command]
+pub fn suspend_tenant(
+    app: AppHandle,
+    tenant: String,
+    reason: Option<String>,
+) -> Result<Suspension, String> {
+    let root = resolve_worker_root(&app)?;
+    runtime_path::check_tenant(&tenant).map_err(|e| e.to_string())?;
+
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+    let reason = reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+
+    let s = tenant_state::mark_suspended(&root, &tenant, &op, reason)
+        .map_err(|e| e.to_string())?;
+
+    audit::record_signed(
+        &root,
+        &op,
+        "tenant_suspended",
+        Some(&tenant),
+        serde_json::json!({ "reason": s.reason }),
+    )
+    .map_err(|e| format!("Suspended but audit write failed: {e}"))?;
+
+    Ok(s)
+}
+
+#[tauri::command]
+pub fn resume_tenant(app: AppHandle, tenant: String) -> Result<bool, String> {
+    let root = resolve_worker_root(&app)?;
+    runtime_path::check_tenant(&tenant).map_err(|e| e.to_string())?;
+
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+
+    let lifted = tenant_state::mark_resumed(&root, &tenant).map_err(|e| e.to_string())?;
+
+    audit::record_signed(
+        &root,
+        &op,
+        "tenant_resumed",
+        Some(&tenant),
+        serde_json::json!({
+            "suspended_at": lifted.suspended_at,
+            "suspended_by": lifted.suspended_by,
+        }),
+    )
+    .map_err(|e| format!("Resumed but audit write failed: {e}"))?;
+
+    Ok(true)
+}
@@ -0,0 +1,31 @@
+use tauri::AppHandle;
+
+use crate::resolve_worker_root;
+use crate::runtime_path;
+use crate::timeline::{self, EventKind, TimelinePage, TimelineQuery};
+
+/// One page of the merged event stream, newest first. `kinds` takes
+/// import | approval | decision | quarantine | audit; omitted means all.
+#[tauri::command]
+pub fn get_timeline(
+    app: AppHandle,
+    tenant: Option<String>,
+    kinds: Option<Vec<EventKind>>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<TimelinePage, String> {
+    let root = resolve_worker_root(&app)?;
+
+    if let Some(t) = &tenant {
+        runtime_path::check_tenant(t).map_err(|e| e.to_string())?;
+    }
+
+    let q = TimelineQuery {
+        tenant,
+        kinds: kinds.unwrap_or_default(),
+        offset: offset.unwrap_or(0),
+        limit,
+    };
+
+    Ok(timeline::query(&root, &q))
+}
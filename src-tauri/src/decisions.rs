@@ -0,0 +1,38 @@
+use std::path::Path;
+
+use crate::retention;
+use crate::GuardianDecisionLite;
+
+/// Raw log lines, untouched, across rotated segments. Used for evidence
+/// export.
+pub fn raw_lines(worker_root: &Path) -> Vec<String> {
+    retention::read_lines(worker_root, retention::GUARDIAN_DECISIONS)
+}
+
+/// Every parseable decision, oldest first, including rotated segments.
+/// Malformed lines are skipped; the worker owns the log and may be
+/// mid-write.
+pub fn load_all(worker_root: &Path) -> Vec<GuardianDecisionLite> {
+    raw_lines(worker_root)
+        .iter()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+/// A decision as the worker logs it, with the fields in `overrides`
+/// replaced. Shared by the tests that build decision logs.
+#[cfg(test)]
+pub fn decision_json(overrides: serde_json::Value) -> serde_json::Value {
+    let mut v = serde_json::json!({
+        "timestamp": "2026-03-01T10:00:00Z", "tenant": "t", "backend": "wasmtime", "proof_mode": false,
+        "decision": "allow", "reason": "ok", "threat_score": 0, "threat_label": "low", "threat_color": "green",
+        "sha256": "aa", "first_seen": false, "known_sha": true, "wasm_size_bytes": 1024,
+        "memory_request_mb": 64, "runtime_request_ms": 1000, "wasi_fs_access": false,
+        "wasi_net_access": false, "wasi_imports": [], "policy_exists": true, "backend_allowed": true,
+        "trusted_signer": true,
+    });
+    for (k, x) in overrides.as_object().expect("overrides must be an object") {
+        v[k] = x.clone();
+    }
+    v
+}
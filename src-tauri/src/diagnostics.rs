@@ -0,0 +1,601 @@
+use serde::{Serialize, Deserialize};
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+use anyhow::{bail, Context, Result};
+use rand::{rngs::OsRng, RngCore};
+
+use crate::atomic_fs::{write_atomic, Expect};
+use crate::process_registry;
+
+/// Tenant name the probe runs under, inside its own scratch runtime.
+const PROBE_TENANT: &str = "_probe";
+
+/// Line the probe module writes to stdout.
+const PROBE_MARKER: &str = "nightcore-probe-ok";
+
+/// Hard stop for the probe run, whatever the policy says.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Known-good WASI module:
+///
+/// ```wat
+/// (module
+///   (import "wasi_snapshot_preview1" "fd_write"
+///     (func $fd_write (param i32 i32 i32 i32) (result i32)))
+///   (memory (export "memory") 1)
+///   (data (i32.const 0) "\08\00\00\00\13\00\00\00nightcore-probe-ok\n")
+///   (func (export "_start")
+///     (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 32)))))
+/// ```
+const PROBE_WASM: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0c, 0x02, 0x60,
+    0x04, 0x7f, 0x7f, 0x7f, 0x7f, 0x01, 0x7f, 0x60, 0x00, 0x00, 0x02, 0x23,
+    0x01, 0x16, 0x77, 0x61, 0x73, 0x69, 0x5f, 0x73, 0x6e, 0x61, 0x70, 0x73,
+    0x68, 0x6f, 0x74, 0x5f, 0x70, 0x72, 0x65, 0x76, 0x69, 0x65, 0x77, 0x31,
+    0x08, 0x66, 0x64, 0x5f, 0x77, 0x72, 0x69, 0x74, 0x65, 0x00, 0x00, 0x03,
+    0x02, 0x01, 0x01, 0x05, 0x03, 0x01, 0x00, 0x01, 0x07, 0x13, 0x02, 0x06,
+    0x6d, 0x65, 0x6d, 0x6f, 0x72, 0x79, 0x02, 0x00, 0x06, 0x5f, 0x73, 0x74,
+    0x61, 0x72, 0x74, 0x00, 0x01, 0x0a, 0x0f, 0x01, 0x0d, 0x00, 0x41, 0x01,
+    0x41, 0x00, 0x41, 0x01, 0x41, 0x20, 0x10, 0x00, 0x1a, 0x0b, 0x0b, 0x21,
+    0x01, 0x00, 0x41, 0x00, 0x0b, 0x1b, 0x08, 0x00, 0x00, 0x00, 0x13, 0x00,
+    0x00, 0x00, 0x6e, 0x69, 0x67, 0x68, 0x74, 0x63, 0x6f, 0x72, 0x65, 0x2d,
+    0x70, 0x72, 0x6f, 0x62, 0x65, 0x2d, 0x6f, 0x6b, 0x0a,
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    /// Not applicable here, or blocked by an earlier failure.
+    Skip,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiagnosticCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub reason: String,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackendDiagnostics {
+    pub backend: String,
+    /// No check failed and at least one passed.
+    pub ok: bool,
+    /// First failure, or a pass/skip tally.
+    pub message: String,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+fn check(name: &str, status: CheckStatus, reason: impl Into<String>) -> DiagnosticCheck {
+    DiagnosticCheck { name: name.into(), status, reason: reason.into(), duration_ms: None }
+}
+
+pub fn summarize(backend: &str, checks: Vec<DiagnosticCheck>) -> BackendDiagnostics {
+    let count = |s| checks.iter().filter(|c| c.status == s).count();
+    let (passed, skipped) = (count(CheckStatus::Pass), count(CheckStatus::Skip));
+
+    let message = match checks.iter().find(|c| c.status == CheckStatus::Fail) {
+        Some(f) => format!("{}: {}", f.name, f.reason),
+        None => format!("{passed} passed, {skipped} skipped"),
+    };
+
+    BackendDiagnostics {
+        backend: backend.into(),
+        ok: passed > 0 && count(CheckStatus::Fail) == 0,
+        message,
+        checks,
+    }
+}
+
+/// First line of `<bin> --version`.
+fn binary_version(bin: &Path) -> Result<String> {
+    let out = Command::new(bin)
+        .arg("--version")
+        .output()
+        .with_context(|| format!("running {}", bin.display()))?;
+
+    if !out.status.success() {
+        bail!("{} --version exited with {}", bin.display(), out.status);
+    }
+
+    let text = String::from_utf8_lossy(&out.stdout);
+    Ok(text.lines().next().unwrap_or("").trim().to_string())
+}
+
+fn version_check(name: &str, bin: Option<PathBuf>, missing: &str) -> DiagnosticCheck {
+    match bin {
+        None => check(name, CheckStatus::Fail, missing),
+        Some(b) => match binary_version(&b) {
+            Ok(v) => check(name, CheckStatus::Pass, format!("{v} ({})", b.display())),
+            Err(e) => check(name, CheckStatus::Fail, e.to_string()),
+        },
+    }
+}
+
+// ---------------------------------------------------------
+// WASMTIME
+// ---------------------------------------------------------
+
+/// Execution budget from the default policy, as the worker enforces it.
+fn default_max_exec_ms(worker_root: &Path) -> u64 {
+    fs::read_to_string(worker_root.join("guardian").join("policies").join("_default.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        .and_then(|p| p.get("max_exec_ms").and_then(|v| v.as_u64()))
+        .unwrap_or(2000)
+}
+
+/// Scratch runtimes of probes, one directory per probe.
+fn probes_dir(worker_root: &Path) -> PathBuf {
+    worker_root.join("state").join("probes")
+}
+
+/// Remove scratch runtimes left by probes that cannot still be running.
+fn clear_stale_probes(worker_root: &Path) {
+    let Ok(entries) = fs::read_dir(probes_dir(worker_root)) else {
+        return;
+    };
+
+    for e in entries.flatten() {
+        let stale = e
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .is_some_and(|age| age > PROBE_TIMEOUT * 2);
+        if stale {
+            let _ = fs::remove_dir_all(e.path());
+        }
+    }
+}
+
+/// Lay out a throwaway runtime with only the probe tenant, so the real
+/// modules, logs and decisions are never touched. Each probe gets its
+/// own directory, so probes run side by side do not clear each other's.
+/// Returns the HOME the worker should run with.
+fn stage_probe(worker_root: &Path, max_exec_ms: u64) -> Result<PathBuf> {
+    clear_stale_probes(worker_root);
+
+    let dir = probes_dir(worker_root);
+    fs::create_dir_all(&dir).with_context(|| format!("creating {}", dir.display()))?;
+    let home = dir.join(format!("{:016x}", OsRng.next_u64()));
+    fs::create_dir(&home).with_context(|| format!("creating {}", home.display()))?;
+
+    let root = home.join(".nightcore");
+    let tenant_dir = root.join("modules").join(PROBE_TENANT);
+    let policies = root.join("guardian").join("policies");
+    for d in [&tenant_dir, &policies, &root.join("logs")] {
+        fs::create_dir_all(d).with_context(|| format!("creating {}", d.display()))?;
+    }
+
+    write_atomic(&tenant_dir.join("module.wasm"), PROBE_WASM, Expect::Absent)?;
+
+    let manifest = serde_json::json!({
+        "tenant": PROBE_TENANT,
+        "display_name": "Backend diagnostics probe",
+        "approved": true,
+        "approved_by": "diagnostics",
+        "approved_sha256": crate::submission::sha256_hex(PROBE_WASM),
+        "ingestion": { "channel": "diagnostics", "source": "console", "timestamp": chrono::Utc::now().to_rfc3339() }
+    });
+    write_atomic(&tenant_dir.join("manifest.json"), serde_json::to_string_pretty(&manifest)?.as_bytes(), Expect::Absent)?;
+
+    let policy = serde_json::json!({
+        "tenant": PROBE_TENANT,
+        "allowed_backends": ["wasmtime"],
+        "allow_proof_mode": false,
+        "require_manifest": true,
+        "require_signature": false,
+        "max_exec_ms": max_exec_ms,
+        "max_memory_mb": 16
+    });
+    write_atomic(&policies.join(format!("{PROBE_TENANT}.json")), serde_json::to_string_pretty(&policy)?.as_bytes(), Expect::Absent)?;
+
+    Ok(home)
+}
+
+struct ProbeRun {
+    success: bool,
+    stdout: String,
+    stderr: String,
+    elapsed: Duration,
+}
+
+/// The worker, run over the staged runtime in `home`.
+fn probe_command(worker_bin: &Path, home: &Path) -> Command {
+    let mut cmd = Command::new(worker_bin);
+    cmd.current_dir(home.join(".nightcore"))
+        .env("HOME", home)
+        .env("USERPROFILE", home)
+        .args(["run", "--all", "--backend", "wasmtime"]);
+    cmd
+}
+
+/// Read a pipe to the end on its own thread.
+fn drain(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<String> {
+    thread::spawn(move || {
+        let mut out = Vec::new();
+        if let Some(mut p) = pipe {
+            let _ = p.read_to_end(&mut out);
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    })
+}
+
+fn run_probe(worker_root: &Path, mut cmd: Command) -> Result<ProbeRun> {
+    let start = Instant::now();
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    let mut tracked = process_registry::spawn(worker_root, &mut cmd, Some(PROBE_TENANT), Some("wasmtime"))?;
+    let child = tracked.child_mut();
+
+    // Drained while polling: a worker that fills a pipe would otherwise
+    // block on it and never exit.
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let status = loop {
+        if let Some(s) = child.try_wait()? {
+            break s;
+        }
+        if start.elapsed() > PROBE_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("worker did not finish within {}s", PROBE_TIMEOUT.as_secs());
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    let elapsed = start.elapsed();
+
+    Ok(ProbeRun {
+        success: status.success(),
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+        elapsed,
+    })
+}
+
+fn probe_check(worker_root: &Path, worker_bin: &Path) -> DiagnosticCheck {
+    const NAME: &str = "probe module";
+    let budget = default_max_exec_ms(worker_root);
+
+    let run = stage_probe(worker_root, budget).and_then(|home| {
+        let r = run_probe(worker_root, probe_command(worker_bin, &home));
+        let _ = fs::remove_dir_all(&home);
+        r
+    });
+
+    let run = match run {
+        Ok(r) => r,
+        Err(e) => return check(NAME, CheckStatus::Fail, e.to_string()),
+    };
+
+    let ms = run.elapsed.as_millis() as u64;
+    let mut c = if !run.success {
+        let err = run.stderr.lines().last().unwrap_or("no output").trim().to_string();
+        check(NAME, CheckStatus::Fail, format!("worker exited with an error: {err}"))
+    } else if !run.stdout.contains(PROBE_MARKER) {
+        check(NAME, CheckStatus::Fail, "worker succeeded but the probe output was missing")
+    } else if ms > budget {
+        check(NAME, CheckStatus::Fail, format!("probe took {ms} ms, over the {budget} ms policy budget"))
+    } else {
+        check(NAME, CheckStatus::Pass, format!("expected output in {ms} ms (budget {budget} ms)"))
+    };
+    c.duration_ms = Some(ms);
+    c
+}
+
+/// Wasmtime CLI on PATH, then the probe module end to end through the
+/// worker.
+pub fn wasmtime_checks(worker_root: &Path, worker_bin: Result<PathBuf, String>) -> Vec<DiagnosticCheck> {
+    let mut checks = vec![version_check(
+        "wasmtime binary",
+        which::which("wasmtime").ok(),
+        "wasmtime not found in PATH",
+    )];
+
+    match worker_bin {
+        Err(e) => {
+            checks.push(check("worker binary", CheckStatus::Fail, e));
+            checks.push(check("probe module", CheckStatus::Skip, "no worker binary to run it"));
+        }
+        Ok(bin) => {
+            checks.push(check("worker binary", CheckStatus::Pass, bin.display().to_string()));
+            checks.push(probe_check(worker_root, &bin));
+        }
+    }
+
+    checks
+}
+
+// ---------------------------------------------------------
+// FIRECRACKER
+// ---------------------------------------------------------
+
+/// Where the microVM assets live. Stored in `state/firecracker.json`;
+/// every field is optional.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FirecrackerConfig {
+    #[serde(default)]
+    pub binary: Option<PathBuf>,
+    #[serde(default)]
+    pub jailer: Option<PathBuf>,
+    #[serde(default)]
+    pub kernel: Option<PathBuf>,
+    #[serde(default)]
+    pub rootfs: Option<PathBuf>,
+}
+
+pub fn load_firecracker_config(worker_root: &Path) -> Result<FirecrackerConfig> {
+    let p = worker_root.join("state").join("firecracker.json");
+    match fs::read_to_string(&p) {
+        Ok(raw) => serde_json::from_str(&raw).with_context(|| format!("parsing {}", p.display())),
+        Err(_) => Ok(FirecrackerConfig::default()),
+    }
+}
+
+fn asset_check(name: &str, path: PathBuf) -> DiagnosticCheck {
+    match fs::metadata(&path) {
+        Ok(m) if m.is_file() && m.len() > 0 => {
+            check(name, CheckStatus::Pass, format!("{} ({} bytes)", path.display(), m.len()))
+        }
+        Ok(_) => check(name, CheckStatus::Fail, format!("{} is empty or not a file", path.display())),
+        Err(_) => check(name, CheckStatus::Fail, format!("{} not found", path.display())),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn kvm_check() -> DiagnosticCheck {
+    const NAME: &str = "/dev/kvm";
+    let dev = Path::new("/dev/kvm");
+    if !dev.exists() {
+        return check(NAME, CheckStatus::Fail, "/dev/kvm missing (virtualization disabled or kvm module not loaded)");
+    }
+
+    match fs::OpenOptions::new().read(true).write(true).open(dev) {
+        Ok(_) => check(NAME, CheckStatus::Pass, "read/write access"),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            check(NAME, CheckStatus::Fail, "permission denied (add this user to the kvm group)")
+        }
+        Err(e) => check(NAME, CheckStatus::Fail, e.to_string()),
+    }
+}
+
+/// Binary version, KVM access, kernel and rootfs, and the jailer.
+pub fn firecracker_checks(worker_root: &Path) -> Vec<DiagnosticCheck> {
+    firecracker_host_checks(worker_root, cfg!(target_os = "linux"))
+}
+
+fn firecracker_host_checks(worker_root: &Path, linux: bool) -> Vec<DiagnosticCheck> {
+    const NAMES: [&str; 5] = ["firecracker binary", "/dev/kvm", "kernel image", "rootfs image", "jailer"];
+
+    if !linux {
+        return NAMES
+            .iter()
+            .map(|n| check(n, CheckStatus::Skip, "Firecracker requires a Linux host"))
+            .collect();
+    }
+
+    let cfg = match load_firecracker_config(worker_root) {
+        Ok(c) => c,
+        Err(e) => {
+            return NAMES
+                .iter()
+                .map(|n| check(n, CheckStatus::Fail, format!("{e:#}")))
+                .collect();
+        }
+    };
+
+    let assets = worker_root.join("firecracker");
+    let mut checks = vec![version_check(
+        NAMES[0],
+        cfg.binary.clone().or_else(|| which::which("firecracker").ok()),
+        "firecracker not found (set `binary` in state/firecracker.json or add it to PATH)",
+    )];
+
+    #[cfg(target_os = "linux")]
+    checks.push(kvm_check());
+
+    checks.push(asset_check(NAMES[2], cfg.kernel.clone().unwrap_or_else(|| assets.join("vmlinux"))));
+    checks.push(asset_check(NAMES[3], cfg.rootfs.clone().unwrap_or_else(|| assets.join("rootfs.ext4"))));
+
+    checks.push(version_check(
+        NAMES[4],
+        cfg.jailer.clone().or_else(|| which::which("jailer").ok()),
+        "jailer not found; microVMs would run without chroot and privilege drop",
+    ));
+
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(checks: &'a [DiagnosticCheck], name: &str) -> &'a DiagnosticCheck {
+        checks.iter().find(|c| c.name == name).unwrap()
+    }
+
+    fn write_config(root: &Path, cfg: serde_json::Value) {
+        fs::create_dir_all(root.join("state")).unwrap();
+        fs::write(root.join("state").join("firecracker.json"), cfg.to_string()).unwrap();
+    }
+
+    #[test]
+    fn firecracker_is_skipped_off_linux() {
+        let dir = tempfile::tempdir().unwrap();
+        let checks = firecracker_host_checks(dir.path(), false);
+
+        assert_eq!(checks.len(), 5);
+        assert!(checks.iter().all(|c| c.status == CheckStatus::Skip));
+        assert!(!summarize("firecracker", checks).ok);
+    }
+
+    #[test]
+    fn firecracker_fails_on_missing_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let assets = root.join("firecracker");
+        fs::create_dir_all(&assets).unwrap();
+        fs::write(assets.join("vmlinux"), b"").unwrap();
+
+        write_config(root, serde_json::json!({
+            "binary": root.join("no-such-firecracker"),
+            "jailer": root.join("no-such-jailer"),
+        }));
+        let checks = firecracker_host_checks(root, true);
+
+        for name in ["firecracker binary", "jailer"] {
+            let c = find(&checks, name);
+            assert_eq!(c.status, CheckStatus::Fail, "{name}");
+            assert!(c.reason.contains("running"), "{}", c.reason);
+        }
+        let kernel = find(&checks, "kernel image");
+        assert_eq!(kernel.status, CheckStatus::Fail);
+        assert!(kernel.reason.contains("empty or not a file"));
+        let rootfs = find(&checks, "rootfs image");
+        assert_eq!(rootfs.status, CheckStatus::Fail);
+        assert!(rootfs.reason.contains("not found"));
+
+        // Configured paths win over the default asset directory.
+        let rootfs = root.join("images").join("rootfs.ext4");
+        fs::create_dir_all(rootfs.parent().unwrap()).unwrap();
+        fs::write(&rootfs, b"ext4").unwrap();
+        write_config(root, serde_json::json!({ "rootfs": rootfs }));
+        let checks = firecracker_host_checks(root, true);
+        assert_eq!(find(&checks, "rootfs image").status, CheckStatus::Pass);
+
+        let summary = summarize("firecracker", checks);
+        assert!(!summary.ok);
+    }
+
+    #[test]
+    fn firecracker_reports_an_unreadable_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("state")).unwrap();
+        fs::write(root.join("state").join("firecracker.json"), "{ not json").unwrap();
+
+        assert!(load_firecracker_config(root).is_err());
+        let checks = firecracker_host_checks(root, true);
+        assert_eq!(checks.len(), 5);
+        assert!(checks.iter().all(|c| c.status == CheckStatus::Fail && c.reason.contains("firecracker.json")));
+    }
+
+    #[test]
+    fn probe_is_staged_in_its_own_runtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let real = root.join("modules").join("real");
+        fs::create_dir_all(&real).unwrap();
+        fs::write(real.join("module.wasm"), b"real").unwrap();
+
+        let home = stage_probe(root, 1500).unwrap();
+        assert_eq!(home.parent(), Some(probes_dir(root).as_path()));
+
+        // A second probe does not share or clear the first one's runtime.
+        let other = stage_probe(root, 1500).unwrap();
+        assert_ne!(home, other);
+        assert!(home.join(".nightcore").join("modules").join(PROBE_TENANT).join("module.wasm").exists());
+
+        let runtime = home.join(".nightcore");
+        let tenant = runtime.join("modules").join(PROBE_TENANT);
+        let wasm = fs::read(tenant.join("module.wasm")).unwrap();
+        assert_eq!(wasm, PROBE_WASM);
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(tenant.join("manifest.json")).unwrap()).unwrap();
+        assert_eq!(manifest["approved_sha256"], crate::submission::sha256_hex(&wasm));
+
+        let policy: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(runtime.join("guardian").join("policies").join(format!("{PROBE_TENANT}.json")))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(policy["max_exec_ms"], 1500);
+        assert_eq!(policy["allowed_backends"], serde_json::json!(["wasmtime"]));
+
+        // Only the probe tenant is in the scratch runtime; the real one is untouched.
+        let tenants: Vec<_> = fs::read_dir(runtime.join("modules")).unwrap().flatten().map(|e| e.file_name()).collect();
+        assert_eq!(tenants, vec![PROBE_TENANT]);
+        assert_eq!(fs::read(real.join("module.wasm")).unwrap(), b"real");
+    }
+
+    /// Walks the module's sections the way a decoder would: every
+    /// section must end where the next starts, in the order the spec
+    /// requires, and the data must be the iovec the code writes.
+    #[test]
+    fn probe_module_is_valid_wasm() {
+        assert_eq!(&PROBE_WASM[..8], b"\0asm\x01\0\0\0");
+
+        let mut pos = 8;
+        let mut ids = vec![];
+        let mut data = None;
+        while pos < PROBE_WASM.len() {
+            let id = PROBE_WASM[pos];
+            let len = PROBE_WASM[pos + 1] as usize;
+            assert!(len < 0x80, "section sizes fit in one LEB128 byte");
+            let body = &PROBE_WASM[pos + 2..pos + 2 + len];
+            if id == 11 {
+                data = Some(body);
+            }
+            ids.push(id);
+            pos += 2 + len;
+        }
+        assert_eq!(pos, PROBE_WASM.len());
+        // type, import, function, memory, export, code, data
+        assert_eq!(ids, vec![1, 2, 3, 5, 7, 10, 11]);
+
+        assert_eq!(
+            crate::wasm_meta::parse_imports(PROBE_WASM).unwrap(),
+            vec!["wasi_snapshot_preview1::fd_write"]
+        );
+
+        // One active segment at offset 0: an iovec { buf: 8, len } then the text.
+        let data = data.unwrap();
+        let text = format!("{PROBE_MARKER}\n");
+        assert_eq!(&data[..6], &[1, 0, 0x41, 0, 0x0b, (8 + text.len()) as u8]);
+        let segment = &data[6..];
+        assert_eq!(u32::from_le_bytes(segment[..4].try_into().unwrap()), 8);
+        assert_eq!(u32::from_le_bytes(segment[4..8].try_into().unwrap()) as usize, text.len());
+        assert_eq!(&segment[8..], text.as_bytes());
+    }
+
+    #[cfg(unix)]
+    fn shell(script: &str) -> Command {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", script]);
+        cmd
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn probe_output_larger_than_a_pipe_is_drained() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = format!(
+            "head -c 1000000 /dev/zero | tr '\\0' x; echo; echo {PROBE_MARKER}; head -c 300000 /dev/zero | tr '\\0' e >&2"
+        );
+
+        let run = run_probe(dir.path(), shell(&script)).unwrap();
+        assert!(run.success);
+        assert!(run.stdout.ends_with(&format!("{PROBE_MARKER}\n")));
+        assert_eq!(run.stdout.len(), 1_000_001 + PROBE_MARKER.len() + 1);
+        assert_eq!(run.stderr.len(), 300_000);
+        assert!(run.elapsed < PROBE_TIMEOUT);
+
+        let failed = run_probe(dir.path(), shell("echo boom >&2; exit 3")).unwrap();
+        assert!(!failed.success);
+        assert_eq!(failed.stderr, "boom\n");
+        assert!(process_registry::list(dir.path()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,399 @@
+use serde::{Serialize, Deserialize};
+use std::{fs, path::Path};
+use serde_json::Value;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+
+use crate::bundle::{self, BundleFile};
+use crate::decisions;
+use crate::operator::Operator;
+use crate::retention;
+use crate::runtime_path;
+use crate::submission::sha256_hex;
+use crate::timeline;
+
+pub const EVIDENCE_FORMAT: &str = "nightcore-evidence/v1";
+pub const REPORTS_FORMAT: &str = "nightcore-reports/v1";
+pub const MANIFEST_FILE: &str = "evidence_manifest.json";
+pub const MANIFEST_SIG_FILE: &str = "evidence_manifest.sig";
+
+/// Time window (RFC 3339) and tenant set an evidence bundle is limited
+/// to. Open bounds and an empty tenant list mean "everything".
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct EvidenceScope {
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub tenants: Vec<String>,
+}
+
+fn parse_ts(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s.trim()).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// A scope with its bounds parsed once.
+struct Filter<'a> {
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    tenants: &'a [String],
+}
+
+impl EvidenceScope {
+    fn filter(&self) -> Result<Filter<'_>> {
+        let parse = |b: &Option<String>| -> Result<Option<DateTime<Utc>>> {
+            match b {
+                Some(s) => parse_ts(s).map(Some).with_context(|| format!("invalid timestamp '{s}'")),
+                None => Ok(None),
+            }
+        };
+
+        let f = Filter {
+            from: parse(&self.from)?,
+            to: parse(&self.to)?,
+            tenants: &self.tenants,
+        };
+        if let (Some(from), Some(to)) = (f.from, f.to) {
+            if from > to {
+                bail!("window start is after its end");
+            }
+        }
+        Ok(f)
+    }
+}
+
+impl Filter<'_> {
+    fn in_window(&self, timestamp: Option<&str>) -> bool {
+        // Records without a readable timestamp are kept: dropping them
+        // would hide evidence rather than narrow it.
+        let Some(ts) = timestamp.and_then(parse_ts) else {
+            return true;
+        };
+
+        self.from.is_none_or(|f| ts >= f) && self.to.is_none_or(|t| ts <= t)
+    }
+
+    fn has_tenant(&self, tenant: Option<&str>) -> bool {
+        // Entries not tied to a tenant (policy saves, kill switch) always
+        // belong to the incident record.
+        match tenant {
+            Some(t) => self.tenants.is_empty() || self.tenants.iter().any(|x| x == t),
+            None => true,
+        }
+    }
+
+    fn matches(&self, record: &Value) -> bool {
+        self.in_window(record.get("timestamp").and_then(|t| t.as_str()))
+            && self.has_tenant(record.get("tenant").and_then(|t| t.as_str()))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EvidenceManifest {
+    pub format: String,
+    pub generated_at: String,
+    pub generated_by: String,
+    pub signer_fingerprint: String,
+    pub signer_public_key: String,
+    pub scope: EvidenceScope,
+    pub files: Vec<BundleFile>,
+}
+
+fn filter_jsonl(lines: Vec<String>, scope: &Filter) -> Vec<u8> {
+    lines
+        .into_iter()
+        .filter(|l| match serde_json::from_str::<Value>(l) {
+            Ok(v) => scope.matches(&v),
+            // Unparseable lines are kept verbatim for the examiner.
+            Err(_) => true,
+        })
+        .map(|l| l + "\n")
+        .collect::<String>()
+        .into_bytes()
+}
+
+/// Drop timestamped, tenant-tagged records outside the scope, anywhere in
+/// a report. Grouping objects (runs, sections) are kept as they are.
+fn filter_report(v: &mut Value, scope: &Filter) {
+    match v {
+        Value::Array(items) => {
+            items.retain(|i| !i.is_object() || scope.matches(i));
+            items.iter_mut().for_each(|i| filter_report(i, scope));
+        }
+        Value::Object(map) => map.values_mut().for_each(|i| filter_report(i, scope)),
+        _ => {}
+    }
+}
+
+fn report(worker_root: &Path, rel: &str, scope: &Filter) -> Option<Vec<u8>> {
+    let raw = fs::read(worker_root.join(rel)).ok()?;
+
+    match serde_json::from_slice::<Value>(&raw) {
+        Ok(mut v) => {
+            filter_report(&mut v, scope);
+            serde_json::to_vec_pretty(&v).ok()
+        }
+        Err(_) => Some(raw),
+    }
+}
+
+fn tenant_evidence(worker_root: &Path, tenant: &str) -> Vec<(String, Vec<u8>)> {
+    let dir = worker_root.join("modules").join(tenant);
+    let mut out = vec![];
+
+    if let Ok(m) = fs::read(dir.join("manifest.json")) {
+        out.push((format!("tenants/{tenant}/manifest.json"), m));
+    }
+
+    let wasm = fs::read(dir.join("module.wasm")).ok();
+    let hashes = serde_json::json!({
+        "computed_sha256": wasm.as_deref().map(sha256_hex),
+        "recorded_sha256": fs::read_to_string(dir.join("module.sha256")).ok().map(|s| s.trim().to_string()),
+        "size_bytes": wasm.as_ref().map(|w| w.len()),
+        "signed": dir.join("module.sig").exists(),
+    });
+    out.push((
+        format!("tenants/{tenant}/hashes.json"),
+        serde_json::to_vec_pretty(&hashes).unwrap_or_default(),
+    ));
+
+    out
+}
+
+fn scoped_tenants(worker_root: &Path, scope: &Filter) -> Result<Vec<String>> {
+    let modules = worker_root.join("modules");
+
+    let mut all: Vec<String> = fs::read_dir(&modules)
+        .with_context(|| format!("reading {}", modules.display()))?
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    all.sort();
+
+    for t in scope.tenants {
+        if !all.contains(t) {
+            bail!("unknown tenant '{t}'");
+        }
+    }
+
+    Ok(all.into_iter().filter(|t| scope.has_tenant(Some(t))).collect())
+}
+
+/// Collect decisions, timeline, anomaly data, tenant manifests and hashes,
+/// the console audit log and a system scan for `scope` into a zip whose
+/// manifest of per-file SHA-256 hashes is signed by the operator.
+pub fn export(worker_root: &Path, scope: &EvidenceScope, op: &Operator, dest: &Path) -> Result<()> {
+    let filter = scope.filter()?;
+
+    let mut files: Vec<(String, Vec<u8>)> = vec![(
+        "logs/guardian_decisions.jsonl".into(),
+        filter_jsonl(decisions::raw_lines(worker_root), &filter),
+    )];
+
+    // Nothing keeps a timeline on disk; it is built here from the same
+    // sources as the timeline view.
+    let events: Vec<Value> = timeline::collect(worker_root)
+        .iter()
+        .filter_map(|e| serde_json::to_value(e).ok())
+        .filter(|e| filter.matches(e))
+        .collect();
+    files.push(("logs/timeline.json".into(), serde_json::to_vec_pretty(&events)?));
+
+    if let Some(b) = report(worker_root, "logs/anomaly_drift.json", &filter) {
+        files.push(("logs/anomaly_drift.json".into(), b));
+    }
+
+    let audit_lines = retention::read_lines(worker_root, retention::CONSOLE_AUDIT);
+    files.push(("logs/console_audit.jsonl".into(), filter_jsonl(audit_lines, &filter)));
+
+    for t in scoped_tenants(worker_root, &filter)? {
+        files.extend(tenant_evidence(worker_root, &t));
+    }
+
+    files.push((
+        "system_scan.json".into(),
+        serde_json::to_vec_pretty(&crate::system_scan(worker_root))?,
+    ));
+
+    let manifest = EvidenceManifest {
+        format: EVIDENCE_FORMAT.into(),
+        generated_at: Utc::now().to_rfc3339(),
+        generated_by: op.name.clone(),
+        signer_fingerprint: op.fingerprint.clone(),
+        signer_public_key: op.public_key_b64(),
+        scope: scope.clone(),
+        files: bundle::file_entries(&files),
+    };
+
+    bundle::write_signed_zip(
+        dest,
+        &files,
+        (MANIFEST_FILE, MANIFEST_SIG_FILE),
+        &serde_json::to_vec_pretty(&manifest)?,
+        op,
+    )
+}
+
+/// Worker report files picked in the UI (`worker://` paths), zipped as
+/// they are with the same signed manifest as an evidence bundle.
+pub fn export_reports(worker_root: &Path, masked: &[String], op: &Operator, dest: &Path) -> Result<()> {
+    if masked.is_empty() {
+        bail!("no files selected");
+    }
+
+    let mut files: Vec<(String, Vec<u8>)> = vec![];
+    for m in masked {
+        let (rel, real) = runtime_path::resolve_readable(worker_root, m)?;
+        if files.iter().any(|(n, _)| *n == rel) {
+            continue;
+        }
+        files.push((rel, fs::read(&real).with_context(|| format!("reading {m}"))?));
+    }
+
+    let manifest = EvidenceManifest {
+        format: REPORTS_FORMAT.into(),
+        generated_at: Utc::now().to_rfc3339(),
+        generated_by: op.name.clone(),
+        signer_fingerprint: op.fingerprint.clone(),
+        signer_public_key: op.public_key_b64(),
+        scope: EvidenceScope::default(),
+        files: bundle::file_entries(&files),
+    };
+
+    bundle::write_signed_zip(
+        dest,
+        &files,
+        (MANIFEST_FILE, MANIFEST_SIG_FILE),
+        &serde_json::to_vec_pretty(&manifest)?,
+        op,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use zip::ZipArchive;
+
+    fn decision(ts: &str, tenant: &str) -> String {
+        decisions::decision_json(serde_json::json!({ "timestamp": ts, "tenant": tenant, "threat_score": 10 })).to_string()
+    }
+
+    fn audit(ts: &str, tenant: Option<&str>) -> String {
+        serde_json::json!({ "timestamp": ts, "action": "tenant_stopped", "tenant": tenant, "actor": "ops" }).to_string()
+    }
+
+    fn read(zip: &mut ZipArchive<fs::File>, name: &str) -> String {
+        let mut s = String::new();
+        zip.by_name(name).unwrap().read_to_string(&mut s).unwrap();
+        s
+    }
+
+    #[test]
+    fn export_keeps_only_the_scoped_tenants_and_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        for (t, ts) in [("t1", "2026-02-01T00:00:00Z"), ("t2", "2026-02-01T00:00:00Z")] {
+            fs::create_dir_all(root.join("modules").join(t)).unwrap();
+            fs::write(
+                root.join("modules").join(t).join("manifest.json"),
+                serde_json::json!({ "ingestion": { "channel": "manual", "source": "gui", "timestamp": ts } }).to_string(),
+            )
+            .unwrap();
+        }
+
+        fs::create_dir_all(root.join("logs")).unwrap();
+        let decisions = [
+            decision("2026-01-01T00:00:00Z", "t1"),
+            decision("2026-02-02T00:00:00Z", "t1"),
+            decision("2026-02-02T00:00:00Z", "t2"),
+            decision("2026-04-01T00:00:00Z", "t1"),
+        ];
+        fs::write(root.join("logs/guardian_decisions.jsonl"), decisions.join("\n") + "\n").unwrap();
+
+        let audits = [
+            audit("2026-02-03T00:00:00Z", Some("t1")),
+            audit("2026-02-03T00:00:00Z", Some("t2")),
+            audit("2026-02-04T00:00:00Z", None),
+            audit("2026-05-01T00:00:00Z", None),
+        ];
+        fs::write(root.join("logs/console_audit.jsonl"), audits.join("\n") + "\n").unwrap();
+
+        let scope = EvidenceScope {
+            from: Some("2026-01-15T00:00:00Z".into()),
+            to: Some("2026-03-01T00:00:00Z".into()),
+            tenants: vec!["t1".into()],
+        };
+        let op = Operator::ephemeral("alice");
+        let dest = root.join("exports/evidence.zip");
+
+        let bad = EvidenceScope { tenants: vec!["nope".into()], ..scope.clone() };
+        assert!(export(root, &bad, &op, &dest).is_err());
+        let reversed = EvidenceScope { from: scope.to.clone(), to: scope.from.clone(), tenants: vec![] };
+        assert!(export(root, &reversed, &op, &dest).is_err());
+
+        export(root, &scope, &op, &dest).unwrap();
+        let mut zip = ZipArchive::new(fs::File::open(&dest).unwrap()).unwrap();
+
+        assert_eq!(read(&mut zip, "logs/guardian_decisions.jsonl"), decisions[1].clone() + "\n");
+        assert_eq!(
+            read(&mut zip, "logs/console_audit.jsonl"),
+            format!("{}\n{}\n", audits[0], audits[2])
+        );
+
+        let names: Vec<String> = zip.file_names().map(|n| n.to_string()).collect();
+        assert!(names.contains(&"tenants/t1/manifest.json".to_string()));
+        assert!(!names.iter().any(|n| n.starts_with("tenants/t2/")));
+
+        let events: Vec<timeline::TimelineEvent> =
+            serde_json::from_str(&read(&mut zip, "logs/timeline.json")).unwrap();
+        let got: Vec<(&str, Option<&str>)> =
+            events.iter().map(|e| (e.timestamp.as_str(), e.tenant.as_deref())).collect();
+        assert_eq!(
+            got,
+            vec![
+                ("2026-02-04T00:00:00Z", None),
+                ("2026-02-03T00:00:00Z", Some("t1")),
+                ("2026-02-02T00:00:00Z", Some("t1")),
+                ("2026-02-01T00:00:00Z", Some("t1")),
+            ]
+        );
+
+        let manifest: EvidenceManifest = serde_json::from_reader(zip.by_name(MANIFEST_FILE).unwrap()).unwrap();
+        assert_eq!(manifest.format, EVIDENCE_FORMAT);
+        assert!(manifest.files.iter().any(|f| f.name == "logs/timeline.json"));
+    }
+
+    #[test]
+    fn exports_only_readable_reports() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("logs")).unwrap();
+        fs::create_dir_all(root.join("state")).unwrap();
+        fs::write(root.join("logs/nightcore_dashboard.html"), "<html/>").unwrap();
+        fs::write(root.join("state/operators.json"), "{}").unwrap();
+
+        let op = Operator::ephemeral("alice");
+        let dest = root.join("exports/reports.zip");
+
+        for bad in ["worker://state/operators.json", "worker://logs/../state/operators.json", "/etc/passwd"] {
+            assert!(export_reports(root, &[bad.into()], &op, &dest).is_err(), "{bad}");
+        }
+        assert!(export_reports(root, &[], &op, &dest).is_err());
+        assert!(!dest.exists());
+
+        export_reports(root, &["worker://logs/nightcore_dashboard.html".into()], &op, &dest).unwrap();
+
+        let mut zip = ZipArchive::new(fs::File::open(&dest).unwrap()).unwrap();
+        let names: Vec<&str> = zip.file_names().collect();
+        assert!(names.contains(&"logs/nightcore_dashboard.html"));
+        assert!(names.contains(&MANIFEST_SIG_FILE));
+
+        let manifest: EvidenceManifest = serde_json::from_reader(zip.by_name(MANIFEST_FILE).unwrap()).unwrap();
+        assert_eq!(manifest.format, REPORTS_FORMAT);
+        assert_eq!(manifest.files[0].sha256, sha256_hex(b"<html/>"));
+    }
+}
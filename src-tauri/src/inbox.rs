@@ -1,11 +1,169 @@
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use anyhow::Result;
 
+use crate::decisions;
+use crate::submission::{self, AgentProvenance};
+use crate::tenant_id;
+use crate::threat::{self, RiskPrediction, StaticSignals};
+use crate::wasm_meta;
+
 #[derive(Debug)]
 pub struct InboxEntry {
     pub tenant: String,
     pub timestamp: String,
     pub path: PathBuf,
+    pub provenance: Option<AgentProvenance>,
+    pub triage: Option<InboxTriage>,
+    /// Held by an operator; cannot be approved until resumed.
+    pub suspended: bool,
+}
+
+/// What an approver sees before deciding.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InboxTriage {
+    pub size_bytes: u64,
+    pub sha256: String,
+    pub wasi_imports: Vec<String>,
+    pub predicted_risk: RiskPrediction,
+
+    pub seen_before: bool,
+    pub rejected_before: bool,
+
+    pub previous_tenant: Option<String>,
+    pub imports_added: Vec<String>,
+    pub imports_removed: Vec<String>,
+}
+
+/// Hash history across the decision log and every other tenant.
+#[derive(Default)]
+struct TriageIndex {
+    seen: HashSet<String>,
+    rejected: HashSet<String>,
+    // sha -> tenants holding it, to exclude the entry itself
+    holders: HashMap<String, Vec<String>>,
+    // agent_id -> (submitted_at, tenant, path)
+    by_agent: HashMap<String, Vec<(String, String, PathBuf)>>,
+}
+
+fn build_triage_index(worker_root: &Path) -> TriageIndex {
+    let mut idx = TriageIndex::default();
+
+    for line in decisions::raw_lines(worker_root) {
+        let Ok(v) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        let Some(sha) = v.get("sha256").and_then(|s| s.as_str()) else {
+            continue;
+        };
+        idx.seen.insert(sha.to_lowercase());
+        if v.get("decision").and_then(|d| d.as_str()) == Some("deny") {
+            idx.rejected.insert(sha.to_lowercase());
+        }
+    }
+
+    let Ok(entries) = std::fs::read_dir(worker_root.join("modules")) else {
+        return idx;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let tenant = entry.file_name().to_string_lossy().to_string();
+
+        let manifest = std::fs::read_to_string(path.join("manifest.json"))
+            .ok()
+            .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok());
+
+        let Some(sha) = tenant_id::tenant_sha(&path) else {
+            continue;
+        };
+
+        idx.holders.entry(sha.clone()).or_default().push(tenant.clone());
+
+        if let Some(m) = &manifest {
+            if m.get("rejected").and_then(|b| b.as_bool()).unwrap_or(false) {
+                idx.rejected.insert(sha.clone());
+            }
+            if let Some(p) = submission::read_provenance(m) {
+                idx.by_agent
+                    .entry(p.agent_id)
+                    .or_default()
+                    .push((p.submitted_at, tenant, path.clone()));
+            }
+        }
+    }
+
+    idx
+}
+
+fn policy_exists(worker_root: &Path, tenant: &str) -> bool {
+    let dir = worker_root.join("guardian").join("policies");
+    dir.join(format!("{tenant}.json")).exists() || dir.join("_default.json").exists()
+}
+
+fn triage_entry(
+    worker_root: &Path,
+    idx: &TriageIndex,
+    tenant: &str,
+    path: &Path,
+    provenance: Option<&AgentProvenance>,
+) -> Option<InboxTriage> {
+    let facts = wasm_meta::inspect_module(&path.join("module.wasm")).ok()?;
+
+    let seen_elsewhere = idx
+        .holders
+        .get(&facts.sha256)
+        .map(|t| t.iter().any(|x| x != tenant))
+        .unwrap_or(false);
+    let seen_before = seen_elsewhere || idx.seen.contains(&facts.sha256);
+
+    let predicted_risk = threat::predict_static(&StaticSignals {
+        first_seen: !seen_before,
+        wasi_fs_access: wasm_meta::wasi_fs_access(&facts.imports),
+        wasi_net_access: wasm_meta::wasi_net_access(&facts.imports),
+        wasm_size_bytes: facts.size_bytes,
+        policy_exists: policy_exists(worker_root, tenant),
+        trusted_signer: provenance.map(|p| p.signature_verified).unwrap_or(false),
+    });
+
+    let previous = provenance.and_then(|p| {
+        idx.by_agent
+            .get(&p.agent_id)?
+            .iter()
+            .filter(|(at, t, _)| t != tenant && *at < p.submitted_at)
+            .max_by(|a, b| a.0.cmp(&b.0))
+            .cloned()
+    });
+
+    let (previous_tenant, imports_added, imports_removed) = match previous {
+        Some((_, prev_tenant, prev_path)) => {
+            let prev = wasm_meta::inspect_module(&prev_path.join("module.wasm"))
+                .map(|f| f.imports)
+                .unwrap_or_default();
+
+            let added = facts.imports.iter().filter(|i| !prev.contains(i)).cloned().collect();
+            let removed = prev.iter().filter(|i| !facts.imports.contains(i)).cloned().collect();
+
+            (Some(prev_tenant), added, removed)
+        }
+        None => (None, vec![], vec![]),
+    };
+
+    Some(InboxTriage {
+        size_bytes: facts.size_bytes,
+        rejected_before: idx.rejected.contains(&facts.sha256),
+        sha256: facts.sha256,
+        wasi_imports: facts.imports,
+        predicted_risk,
+        seen_before,
+        previous_tenant,
+        imports_added,
+        imports_removed,
+    })
 }
 
 /// GUI-side adapter for the worker inbox
@@ -19,6 +177,8 @@ pub fn scan_system_inbox(worker_root: &Path) -> Result<Vec<InboxEntry>> {
         return Ok(out);
     }
 
+    let idx = build_triage_index(worker_root);
+
     for entry in std::fs::read_dir(&modules_dir)? {
         let entry = entry?;
         let path = entry.path();
@@ -30,10 +190,14 @@ pub fn scan_system_inbox(worker_root: &Path) -> Result<Vec<InboxEntry>> {
         let manifest_path = path.join("manifest.json");
 
         if !manifest_path.exists() {
+            let triage = triage_entry(worker_root, &idx, &tenant, &path, None);
             out.push(InboxEntry {
                 tenant,
                 timestamp: "unknown".into(),
                 path,
+                provenance: None,
+                triage,
+                suspended: false,
             });
             continue;
         }
@@ -42,6 +206,8 @@ pub fn scan_system_inbox(worker_root: &Path) -> Result<Vec<InboxEntry>> {
         let v: serde_json::Value = serde_json::from_str(&raw)?;
 
         let approved = v.get("approved").and_then(|b| b.as_bool()).unwrap_or(false);
+        let rejected = v.get("rejected").and_then(|b| b.as_bool()).unwrap_or(false);
+        let suspended = v.get("suspended").is_some_and(|s| !s.is_null());
         let channel = v
             .get("ingestion")
             .and_then(|i| i.get("channel"))
@@ -55,11 +221,16 @@ pub fn scan_system_inbox(worker_root: &Path) -> Result<Vec<InboxEntry>> {
             .unwrap_or("unknown")
             .to_string();
 
-        if channel != "manual" && !approved {
+        if channel != "manual" && !approved && !rejected {
+            let provenance = submission::read_provenance(&v);
+            let triage = triage_entry(worker_root, &idx, &tenant, &path, provenance.as_ref());
             out.push(InboxEntry {
                 tenant,
                 timestamp: ts,
                 path,
+                provenance,
+                triage,
+                suspended,
             });
         }
     }
@@ -0,0 +1,887 @@
+use serde::{Serialize, Deserialize};
+use std::{
+    fs,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::audit::AuditEntry;
+use crate::retention;
+use crate::revisions::RevisionRecord;
+use crate::submission::sha256_hex;
+use crate::GuardianDecisionLite;
+
+/// Bumped when the schema changes; an index of another version is
+/// dropped and rebuilt from the logs.
+const SCHEMA_VERSION: i64 = 2;
+
+/// Console audit actions that make up a tenant's approval history.
+const APPROVAL_ACTIONS: [&str; 6] = [
+    "tenant_approved",
+    "quorum_approval_recorded",
+    "tenant_rejected",
+    "trust_renewed",
+    "tenant_suspended",
+    "tenant_resumed",
+];
+
+/// Console audit actions that change which revision a tenant runs.
+const REVISION_ACTIONS: [&str; 3] = ["revision_added", "revision_promoted", "revision_rolled_back"];
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS meta (
+    key   TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS decisions (
+    line_sha           TEXT PRIMARY KEY,
+    timestamp          TEXT NOT NULL,
+    tenant             TEXT NOT NULL,
+    backend            TEXT NOT NULL,
+    decision           TEXT NOT NULL,
+    reason             TEXT NOT NULL,
+    threat_score       INTEGER NOT NULL,
+    sha256             TEXT NOT NULL,
+    first_seen         INTEGER NOT NULL,
+    known_sha          INTEGER NOT NULL,
+    wasm_size_bytes    INTEGER NOT NULL,
+    memory_request_mb  INTEGER NOT NULL,
+    runtime_request_ms INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS decisions_sha ON decisions (sha256);
+CREATE INDEX IF NOT EXISTS decisions_tenant_time ON decisions (tenant, timestamp);
+CREATE INDEX IF NOT EXISTS decisions_backend ON decisions (backend);
+CREATE TABLE IF NOT EXISTS segments (
+    name TEXT PRIMARY KEY
+);
+CREATE TABLE IF NOT EXISTS tenants (
+    tenant        TEXT PRIMARY KEY,
+    display_name  TEXT,
+    sha256        TEXT,
+    channel       TEXT,
+    source        TEXT,
+    imported_at   TEXT,
+    approved      INTEGER NOT NULL,
+    approved_at   TEXT,
+    approved_by   TEXT
+);
+CREATE TABLE IF NOT EXISTS revisions (
+    tenant     TEXT NOT NULL,
+    sha256     TEXT NOT NULL,
+    added_at   TEXT NOT NULL,
+    added_by   TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    active     INTEGER NOT NULL,
+    PRIMARY KEY (tenant, sha256)
+);
+CREATE TABLE IF NOT EXISTS tenant_events (
+    line_sha  TEXT PRIMARY KEY,
+    timestamp TEXT NOT NULL,
+    tenant    TEXT NOT NULL,
+    action    TEXT NOT NULL,
+    actor     TEXT NOT NULL,
+    detail    TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS tenant_events_tenant_time ON tenant_events (tenant, timestamp);
+";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimeBucket {
+    pub bucket: String,
+    pub total: u64,
+    pub allows: u64,
+    pub denies: u64,
+    pub avg_score: f64,
+    pub max_score: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShaEvent {
+    pub timestamp: String,
+    pub tenant: String,
+    pub backend: String,
+    pub decision: String,
+    pub reason: String,
+    pub threat_score: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackendStats {
+    pub backend: String,
+    pub total: u64,
+    pub allows: u64,
+    pub denies: u64,
+    pub avg_score: f64,
+    pub avg_runtime_request_ms: f64,
+    pub avg_memory_request_mb: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShaAnalytics {
+    pub sha256: String,
+    pub tenants: Vec<String>,
+    pub first_decision: String,
+    pub last_decision: String,
+    pub decisions: u64,
+    pub denies: u64,
+    /// Decisions the worker flagged as the first sighting of this hash.
+    pub first_seen_flags: u64,
+    /// The worker knew this hash at least once.
+    pub known: bool,
+}
+
+/// A console audit entry about one tenant.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TenantEvent {
+    pub timestamp: String,
+    pub action: String,
+    pub actor: String,
+    pub detail: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TenantRevision {
+    pub sha256: String,
+    pub added_at: String,
+    pub added_by: String,
+    pub size_bytes: u64,
+    pub active: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScorePoint {
+    pub timestamp: String,
+    pub sha256: String,
+    pub backend: String,
+    pub decision: String,
+    pub threat_score: u8,
+}
+
+/// What the index knows about one tenant. Manifest fields are empty for
+/// a tenant that has been deleted; its logged history is kept.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TenantHistory {
+    pub tenant: String,
+    pub present: bool,
+    pub display_name: Option<String>,
+    pub sha256: Option<String>,
+    pub channel: Option<String>,
+    pub source: Option<String>,
+    pub imported_at: Option<String>,
+    pub approved: bool,
+    pub approved_at: Option<String>,
+    pub approved_by: Option<String>,
+    /// Approvals, rejections, renewals and suspensions, oldest first.
+    pub approvals: Vec<TenantEvent>,
+    /// Stored revisions, oldest first.
+    pub revisions: Vec<TenantRevision>,
+    /// Revisions added, promoted and rolled back, oldest first.
+    pub revision_events: Vec<TenantEvent>,
+    /// Guardian scores, oldest first.
+    pub scores: Vec<ScorePoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SyncReport {
+    pub decisions_added: u64,
+    pub audit_entries_added: u64,
+    pub segments_indexed: u64,
+    pub tenants: u64,
+}
+
+pub fn db_path(worker_root: &Path) -> PathBuf {
+    worker_root.join("state").join("index.sqlite")
+}
+
+fn meta_get(conn: &Connection, key: &str) -> Result<Option<String>> {
+    Ok(conn
+        .query_row("SELECT value FROM meta WHERE key = ?1", [key], |r| r.get(0))
+        .optional()?)
+}
+
+fn meta_set(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO meta (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+pub fn open(worker_root: &Path) -> Result<Connection> {
+    let p = db_path(worker_root);
+    if let Some(dir) = p.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let conn = Connection::open(&p).with_context(|| format!("opening {}", p.display()))?;
+    conn.execute_batch(SCHEMA)?;
+
+    let version = meta_get(&conn, "schema_version")?.and_then(|v| v.parse::<i64>().ok());
+    if version != Some(SCHEMA_VERSION) {
+        drop(conn);
+        return recreate(worker_root);
+    }
+    Ok(conn)
+}
+
+fn recreate(worker_root: &Path) -> Result<Connection> {
+    let p = db_path(worker_root);
+    if let Some(dir) = p.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let _ = fs::remove_file(&p);
+
+    let conn = Connection::open(&p).with_context(|| format!("opening {}", p.display()))?;
+    conn.execute_batch(SCHEMA)?;
+    meta_set(&conn, "schema_version", &SCHEMA_VERSION.to_string())?;
+    Ok(conn)
+}
+
+/// Identical lines are the same event; the line hash is the key, so
+/// re-reading a segment or the active file never double counts.
+fn insert_decision(conn: &Connection, line: &str) -> Result<bool> {
+    let Ok(d) = serde_json::from_str::<GuardianDecisionLite>(line) else {
+        return Ok(false);
+    };
+
+    let n = conn.execute(
+        "INSERT OR IGNORE INTO decisions (
+            line_sha, timestamp, tenant, backend, decision, reason, threat_score,
+            sha256, first_seen, known_sha, wasm_size_bytes, memory_request_mb, runtime_request_ms
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        params![
+            sha256_hex(line.as_bytes()),
+            d.timestamp,
+            d.tenant,
+            d.backend,
+            d.decision,
+            d.reason,
+            d.threat_score,
+            d.sha256.to_lowercase(),
+            d.first_seen,
+            d.known_sha,
+            d.wasm_size_bytes as i64,
+            d.memory_request_mb as i64,
+            d.runtime_request_ms as i64,
+        ],
+    )?;
+    Ok(n > 0)
+}
+
+/// Only entries naming a tenant are kept; the rest of the audit log is
+/// not queried through the index.
+fn insert_audit(conn: &Connection, line: &str) -> Result<bool> {
+    let Ok(a) = serde_json::from_str::<AuditEntry>(line) else {
+        return Ok(false);
+    };
+    let Some(tenant) = a.tenant else {
+        return Ok(false);
+    };
+
+    let n = conn.execute(
+        "INSERT OR IGNORE INTO tenant_events (line_sha, timestamp, tenant, action, actor, detail)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            sha256_hex(line.as_bytes()),
+            a.timestamp,
+            tenant,
+            a.action,
+            a.actor,
+            a.detail.to_string(),
+        ],
+    )?;
+    Ok(n > 0)
+}
+
+/// How each indexed log's lines are stored.
+type Insert = fn(&Connection, &str) -> Result<bool>;
+
+/// Hash of the bytes at both ends of the span already indexed, up to
+/// 4 KiB each. Rotation renames the file aside, so a file that no longer
+/// matches is a different file, even if it has grown past the offset.
+fn indexed_mark(f: &mut fs::File, offset: u64) -> Result<String> {
+    let k = offset.min(4096);
+    let mut bytes = Vec::new();
+    for start in [0, offset - k] {
+        f.seek(SeekFrom::Start(start))?;
+        (&mut *f).take(k).read_to_end(&mut bytes)?;
+    }
+    Ok(sha256_hex(&bytes))
+}
+
+/// Read an active log from the last indexed offset. The offset is reset
+/// when the file shrank or the indexed span no longer matches, i.e. it
+/// was rotated.
+fn tail_active(conn: &Connection, worker_root: &Path, log: &str, insert: Insert) -> Result<u64> {
+    let path = retention::active_path(worker_root, log);
+    let Ok(mut f) = fs::File::open(&path) else {
+        return Ok(0);
+    };
+
+    let len = f.metadata()?.len();
+
+    let offset_key = format!("{log}.offset");
+    let mark_key = format!("{log}.mark");
+
+    let mut offset: u64 = meta_get(conn, &offset_key)?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    if offset > len || meta_get(conn, &mark_key)? != Some(indexed_mark(&mut f, offset)?) {
+        offset = 0;
+    }
+
+    f.seek(SeekFrom::Start(offset))?;
+    let mut buf = Vec::new();
+    f.read_to_end(&mut buf)?;
+
+    // Only complete lines; a partial one is picked up next time.
+    let complete = buf.iter().rposition(|b| *b == b'\n').map(|i| i + 1).unwrap_or(0);
+
+    let mut added = 0;
+    for line in String::from_utf8_lossy(&buf[..complete]).lines() {
+        if !line.trim().is_empty() && insert(conn, line)? {
+            added += 1;
+        }
+    }
+
+    let offset = offset + complete as u64;
+    meta_set(conn, &offset_key, &offset.to_string())?;
+    meta_set(conn, &mark_key, &indexed_mark(&mut f, offset)?)?;
+    Ok(added)
+}
+
+fn sync_segments(conn: &Connection, worker_root: &Path, log: &str, insert: Insert) -> Result<(u64, u64)> {
+    let mut added = 0;
+    let mut indexed = 0;
+
+    for seg in retention::segments(worker_root, log) {
+        let name = seg.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let known: Option<String> = conn
+            .query_row("SELECT name FROM segments WHERE name = ?1", [&name], |r| r.get(0))
+            .optional()?;
+        if known.is_some() {
+            continue;
+        }
+
+        for line in retention::read_segment_lines(&seg)? {
+            if insert(conn, &line)? {
+                added += 1;
+            }
+        }
+        conn.execute("INSERT INTO segments (name) VALUES (?1)", [&name])?;
+        indexed += 1;
+    }
+
+    Ok((added, indexed))
+}
+
+/// Manifests and revision records are small and few; they are re-read
+/// in full on every sync.
+fn sync_tenants(conn: &Connection, worker_root: &Path) -> Result<u64> {
+    conn.execute("DELETE FROM tenants", [])?;
+    conn.execute("DELETE FROM revisions", [])?;
+
+    let Ok(entries) = fs::read_dir(worker_root.join("modules")) else {
+        return Ok(0);
+    };
+
+    let mut n = 0;
+    for e in entries.flatten() {
+        let dir = e.path();
+        if !dir.is_dir() {
+            continue;
+        }
+        let tenant = e.file_name().to_string_lossy().to_string();
+
+        let m: serde_json::Value = fs::read_to_string(dir.join("manifest.json"))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        let s = |v: Option<&serde_json::Value>| v.and_then(|x| x.as_str()).map(|x| x.to_string());
+
+        conn.execute(
+            "INSERT INTO tenants (tenant, display_name, sha256, channel, source, imported_at, approved, approved_at, approved_by)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                tenant,
+                s(m.get("display_name")),
+                crate::tenant_id::tenant_sha(&dir),
+                s(m.pointer("/ingestion/channel")),
+                s(m.pointer("/ingestion/source")),
+                s(m.pointer("/ingestion/timestamp")),
+                m.get("approved").and_then(|b| b.as_bool()).unwrap_or(false),
+                s(m.get("approved_at")),
+                s(m.get("approved_by")),
+            ],
+        )?;
+
+        let active = s(m.get("active_revision"));
+        for rev in fs::read_dir(dir.join("revisions")).into_iter().flatten().flatten() {
+            let Some(r) = fs::read_to_string(rev.path().join("revision.json"))
+                .ok()
+                .and_then(|raw| serde_json::from_str::<RevisionRecord>(&raw).ok())
+            else {
+                continue;
+            };
+            conn.execute(
+                "INSERT OR IGNORE INTO revisions (tenant, sha256, added_at, added_by, size_bytes, active)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    tenant,
+                    r.sha256,
+                    r.added_at,
+                    r.added_by,
+                    r.size_bytes as i64,
+                    active.as_deref() == Some(r.sha256.as_str()),
+                ],
+            )?;
+        }
+        n += 1;
+    }
+
+    Ok(n)
+}
+
+/// Bring the index up to date with the logs and manifests.
+pub fn sync(conn: &mut Connection, worker_root: &Path) -> Result<SyncReport> {
+    let tx = conn.transaction()?;
+
+    let log = retention::GUARDIAN_DECISIONS;
+    let (from_segments, decision_segments) = sync_segments(&tx, worker_root, log, insert_decision)?;
+    let from_active = tail_active(&tx, worker_root, log, insert_decision)?;
+
+    let log = retention::CONSOLE_AUDIT;
+    let (audit_segments, audit_segments_indexed) = sync_segments(&tx, worker_root, log, insert_audit)?;
+    let audit_active = tail_active(&tx, worker_root, log, insert_audit)?;
+
+    let tenants = sync_tenants(&tx, worker_root)?;
+
+    tx.commit()?;
+
+    Ok(SyncReport {
+        decisions_added: from_segments + from_active,
+        audit_entries_added: audit_segments + audit_active,
+        segments_indexed: decision_segments + audit_segments_indexed,
+        tenants,
+    })
+}
+
+/// Open and sync, the entry point for every query command.
+pub fn open_synced(worker_root: &Path) -> Result<Connection> {
+    let mut conn = open(worker_root)?;
+    sync(&mut conn, worker_root)?;
+    Ok(conn)
+}
+
+/// Throw the index away and rebuild it from the JSONL files.
+pub fn rebuild(worker_root: &Path) -> Result<SyncReport> {
+    let mut conn = recreate(worker_root)?;
+    sync(&mut conn, worker_root)
+}
+
+/// Decision counts per hour or day, optionally for one tenant.
+pub fn decisions_over_time(
+    conn: &Connection,
+    tenant: Option<&str>,
+    by_day: bool,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<Vec<TimeBucket>> {
+    // RFC 3339 prefixes: `YYYY-MM-DD` or `YYYY-MM-DDTHH`.
+    let width = if by_day { 10 } else { 13 };
+
+    let mut stmt = conn.prepare(
+        "SELECT substr(timestamp, 1, ?1) AS bucket,
+                COUNT(*),
+                SUM(decision = 'allow'),
+                SUM(decision = 'deny'),
+                AVG(threat_score),
+                MAX(threat_score)
+         FROM decisions
+         WHERE (?2 IS NULL OR tenant = ?2)
+           AND (?3 IS NULL OR timestamp >= ?3)
+           AND (?4 IS NULL OR timestamp <= ?4)
+         GROUP BY bucket
+         ORDER BY bucket",
+    )?;
+
+    let rows = stmt.query_map(params![width, tenant, from, to], |r| {
+        Ok(TimeBucket {
+            bucket: r.get(0)?,
+            total: r.get(1)?,
+            allows: r.get(2)?,
+            denies: r.get(3)?,
+            avg_score: r.get(4)?,
+            max_score: r.get(5)?,
+        })
+    })?;
+
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+/// Every decision for one module hash, across tenants, oldest first.
+pub fn sha_history(conn: &Connection, sha256: &str) -> Result<Vec<ShaEvent>> {
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, tenant, backend, decision, reason, threat_score
+         FROM decisions WHERE sha256 = ?1 ORDER BY timestamp",
+    )?;
+
+    let rows = stmt.query_map([sha256.to_lowercase()], |r| {
+        Ok(ShaEvent {
+            timestamp: r.get(0)?,
+            tenant: r.get(1)?,
+            backend: r.get(2)?,
+            decision: r.get(3)?,
+            reason: r.get(4)?,
+            threat_score: r.get(5)?,
+        })
+    })?;
+
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+pub fn backend_stats(conn: &Connection) -> Result<Vec<BackendStats>> {
+    let mut stmt = conn.prepare(
+        "SELECT backend, COUNT(*), SUM(decision = 'allow'), SUM(decision = 'deny'),
+                AVG(threat_score), AVG(runtime_request_ms), AVG(memory_request_mb)
+         FROM decisions GROUP BY backend ORDER BY backend",
+    )?;
+
+    let rows = stmt.query_map([], |r| {
+        Ok(BackendStats {
+            backend: r.get(0)?,
+            total: r.get(1)?,
+            allows: r.get(2)?,
+            denies: r.get(3)?,
+            avg_score: r.get(4)?,
+            avg_runtime_request_ms: r.get(5)?,
+            avg_memory_request_mb: r.get(6)?,
+        })
+    })?;
+
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+/// First-seen and known-hash view per module hash, optionally limited to
+/// hashes one tenant has run. Newest activity first.
+pub fn sha_analytics(conn: &Connection, tenant: Option<&str>) -> Result<Vec<ShaAnalytics>> {
+    let mut stmt = conn.prepare(
+        "SELECT sha256, group_concat(DISTINCT tenant), MIN(timestamp), MAX(timestamp),
+                COUNT(*), SUM(decision = 'deny'), SUM(first_seen), MAX(known_sha)
+         FROM decisions
+         WHERE ?1 IS NULL OR sha256 IN (SELECT sha256 FROM decisions WHERE tenant = ?1)
+         GROUP BY sha256
+         ORDER BY MAX(timestamp) DESC",
+    )?;
+
+    let rows = stmt.query_map([tenant], |r| {
+        let tenants: String = r.get(1)?;
+        Ok(ShaAnalytics {
+            sha256: r.get(0)?,
+            tenants: tenants.split(',').map(|s| s.to_string()).collect(),
+            first_decision: r.get(2)?,
+            last_decision: r.get(3)?,
+            decisions: r.get(4)?,
+            denies: r.get(5)?,
+            first_seen_flags: r.get(6)?,
+            known: r.get(7)?,
+        })
+    })?;
+
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+fn tenant_events(conn: &Connection, tenant: &str, actions: &[&str]) -> Result<Vec<TenantEvent>> {
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, action, actor, detail FROM tenant_events
+         WHERE tenant = ?1 AND action IN (SELECT value FROM json_each(?2))
+         ORDER BY timestamp",
+    )?;
+
+    let rows = stmt.query_map(params![tenant, serde_json::to_string(actions)?], |r| {
+        let detail: String = r.get(3)?;
+        Ok(TenantEvent {
+            timestamp: r.get(0)?,
+            action: r.get(1)?,
+            actor: r.get(2)?,
+            detail: serde_json::from_str(&detail).unwrap_or_default(),
+        })
+    })?;
+
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+/// Approval, revision and score history of one tenant. None if the index
+/// has never seen it.
+pub fn tenant_history(conn: &Connection, tenant: &str) -> Result<Option<TenantHistory>> {
+    let mut history = conn
+        .query_row(
+            "SELECT display_name, sha256, channel, source, imported_at, approved, approved_at, approved_by
+             FROM tenants WHERE tenant = ?1",
+            [tenant],
+            |r| {
+                Ok(TenantHistory {
+                    tenant: tenant.into(),
+                    present: true,
+                    display_name: r.get(0)?,
+                    sha256: r.get(1)?,
+                    channel: r.get(2)?,
+                    source: r.get(3)?,
+                    imported_at: r.get(4)?,
+                    approved: r.get(5)?,
+                    approved_at: r.get(6)?,
+                    approved_by: r.get(7)?,
+                    ..Default::default()
+                })
+            },
+        )
+        .optional()?
+        .unwrap_or_else(|| TenantHistory { tenant: tenant.into(), ..Default::default() });
+
+    history.approvals = tenant_events(conn, tenant, &APPROVAL_ACTIONS)?;
+    history.revision_events = tenant_events(conn, tenant, &REVISION_ACTIONS)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT sha256, added_at, added_by, size_bytes, active FROM revisions
+         WHERE tenant = ?1 ORDER BY added_at",
+    )?;
+    history.revisions = stmt
+        .query_map([tenant], |r| {
+            Ok(TenantRevision {
+                sha256: r.get(0)?,
+                added_at: r.get(1)?,
+                added_by: r.get(2)?,
+                size_bytes: r.get(3)?,
+                active: r.get(4)?,
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, sha256, backend, decision, threat_score FROM decisions
+         WHERE tenant = ?1 ORDER BY timestamp",
+    )?;
+    history.scores = stmt
+        .query_map([tenant], |r| {
+            Ok(ScorePoint {
+                timestamp: r.get(0)?,
+                sha256: r.get(1)?,
+                backend: r.get(2)?,
+                decision: r.get(3)?,
+                threat_score: r.get(4)?,
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let seen = history.present
+        || !history.approvals.is_empty()
+        || !history.revision_events.is_empty()
+        || !history.scores.is_empty();
+    Ok(seen.then_some(history))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atomic_fs;
+    use crate::audit;
+    use crate::decisions;
+    use crate::operator::Operator;
+
+    fn decision(ts: &str, tenant: &str, sha: &str, backend: &str, verdict: &str, score: u8) -> String {
+        decisions::decision_json(serde_json::json!({
+            "timestamp": ts, "tenant": tenant, "sha256": sha, "backend": backend, "decision": verdict,
+            "threat_score": score, "first_seen": score == 0, "known_sha": score > 0,
+            "memory_request_mb": 2, "runtime_request_ms": 100,
+        }))
+        .to_string()
+    }
+
+    fn log(root: &Path, line: &str) {
+        let active = retention::active_path(root, retention::GUARDIAN_DECISIONS);
+        atomic_fs::append_line(&active, line).unwrap();
+    }
+
+    fn count(conn: &Connection, table: &str) -> u64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |r| r.get(0)).unwrap()
+    }
+
+    fn tenant(root: &Path, name: &str, manifest: serde_json::Value) {
+        let dir = root.join("modules").join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("module.wasm"), b"\0asm\x01\0\0\0").unwrap();
+        fs::write(dir.join("manifest.json"), manifest.to_string()).unwrap();
+    }
+
+    #[test]
+    fn rebuild_recreates_the_index_from_the_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let op = Operator::ephemeral("ops");
+
+        log(root, &decision("2026-03-01T10:00:00Z", "t1", "aa", "wasmtime", "allow", 10));
+        log(root, &decision("2026-03-01T11:00:00Z", "t1", "aa", "wasmtime", "deny", 80));
+        audit::record_signed(root, &op, "tenant_approved", Some("t1"), serde_json::Value::Null).unwrap();
+        retention::rotate_one(root, retention::GUARDIAN_DECISIONS, &op).unwrap().unwrap();
+        retention::rotate_one(root, retention::CONSOLE_AUDIT, &op).unwrap().unwrap();
+        log(root, &decision("2026-03-02T10:00:00Z", "t2", "bb", "wasmtime", "allow", 5));
+        audit::record_signed(root, &op, "tenant_rejected", Some("t2"), serde_json::Value::Null).unwrap();
+        tenant(root, "t1", serde_json::json!({ "approved": true }));
+
+        let report = rebuild(root).unwrap();
+        assert_eq!(report.decisions_added, 3);
+        assert_eq!(report.audit_entries_added, 2);
+        assert_eq!(report.segments_indexed, 2);
+        assert_eq!(report.tenants, 1);
+
+        // Rebuilding again, or after losing the file, gives the same index.
+        let again = rebuild(root).unwrap();
+        assert_eq!(again.decisions_added, 3);
+        fs::remove_file(db_path(root)).unwrap();
+        let conn = open_synced(root).unwrap();
+        assert_eq!(count(&conn, "decisions"), 3);
+        assert_eq!(count(&conn, "tenant_events"), 2);
+
+        // Syncing an index that is up to date adds nothing.
+        let mut conn = conn;
+        let idle = sync(&mut conn, root).unwrap();
+        assert_eq!((idle.decisions_added, idle.audit_entries_added, idle.segments_indexed), (0, 0, 0));
+    }
+
+    #[test]
+    fn tailing_follows_the_log_across_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let op = Operator::ephemeral("ops");
+        let mut conn = open(root).unwrap();
+
+        log(root, &decision("2026-03-01T10:00:00Z", "t1", "aa", "wasmtime", "allow", 1));
+        log(root, &decision("2026-03-01T10:00:01Z", "t1", "aa", "wasmtime", "allow", 2));
+        assert_eq!(sync(&mut conn, root).unwrap().decisions_added, 2);
+
+        log(root, &decision("2026-03-01T10:00:02Z", "t1", "aa", "wasmtime", "allow", 3));
+        assert_eq!(sync(&mut conn, root).unwrap().decisions_added, 1);
+
+        // A line still being written waits for its newline.
+        let active = retention::active_path(root, retention::GUARDIAN_DECISIONS);
+        let partial = decision("2026-03-01T10:00:03Z", "t1", "aa", "wasmtime", "allow", 4);
+        atomic_fs::append_bytes(&active, partial.as_bytes()).unwrap();
+        assert_eq!(sync(&mut conn, root).unwrap().decisions_added, 0);
+        atomic_fs::append_bytes(&active, b"\n").unwrap();
+        assert_eq!(sync(&mut conn, root).unwrap().decisions_added, 1);
+
+        // After rotation the new file outgrows the old offset before the
+        // next sync; none of its lines may be skipped.
+        retention::rotate_one(root, retention::GUARDIAN_DECISIONS, &op).unwrap().unwrap();
+        for n in 0..6 {
+            log(root, &decision(&format!("2026-03-02T10:00:0{n}Z"), "t1", "aa", "wasmtime", "allow", 10 + n));
+        }
+        let report = sync(&mut conn, root).unwrap();
+        assert_eq!(report.segments_indexed, 1);
+        assert_eq!(report.decisions_added, 6);
+        assert_eq!(count(&conn, "decisions"), 10);
+    }
+
+    #[test]
+    fn decision_queries() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        log(root, &decision("2026-03-01T10:00:00Z", "t1", "AA", "wasmtime", "allow", 10));
+        log(root, &decision("2026-03-01T10:30:00Z", "t1", "aa", "wasmtime", "deny", 90));
+        log(root, &decision("2026-03-01T11:00:00Z", "t2", "aa", "firecracker", "allow", 20));
+        log(root, &decision("2026-03-02T09:00:00Z", "t2", "bb", "wasmtime", "allow", 0));
+        let conn = open_synced(root).unwrap();
+
+        let days = decisions_over_time(&conn, None, true, None, None).unwrap();
+        let summary: Vec<_> = days.iter().map(|b| (b.bucket.as_str(), b.total, b.denies, b.max_score)).collect();
+        assert_eq!(summary, vec![("2026-03-01", 3, 1, 90), ("2026-03-02", 1, 0, 0)]);
+
+        let hours = decisions_over_time(&conn, Some("t1"), false, None, None).unwrap();
+        assert_eq!(hours.len(), 1);
+        assert_eq!((hours[0].bucket.as_str(), hours[0].total, hours[0].avg_score), ("2026-03-01T10", 2, 50.0));
+
+        let bounded = decisions_over_time(&conn, None, true, Some("2026-03-01T10:15:00Z"), Some("2026-03-01T23:59:59Z")).unwrap();
+        assert_eq!(bounded.iter().map(|b| b.total).sum::<u64>(), 2);
+
+        let history = sha_history(&conn, "AA").unwrap();
+        let seen: Vec<_> = history.iter().map(|e| (e.tenant.as_str(), e.backend.as_str(), e.threat_score)).collect();
+        assert_eq!(seen, vec![("t1", "wasmtime", 10), ("t1", "wasmtime", 90), ("t2", "firecracker", 20)]);
+
+        let stats = backend_stats(&conn).unwrap();
+        let by_backend: Vec<_> = stats.iter().map(|s| (s.backend.as_str(), s.total, s.allows, s.denies)).collect();
+        assert_eq!(by_backend, vec![("firecracker", 1, 1, 0), ("wasmtime", 3, 2, 1)]);
+        assert_eq!(stats[1].avg_runtime_request_ms, 100.0);
+
+        let all = sha_analytics(&conn, None).unwrap();
+        assert_eq!(all.iter().map(|a| a.sha256.as_str()).collect::<Vec<_>>(), vec!["bb", "aa"]);
+        let aa = &all[1];
+        assert_eq!((aa.decisions, aa.denies, aa.first_seen_flags, aa.known), (3, 1, 0, true));
+        let mut tenants = aa.tenants.clone();
+        tenants.sort();
+        assert_eq!(tenants, vec!["t1", "t2"]);
+        assert_eq!((all[0].first_seen_flags, all[0].known), (1, false));
+
+        let t1 = sha_analytics(&conn, Some("t1")).unwrap();
+        assert_eq!(t1.iter().map(|a| a.sha256.as_str()).collect::<Vec<_>>(), vec!["aa"]);
+    }
+
+    #[test]
+    fn tenant_history_collects_approvals_revisions_and_scores() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let op = Operator::ephemeral("ops");
+
+        tenant(root, "t1", serde_json::json!({
+            "display_name": "Tenant One",
+            "ingestion": { "channel": "manual", "source": "gui", "timestamp": "2026-03-01T09:00:00Z" },
+            "approved": true, "approved_at": "2026-03-01T09:30:00Z", "approved_by": "ops",
+        }));
+        let first = crate::revisions::ensure_initialized(root, "t1").unwrap();
+        let second = crate::revisions::add_revision(root, "t1", b"\0asm\x01\0\0\0\0", "ops").unwrap();
+
+        audit::record_signed(root, &op, "tenant_approved", Some("t1"), serde_json::Value::Null).unwrap();
+        audit::record_signed(root, &op, "revision_added", Some("t1"), serde_json::json!({ "sha256": second.sha256 })).unwrap();
+        audit::record_signed(root, &op, "trust_renewed", Some("t1"), serde_json::json!({ "ttl_hours": 24 })).unwrap();
+        audit::record_signed(root, &op, "tenant_approved", Some("t2"), serde_json::Value::Null).unwrap();
+        audit::record_signed(root, &op, "kill_switch", None, serde_json::Value::Null).unwrap();
+
+        log(root, &decision("2026-03-01T10:00:00Z", "t1", &first, "wasmtime", "allow", 10));
+        log(root, &decision("2026-03-01T11:00:00Z", "t1", &first, "wasmtime", "deny", 70));
+        log(root, &decision("2026-03-01T12:00:00Z", "gone", "cc", "wasmtime", "deny", 95));
+
+        let conn = open_synced(root).unwrap();
+        let h = tenant_history(&conn, "t1").unwrap().unwrap();
+
+        assert!(h.present);
+        assert_eq!(h.display_name.as_deref(), Some("Tenant One"));
+        assert_eq!((h.channel.as_deref(), h.imported_at.as_deref()), (Some("manual"), Some("2026-03-01T09:00:00Z")));
+        assert!(h.approved);
+        assert_eq!(h.approved_by.as_deref(), Some("ops"));
+
+        let approvals: Vec<_> = h.approvals.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(approvals, vec!["tenant_approved", "trust_renewed"]);
+        assert_eq!(h.approvals[1].detail["ttl_hours"], 24);
+        assert_eq!(h.approvals[0].actor, "ops");
+
+        let revisions: Vec<_> = h.revisions.iter().map(|r| (r.sha256.as_str(), r.active)).collect();
+        assert_eq!(revisions, vec![(first.as_str(), true), (second.sha256.as_str(), false)]);
+        assert_eq!(h.revision_events.len(), 1);
+        assert_eq!(h.revision_events[0].detail["sha256"], second.sha256.as_str());
+
+        let scores: Vec<_> = h.scores.iter().map(|s| (s.decision.as_str(), s.threat_score)).collect();
+        assert_eq!(scores, vec![("allow", 10), ("deny", 70)]);
+
+        // Deleted tenants keep their logged history.
+        let gone = tenant_history(&conn, "gone").unwrap().unwrap();
+        assert!(!gone.present);
+        assert_eq!(gone.scores.len(), 1);
+        let t2 = tenant_history(&conn, "t2").unwrap().unwrap();
+        assert_eq!(t2.approvals.len(), 1);
+
+        assert!(tenant_history(&conn, "never").unwrap().is_none());
+    }
+}
@@ -1,5 +1,5 @@
 use serde::{Serialize, Deserialize};
-use std::{env, fs, path::PathBuf};
+use std::{env, fs, path::{Path, PathBuf}};
 
 use tauri_plugin_opener::init as opener_init;
 use tauri_plugin_shell::init as shell_init;
@@ -14,6 +14,7 @@ use pro_api::{
     get_pro_status,
     pro_apply_license,
     pro_deactivate,
+    pro_refresh_license,
     pro_load_policies,
     pro_save_policies,
     pro_list_quarantine,
@@ -26,10 +27,79 @@ use pro_api::{
 // INGESTION + STATE
 // ---------------------------------------------------------
 mod commands;
+use commands::export_evidence::{export_evidence_bundle, export_zip};
+use commands::export_tenant::export_tenant_bundle;
 use commands::import_tenant::import_tenant_from_file;
+use commands::submit_agent::submit_agent_module;
+use commands::revisions::{
+    list_tenant_revisions,
+    import_tenant_revision,
+    promote_tenant_revision,
+    rollback_tenant_revision,
+    compare_tenant_revisions,
+};
+use commands::anomaly::{run_anomaly_engine, get_anomaly_report};
+use commands::timeline::get_timeline;
+use commands::runs::{list_runs, get_run};
+use commands::tenant_control::{stop_tenant, suspend_tenant, resume_tenant};
+use commands::schedules::{
+    list_schedules,
+    create_schedule,
+    delete_schedule,
+    set_schedule_enabled,
+};
+use commands::diagnostics::{
+    list_backends,
+    test_backend,
+    test_wasmtime_backend,
+    test_firecracker_backend,
+};
+use commands::index::{
+    index_decisions_over_time,
+    index_sha_history,
+    index_backend_stats,
+    index_sha_analytics,
+    index_tenant_history,
+    rebuild_index,
+};
+use commands::retention::{
+    get_retention_config,
+    save_retention_config,
+    rotate_logs,
+    verify_log_seals,
+};
+use commands::operator::{
+    list_operator_profiles,
+    create_operator_profile,
+    set_active_operator,
+};
 
+mod anomaly;
+mod approvals;
+mod atomic_fs;
+mod audit;
+mod backends;
+mod bundle;
+mod decisions;
+mod diagnostics;
+mod evidence;
 mod inbox;
+mod index_db;
+mod license;
+mod operator;
+mod process_registry;
+mod quarantine;
+mod retention;
+mod revisions;
+mod runs;
+mod runtime_path;
+mod scheduler;
+mod submission;
+mod tenant_id;
 mod tenant_state;
+mod threat;
+mod timeline;
+mod wasm_meta;
 
 // ---------------------------------------------------------
 // GUARDIAN STRUCT (MATCHES WORKER)
@@ -62,6 +132,11 @@ pub struct GuardianDecisionLite {
     pub policy_exists: bool,
     pub backend_allowed: bool,
     pub trusted_signer: bool,
+
+    /// Console run that produced the decision, echoed by workers that
+    /// read `NIGHTCORE_RUN_ID`.
+    #[serde(default)]
+    pub run_id: Option<String>,
 }
 
 // ============================================================
@@ -165,14 +240,21 @@ fn get_worker_logs_path(app: tauri::AppHandle) -> Result<String, String> {
 #[tauri::command]
 fn read_runtime_file(rel: String) -> Result<String, String> {
     let root = resolve_worker_runtime_root()?;
-    let path = root.join(rel);
+    runtime_path::read_runtime_text(&root, &rel).map_err(|e| e.to_string())
+}
 
-    if !path.starts_with(&root) {
-        return Err("Invalid runtime file path".into());
-    }
+/// Open a runtime report in the system handler. Only masked
+/// `worker://` paths to html, json or log files inside the root.
+#[tauri::command]
+fn open_path_universal(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    use tauri_plugin_opener::OpenerExt;
 
-    fs::read_to_string(&path)
-        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+    let root = resolve_worker_root(&app)?;
+    let real = runtime_path::resolve_openable(&root, &path).map_err(|e| e.to_string())?;
+
+    app.opener()
+        .open_path(real.to_string_lossy(), None::<&str>)
+        .map_err(|e| format!("Failed to open {path}: {e}"))
 }
 
 // ============================================================
@@ -204,6 +286,9 @@ pub struct FullSystemStatus {
     pub tenants: Vec<TenantInfo>,
     pub logs: LogStatus,
     pub firecracker_installed: bool,
+    pub backends: Vec<backends::BackendInfo>,
+    /// Guardian policies that name unregistered backends or options.
+    pub policy_warnings: Vec<String>,
     pub worker_version: String,
     pub sdk_version: String,
 }
@@ -211,7 +296,11 @@ pub struct FullSystemStatus {
 #[tauri::command]
 async fn get_full_system_scan(app: tauri::AppHandle) -> FullSystemStatus {
     let worker_root = ensure_worker_runtime_dirs(&app).unwrap_or_else(|_| PathBuf::from("unknown"));
+    system_scan(&worker_root)
+}
 
+/// Snapshot behind `get_full_system_scan`, also captured in evidence bundles.
+pub fn system_scan(worker_root: &Path) -> FullSystemStatus {
     let home = env::var("HOME").or_else(|_| env::var("USERPROFILE")).unwrap_or_default();
     let display = worker_root.to_string_lossy().replace(home.as_str(), "~");
 
@@ -250,7 +339,9 @@ async fn get_full_system_scan(app: tauri::AppHandle) -> FullSystemStatus {
         worker_display: display,
         tenants,
         logs: logs_status,
-        firecracker_installed: which::which("firecracker").is_ok(),
+        firecracker_installed: backends::get("firecracker").is_some_and(|b| b.detect(worker_root)),
+        backends: backends::describe_all(worker_root),
+        policy_warnings: backends::policy_warnings(worker_root),
         worker_version: "unknown".into(),
         sdk_version: "v1".into(),
     }
@@ -262,19 +353,20 @@ async fn get_full_system_scan(app: tauri::AppHandle) -> FullSystemStatus {
 #[tauri::command]
 fn get_guardian_decisions(_app: tauri::AppHandle)
 -> Result<Vec<GuardianDecisionLite>, String> {
-    let raw = match read_runtime_file("logs/guardian_decisions.jsonl".into()) {
-        Ok(v) => v,
-        Err(_) => return Ok(vec![]),
-    };
-
-    let mut out = vec![];
-    for line in raw.lines() {
-        if let Ok(v) = serde_json::from_str::<GuardianDecisionLite>(line) {
-            out.push(v);
-        }
-    }
+    let root = resolve_worker_runtime_root()?;
+    Ok(decisions::load_all(&root))
+}
 
-    Ok(out)
+/// Weighted factors behind one decision, found by tenant and timestamp.
+#[tauri::command]
+fn explain_guardian_decision(tenant: String, timestamp: String)
+-> Result<threat::ScoreExplanation, String> {
+    let root = resolve_worker_runtime_root()?;
+    decisions::load_all(&root)
+        .iter()
+        .find(|d| d.tenant == tenant && d.timestamp == timestamp)
+        .map(threat::explain)
+        .ok_or_else(|| format!("no decision for {tenant} at {timestamp}"))
 }
 
 #[tauri::command]
@@ -287,6 +379,19 @@ fn get_tenant_states(app: tauri::AppHandle)
 // ============================================================
 // WORKER COMMAND EXECUTION
 // ============================================================
+/// The worker invoked from the runtime root with the user's HOME.
+fn worker_command(runtime_root: &Path, bin: &Path, args: &[String]) -> std::process::Command {
+    let home = env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .unwrap_or_default();
+
+    let mut cmd = std::process::Command::new(bin);
+    cmd.current_dir(runtime_root)
+        .env("HOME", home)
+        .args(args);
+    cmd
+}
+
 #[tauri::command]
 async fn run_worker_cmd(
     app: tauri::AppHandle,
@@ -295,16 +400,43 @@ async fn run_worker_cmd(
     let runtime_root = ensure_worker_runtime_dirs(&app)?;
     let bin = resolve_bundled_worker_bin(&app)?;
 
-    let home = env::var("HOME")
-        .or_else(|_| env::var("USERPROFILE"))
-        .unwrap_or_default();
+    if let Some(t) = process_registry::arg_value(&args, "--tenant") {
+        runtime_path::check_tenant(t).map_err(|e| e.to_string())?;
+        tenant_state::ensure_not_suspended(&runtime_root, t, "running it").map_err(|e| e.to_string())?;
+        tenant_state::ensure_not_expired(&runtime_root, t).map_err(|e| e.to_string())?;
+    }
+    if let Some(b) = process_registry::arg_value(&args, "--backend") {
+        if backends::get(b).is_none() {
+            return Err(format!("Unknown backend '{b}' (registered: {})", backends::names().join(", ")));
+        }
+    }
+    if args.iter().any(|a| a == "--all") {
+        tenant_state::ensure_none_suspended(&runtime_root).map_err(|e| e.to_string())?;
+        tenant_state::ensure_none_expired(&runtime_root).map_err(|e| e.to_string())?;
+    }
 
-    let output = std::process::Command::new(bin)
-        .current_dir(&runtime_root)
-        .env("HOME", home)
-        .args(args)
-        .output()
-        .map_err(|e| e.to_string())?;
+    let mut cmd = worker_command(&runtime_root, &bin, &args);
+    cmd.stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+
+    let run = runs::start(&args, "console");
+    run.tag(&mut cmd);
+
+    // Tracked so the kill switch can stop this run and nothing else.
+    let output = process_registry::spawn(
+        &runtime_root,
+        &mut cmd,
+        process_registry::arg_value(&args, "--tenant"),
+        process_registry::arg_value(&args, "--backend"),
+    )
+    .and_then(|child| child.wait_with_output().map_err(anyhow::Error::from));
+
+    let record = run.finish(&runtime_root, &output)
+        .map_err(|e| format!("Run finished but recording it failed: {e}"))?;
+    quarantine::apply_to_run(&runtime_root, &record)
+        .map_err(|e| format!("Run finished but auto-quarantine failed: {e}"))?;
+
+    let output = output.map_err(|e| e.to_string())?;
 
     if !output.status.success() {
         return Err(String::from_utf8_lossy(&output.stderr).into());
@@ -322,6 +454,9 @@ pub struct InboxEntry {
     pub timestamp: String,
     pub signed: bool,
     pub path: String,
+    pub provenance: Option<submission::AgentProvenance>,
+    pub triage: Option<inbox::InboxTriage>,
+    pub suspended: bool,
 }
 
 #[tauri::command]
@@ -342,6 +477,9 @@ fn list_agent_inbox(app: tauri::AppHandle)
             timestamp: e.timestamp,
             signed,
             path: masked,
+            provenance: e.provenance,
+            triage: e.triage,
+            suspended: e.suspended,
         });
     }
 
@@ -351,21 +489,15 @@ fn list_agent_inbox(app: tauri::AppHandle)
 // ============================================================
 // APPROVAL — FIXED
 // ============================================================
-#[tauri::command]
-fn approve_agent_tenant(app: tauri::AppHandle, tenant: String)
--> Result<bool, String> {
-    let root = ensure_worker_runtime_dirs(&app)?;
-
-    tenant_state::mark_authorized(&root, &tenant)
-        .map_err(|e| e.to_string())?;
-
-    let bin = resolve_bundled_worker_bin(&app)?;
+fn worker_approve(app: &tauri::AppHandle, root: &std::path::Path, tenant: &str)
+-> Result<(), String> {
+    let bin = resolve_bundled_worker_bin(app)?;
 
     let status = std::process::Command::new(bin)
-        .current_dir(&root)
+        .current_dir(root)
         .env("HOME", env::var("HOME").or_else(|_| env::var("USERPROFILE")).unwrap_or_default())
         .arg("approve")
-        .arg(&tenant)
+        .arg(tenant)
         .status()
         .map_err(|e| e.to_string())?;
 
@@ -373,6 +505,136 @@ fn approve_agent_tenant(app: tauri::AppHandle, tenant: String)
         return Err("Worker approval failed".into());
     }
 
+    Ok(())
+}
+
+#[tauri::command]
+fn approve_agent_tenant(app: tauri::AppHandle, tenant: String)
+-> Result<bool, String> {
+    let root = ensure_worker_runtime_dirs(&app)?;
+    runtime_path::check_tenant(&tenant).map_err(|e| e.to_string())?;
+
+    if let Some(q) = approvals::load_quorum(&root, &tenant).map_err(|e| e.to_string())? {
+        return Err(format!(
+            "Tenant requires {} of {} maintainer approvals; submit signed approvals instead",
+            q.required,
+            q.maintainers.len()
+        ));
+    }
+
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+    tenant_state::ensure_approvable(&root, &tenant).map_err(|e| e.to_string())?;
+
+    // The worker goes first so a failed worker approval leaves the
+    // manifest as it was.
+    worker_approve(&app, &root, &tenant)?;
+
+    tenant_state::mark_authorized(&root, &tenant, tenant_state::ApprovedBy::Operator(&op))
+        .map_err(|e| format!("Worker approved but recording the approval failed: {e}"))?;
+
+    audit::record_signed(&root, &op, "tenant_approved", Some(&tenant), serde_json::Value::Null)
+        .map_err(|e| format!("Approved but audit write failed: {e}"))?;
+
+    Ok(true)
+}
+
+#[tauri::command]
+fn get_tenant_quorum(app: tauri::AppHandle, tenant: String)
+-> Result<Option<approvals::QuorumStatus>, String> {
+    let root = ensure_worker_runtime_dirs(&app)?;
+    runtime_path::check_tenant(&tenant).map_err(|e| e.to_string())?;
+    approvals::quorum_status(&root, &tenant).map_err(|e| e.to_string())
+}
+
+/// One maintainer's signature over the module SHA-256. The tenant is
+/// cleared only when the policy quorum is reached.
+#[tauri::command]
+fn submit_tenant_approval(
+    app: tauri::AppHandle,
+    tenant: String,
+    approver: String,
+    signed_at: String,
+    signature: String,
+) -> Result<approvals::QuorumStatus, String> {
+    let root = ensure_worker_runtime_dirs(&app)?;
+    runtime_path::check_tenant(&tenant).map_err(|e| e.to_string())?;
+    tenant_state::ensure_not_suspended(&root, &tenant, "recording approvals").map_err(|e| e.to_string())?;
+
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+
+    let status = approvals::add_approval(&root, &tenant, &approver, &signed_at, &signature)
+        .map_err(|e| e.to_string())?;
+
+    audit::record_signed(
+        &root,
+        &op,
+        "quorum_approval_recorded",
+        Some(&tenant),
+        serde_json::json!({ "approver": approver, "sha256": status.sha256, "met": status.met }),
+    )
+    .map_err(|e| format!("Approval recorded but audit write failed: {e}"))?;
+
+    if status.met {
+        tenant_state::ensure_approvable(&root, &tenant).map_err(|e| e.to_string())?;
+        worker_approve(&app, &root, &tenant)?;
+
+        tenant_state::mark_authorized(
+            &root,
+            &tenant,
+            tenant_state::ApprovedBy::Quorum(&status.approvers),
+        )
+        .map_err(|e| format!("Worker approved but recording the approval failed: {e}"))?;
+    }
+
+    Ok(status)
+}
+
+#[tauri::command]
+fn reject_agent_tenant(app: tauri::AppHandle, tenant: String)
+-> Result<bool, String> {
+    let root = ensure_worker_runtime_dirs(&app)?;
+    runtime_path::check_tenant(&tenant).map_err(|e| e.to_string())?;
+
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+
+    tenant_state::mark_rejected(&root, &tenant, &op)
+        .map_err(|e| e.to_string())?;
+
+    audit::record_signed(&root, &op, "tenant_rejected", Some(&tenant), serde_json::Value::Null)
+        .map_err(|e| format!("Rejected but audit write failed: {e}"))?;
+
+    Ok(true)
+}
+
+/// Extend or re-approve time-bound trust. Omitted terms fall back to
+/// the tenant's policy.
+#[tauri::command]
+fn renew_tenant_trust(
+    app: tauri::AppHandle,
+    tenant: String,
+    ttl_hours: Option<u64>,
+    max_executions: Option<u64>,
+) -> Result<bool, String> {
+    let root = ensure_worker_runtime_dirs(&app)?;
+    runtime_path::check_tenant(&tenant).map_err(|e| e.to_string())?;
+
+    let overrides = (ttl_hours.is_some() || max_executions.is_some())
+        .then_some(tenant_state::TrustTerms { ttl_hours, max_executions });
+
+    let op = operator::active(&root).map_err(|e| e.to_string())?;
+
+    let terms = tenant_state::renew_trust(&root, &tenant, &op, overrides)
+        .map_err(|e| e.to_string())?;
+
+    audit::record_signed(
+        &root,
+        &op,
+        "trust_renewed",
+        Some(&tenant),
+        serde_json::to_value(&terms).unwrap_or_default(),
+    )
+    .map_err(|e| format!("Trust renewed but audit write failed: {e}"))?;
+
     Ok(true)
 }
 
@@ -398,20 +660,87 @@ pub fn run() {
         .plugin(opener_init())
         .plugin(shell_init())
         .plugin(dialog_init())
+        .setup(|app| {
+            // Rotation due since the last session happens off the UI thread.
+            if let Ok(root) = ensure_worker_runtime_dirs(app.handle()) {
+                std::thread::spawn(move || {
+                    // Entries left by a previous session whose processes are gone.
+                    let _ = process_registry::prune(&root);
+                    let _ = retention::run(&root, false);
+                    let _ = index_db::open_synced(&root);
+                    let _ = anomaly::run(&root);
+                });
+            }
+
+            // Scheduled runs, including ones that fell due while closed.
+            let handle = app.handle().clone();
+            std::thread::spawn(move || loop {
+                if let Ok(root) = resolve_worker_root(&handle) {
+                    let _ = scheduler::tick(&root, &resolve_bundled_worker_bin(&handle));
+                }
+                std::thread::sleep(scheduler::TICK_INTERVAL);
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             get_worker_logs_path,
             read_runtime_file,
+            open_path_universal,
+            get_retention_config,
+            save_retention_config,
+            rotate_logs,
+            verify_log_seals,
+            index_decisions_over_time,
+            index_sha_history,
+            index_backend_stats,
+            index_sha_analytics,
+            index_tenant_history,
+            rebuild_index,
+            run_anomaly_engine,
+            get_anomaly_report,
+            get_timeline,
+            list_runs,
+            get_run,
             get_full_system_scan,
+            list_backends,
+            test_backend,
+            test_wasmtime_backend,
+            test_firecracker_backend,
             get_guardian_decisions,
+            explain_guardian_decision,
             get_tenant_states,
             run_worker_cmd,
             import_tenant_from_file,
+            export_tenant_bundle,
+            export_evidence_bundle,
+            export_zip,
+            submit_agent_module,
             list_agent_inbox,
             approve_agent_tenant,
+            reject_agent_tenant,
+            get_tenant_quorum,
+            submit_tenant_approval,
+            renew_tenant_trust,
+            stop_tenant,
+            suspend_tenant,
+            resume_tenant,
+            list_schedules,
+            create_schedule,
+            delete_schedule,
+            set_schedule_enabled,
+            list_operator_profiles,
+            create_operator_profile,
+            set_active_operator,
+            list_tenant_revisions,
+            import_tenant_revision,
+            promote_tenant_revision,
+            rollback_tenant_revision,
+            compare_tenant_revisions,
             tauri_get_pro_status,
             unlock_pro_from_license,
             pro_deactivate,
+            pro_refresh_license,
             pro_load_policies,
             pro_save_policies,
             pro_list_quarantine,
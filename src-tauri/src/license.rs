@@ -0,0 +1,214 @@
+use serde::{Serialize, Deserialize};
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD}, Engine as _};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+use crate::submission::sha256_hex;
+
+/// Leading segment of every token: `NCL1.<payload>.<signature>`, both
+/// unpadded base64url. The signature covers `NCL1.<payload>`.
+pub const TOKEN_PREFIX: &str = "NCL1";
+
+/// Vendor license-signing key (base64 of the 32 raw bytes), fixed at
+/// build time. Builds without it accept no license.
+#[cfg(not(test))]
+const VENDOR_PUBLIC_KEY: Option<&str> = option_env!("NIGHTCORE_LICENSE_PUBKEY");
+
+/// Test builds trust [`test_vendor`] instead.
+#[cfg(test)]
+const VENDOR_PUBLIC_KEY: Option<&str> = Some(test_vendor::PUBLIC_KEY);
+
+/// What the vendor signed.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LicenseClaims {
+    pub license_id: String,
+    pub licensee: String,
+    pub tier: String,
+    pub issued_at: String,
+    /// `None` for perpetual licenses.
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    pub seats: u32,
+    /// Fingerprints of the devices the license is bound to, at most
+    /// `seats` of them.
+    pub devices: Vec<String>,
+}
+
+/// Stable, non-identifying id for a device, given to the vendor when
+/// requesting a license.
+pub fn device_fingerprint(device_id: &str) -> String {
+    sha256_hex(format!("nightcore-device:v1:{device_id}").as_bytes())
+}
+
+fn vendor_key() -> Result<VerifyingKey> {
+    let Some(b64) = VENDOR_PUBLIC_KEY else {
+        bail!("this build has no license public key; offline licenses cannot be verified");
+    };
+
+    let bytes: [u8; 32] = STANDARD
+        .decode(b64.trim())
+        .context("decoding the embedded license key")?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("embedded license key is not 32 bytes"))?;
+
+    VerifyingKey::from_bytes(&bytes).context("embedded license key is invalid")
+}
+
+/// Signature and payload only: the claims as the vendor issued them,
+/// whether or not they apply to this device today.
+pub fn decode(token: &str) -> Result<LicenseClaims> {
+    let token = token.trim();
+    let mut parts = token.split('.');
+
+    let (Some(prefix), Some(payload), Some(sig), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        bail!("not a Night Core license token");
+    };
+    if prefix != TOKEN_PREFIX {
+        bail!("unsupported license format '{prefix}'");
+    }
+
+    let sig = Signature::from_slice(&URL_SAFE_NO_PAD.decode(sig).context("decoding license signature")?)
+        .context("malformed license signature")?;
+
+    vendor_key()?
+        .verify(format!("{prefix}.{payload}").as_bytes(), &sig)
+        .map_err(|_| anyhow::anyhow!("license signature does not verify"))?;
+
+    let raw = URL_SAFE_NO_PAD.decode(payload).context("decoding license payload")?;
+    serde_json::from_slice(&raw).context("parsing license payload")
+}
+
+/// Full offline check: vendor signature, expiry, seat count and device
+/// binding.
+pub fn verify(token: &str, device_fp: &str, now: DateTime<Utc>) -> Result<LicenseClaims> {
+    let claims = decode(token)?;
+
+    if let Some(exp) = &claims.expires_at {
+        let exp = DateTime::parse_from_rfc3339(exp)
+            .with_context(|| format!("invalid expires_at '{exp}'"))?
+            .with_timezone(&Utc);
+        if exp <= now {
+            bail!("license {} expired at {}", claims.license_id, exp.to_rfc3339());
+        }
+    }
+
+    if claims.devices.len() > claims.seats as usize {
+        bail!(
+            "license {} binds {} devices but has {} seats",
+            claims.license_id,
+            claims.devices.len(),
+            claims.seats
+        );
+    }
+
+    if !claims.devices.iter().any(|d| d == device_fp) {
+        bail!("license {} is not bound to this device ({device_fp})", claims.license_id);
+    }
+
+    Ok(claims)
+}
+
+/// A stand-in vendor with a fixed key, for tests that need tokens.
+#[cfg(test)]
+pub mod test_vendor {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const SECRET: [u8; 32] = [7; 32];
+    pub const PUBLIC_KEY: &str = "6kpsY+KcUgq+9VB7Ey7F+ZVHdq6+vnuSQh7qaRRG0iw=";
+
+    pub fn key() -> SigningKey {
+        SigningKey::from_bytes(&SECRET)
+    }
+
+    /// Token for `claims`, as `license_tool issue` would produce it.
+    pub fn issue(claims: &LicenseClaims) -> String {
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims).unwrap());
+        let signed = format!("{TOKEN_PREFIX}.{payload}");
+        let sig = URL_SAFE_NO_PAD.encode(key().sign(signed.as_bytes()).to_bytes());
+        format!("{signed}.{sig}")
+    }
+
+    pub fn claims(license_id: &str, expires_at: Option<DateTime<Utc>>, devices: &[&str]) -> LicenseClaims {
+        LicenseClaims {
+            license_id: license_id.into(),
+            licensee: "Example Ltd".into(),
+            tier: "pro".into(),
+            issued_at: Utc::now().to_rfc3339(),
+            expires_at: expires_at.map(|t| t.to_rfc3339()),
+            seats: devices.len().max(1) as u32,
+            devices: devices.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+    use test_vendor::{claims, issue};
+
+    #[test]
+    fn test_key_matches_its_secret() {
+        assert_eq!(STANDARD.encode(test_vendor::key().verifying_key().to_bytes()), test_vendor::PUBLIC_KEY);
+    }
+
+    #[test]
+    fn verifies_a_bound_unexpired_license() {
+        let now = Utc::now();
+        let token = issue(&claims("L1", Some(now + TimeDelta::days(30)), &["dev-a", "dev-b"]));
+
+        let c = verify(&token, "dev-b", now).unwrap();
+        assert_eq!(c.license_id, "L1");
+        assert_eq!(c.seats, 2);
+        assert!(verify(&format!("  {token}\n"), "dev-a", now).is_ok());
+
+        let perpetual = issue(&claims("L2", None, &["dev-a"]));
+        assert!(verify(&perpetual, "dev-a", now + TimeDelta::days(36_500)).is_ok());
+    }
+
+    #[test]
+    fn refuses_expired_unbound_and_oversubscribed() {
+        let now = Utc::now();
+        let token = issue(&claims("L1", Some(now), &["dev-a"]));
+        assert!(verify(&token, "dev-a", now).unwrap_err().to_string().contains("expired"));
+
+        let token = issue(&claims("L1", None, &["dev-a"]));
+        assert!(verify(&token, "dev-x", now).unwrap_err().to_string().contains("not bound"));
+
+        let mut c = claims("L1", None, &["dev-a", "dev-b"]);
+        c.seats = 1;
+        assert!(verify(&issue(&c), "dev-a", now).unwrap_err().to_string().contains("seats"));
+
+        let mut c = claims("L1", None, &["dev-a"]);
+        c.expires_at = Some("next tuesday".into());
+        assert!(verify(&issue(&c), "dev-a", now).is_err());
+    }
+
+    #[test]
+    fn refuses_tampered_or_foreign_tokens() {
+        let token = issue(&claims("L1", None, &["dev-a"]));
+        let (signed, sig) = token.rsplit_once('.').unwrap();
+
+        let other = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims("L1", None, &["dev-x"])).unwrap());
+        let swapped = format!("{TOKEN_PREFIX}.{other}.{sig}");
+        assert!(decode(&swapped).unwrap_err().to_string().contains("does not verify"));
+
+        let payload = signed.strip_prefix("NCL1.").unwrap();
+        assert!(decode(&format!("NCL2.{payload}.{sig}")).unwrap_err().to_string().contains("unsupported"));
+        for bad in ["", "NCL1", "NCL1.a.b.c", "ABCD-1234-EFGH", &format!("{signed}.!!")] {
+            assert!(decode(bad).is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn fingerprint_is_stable_and_opaque() {
+        let fp = device_fingerprint("alice@host");
+        assert_eq!(fp, device_fingerprint("alice@host"));
+        assert_ne!(fp, device_fingerprint("bob@host"));
+        assert_eq!(fp.len(), 64);
+        assert!(!fp.contains("alice"));
+    }
+}
@@ -0,0 +1,269 @@
+use serde::{Serialize, Deserialize};
+use std::{env, fs, path::{Path, PathBuf}};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::Utc;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::rngs::OsRng;
+
+use crate::approvals::key_fingerprint;
+use crate::atomic_fs::{self, Expect};
+
+const KEYRING_SERVICE: &str = "Night Core Console";
+
+/// Public half of an operator identity, kept in `state/operators.json`.
+/// The private key never leaves the keyring (or its 0600 fallback file).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OperatorProfile {
+    pub name: String,
+    pub public_key: String,
+    pub fingerprint: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OperatorRegistry {
+    pub active: Option<String>,
+    pub profiles: Vec<OperatorProfile>,
+}
+
+/// The operator acting in this session, able to sign.
+pub struct Operator {
+    pub name: String,
+    pub fingerprint: String,
+    key: SigningKey,
+}
+
+impl Operator {
+    pub fn sign(&self, msg: &[u8]) -> String {
+        STANDARD.encode(self.key.sign(msg).to_bytes())
+    }
+
+    pub fn public_key_b64(&self) -> String {
+        STANDARD.encode(self.key.verifying_key().as_bytes())
+    }
+}
+
+#[cfg(test)]
+impl Operator {
+    /// Throwaway identity that never touches the keyring.
+    pub fn ephemeral(name: &str) -> Self {
+        let key = SigningKey::generate(&mut OsRng);
+        Operator {
+            name: name.into(),
+            fingerprint: key_fingerprint(key.verifying_key().as_bytes()),
+            key,
+        }
+    }
+
+    /// Ephemeral identity with its public half in the registry, so its
+    /// signatures verify against `worker_root`.
+    pub fn registered(worker_root: &Path, name: &str) -> Self {
+        let op = Self::ephemeral(name);
+        let (mut reg, prev) = load_registry_versioned(worker_root).unwrap();
+        reg.profiles.push(OperatorProfile {
+            name: name.into(),
+            public_key: op.public_key_b64(),
+            fingerprint: op.fingerprint.clone(),
+            created_at: Utc::now().to_rfc3339(),
+        });
+        save_registry(worker_root, &reg, prev.as_deref()).unwrap();
+        op
+    }
+}
+
+fn registry_path(worker_root: &Path) -> PathBuf {
+    worker_root.join("state").join("operators.json")
+}
+
+fn fallback_key_path(worker_root: &Path, name: &str) -> PathBuf {
+    worker_root.join("keys").join("operators").join(format!("{name}.key"))
+}
+
+fn keyring_account(name: &str) -> String {
+    format!("operator-key:{name}")
+}
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && !name.starts_with('.')
+}
+
+/// Whether `signature_b64` over `msg` was made by the registered operator
+/// whose key has `fingerprint`. Unknown operators never verify.
+pub fn verify_signature(worker_root: &Path, fingerprint: &str, msg: &[u8], signature_b64: &str) -> bool {
+    let Ok(reg) = load_registry(worker_root) else {
+        return false;
+    };
+
+    reg.profiles.iter().filter(|p| p.fingerprint == fingerprint).any(|p| {
+        let key = STANDARD
+            .decode(p.public_key.trim())
+            .ok()
+            .and_then(|b| <[u8; 32]>::try_from(b).ok())
+            .and_then(|b| VerifyingKey::from_bytes(&b).ok());
+        let sig = STANDARD
+            .decode(signature_b64.trim())
+            .ok()
+            .and_then(|b| <[u8; 64]>::try_from(b).ok());
+
+        match (key, sig) {
+            (Some(k), Some(s)) => {
+                key_fingerprint(k.as_bytes()) == fingerprint && k.verify(msg, &Signature::from_bytes(&s)).is_ok()
+            }
+            _ => false,
+        }
+    })
+}
+
+pub fn load_registry(worker_root: &Path) -> Result<OperatorRegistry> {
+    Ok(load_registry_versioned(worker_root)?.0)
+}
+
+fn load_registry_versioned(worker_root: &Path) -> Result<(OperatorRegistry, Option<String>)> {
+    let p = registry_path(worker_root);
+    let Some((raw, sha)) = atomic_fs::read_versioned(&p)? else {
+        return Ok((OperatorRegistry::default(), None));
+    };
+
+    let reg = serde_json::from_slice(&raw)
+        .with_context(|| format!("parsing {}", p.display()))?;
+    Ok((reg, Some(sha)))
+}
+
+fn save_registry(worker_root: &Path, reg: &OperatorRegistry, prev: Option<&str>) -> Result<()> {
+    atomic_fs::write_atomic(
+        &registry_path(worker_root),
+        serde_json::to_string_pretty(reg)?.as_bytes(),
+        Expect::from_prev(prev),
+    )
+}
+
+fn store_key(worker_root: &Path, name: &str, key: &SigningKey) -> Result<()> {
+    let b64 = STANDARD.encode(key.to_bytes());
+
+    if let Ok(entry) = keyring::Entry::new(KEYRING_SERVICE, &keyring_account(name)) {
+        if entry.set_password(&b64).is_ok() {
+            return Ok(());
+        }
+    }
+
+    // No usable keyring (headless Linux, locked session): keep the key
+    // in a user-only file instead.
+    atomic_fs::write_private(&fallback_key_path(worker_root, name), b64.as_bytes(), Expect::Absent)
+}
+
+/// Drop a key stored for a profile that never made it into the
+/// registry, unless it has since been replaced by another key.
+fn discard_key(worker_root: &Path, name: &str, key: &SigningKey) {
+    if !load_key(worker_root, name).is_ok_and(|k| k == *key) {
+        return;
+    }
+    if let Ok(entry) = keyring::Entry::new(KEYRING_SERVICE, &keyring_account(name)) {
+        let _ = entry.delete_password();
+    }
+    let _ = fs::remove_file(fallback_key_path(worker_root, name));
+}
+
+fn load_key(worker_root: &Path, name: &str) -> Result<SigningKey> {
+    let from_keyring = keyring::Entry::new(KEYRING_SERVICE, &keyring_account(name))
+        .ok()
+        .and_then(|e| e.get_password().ok());
+
+    let b64 = match from_keyring {
+        Some(v) => v,
+        None => fs::read_to_string(fallback_key_path(worker_root, name))
+            .map_err(|_| anyhow!("no signing key found for operator '{name}'"))?,
+    };
+
+    let bytes: [u8; 32] = STANDARD
+        .decode(b64.trim())
+        .context("decoding operator key")?
+        .try_into()
+        .map_err(|_| anyhow!("operator key for '{name}' must be 32 bytes"))?;
+
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+pub fn create_profile(worker_root: &Path, name: &str) -> Result<OperatorProfile> {
+    if !valid_name(name) {
+        bail!("invalid operator name '{name}'");
+    }
+
+    let (mut reg, prev) = load_registry_versioned(worker_root)?;
+    if reg.profiles.iter().any(|p| p.name == name) {
+        bail!("operator '{name}' already exists");
+    }
+
+    let key = SigningKey::generate(&mut OsRng);
+    store_key(worker_root, name, &key)?;
+
+    let public = key.verifying_key();
+    let profile = OperatorProfile {
+        name: name.into(),
+        public_key: STANDARD.encode(public.as_bytes()),
+        fingerprint: key_fingerprint(public.as_bytes()),
+        created_at: Utc::now().to_rfc3339(),
+    };
+
+    reg.profiles.push(profile.clone());
+    reg.active = Some(name.into());
+    // A key left behind without its profile would make the next attempt
+    // fail to write the fallback key file.
+    if let Err(e) = save_registry(worker_root, &reg, prev.as_deref()) {
+        discard_key(worker_root, name, &key);
+        return Err(e);
+    }
+
+    Ok(profile)
+}
+
+pub fn set_active(worker_root: &Path, name: &str) -> Result<()> {
+    let (mut reg, prev) = load_registry_versioned(worker_root)?;
+    if !reg.profiles.iter().any(|p| p.name == name) {
+        bail!("unknown operator '{name}'");
+    }
+    reg.active = Some(name.into());
+    save_registry(worker_root, &reg, prev.as_deref())
+}
+
+fn default_name() -> String {
+    let user: String = env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_default()
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect();
+
+    if user.is_empty() { "operator".into() } else { user }
+}
+
+/// The active operator. A first run creates a profile named after the
+/// OS user so approvals are attributable from the start.
+pub fn active(worker_root: &Path) -> Result<Operator> {
+    let reg = load_registry(worker_root)?;
+
+    let chosen = match &reg.active {
+        Some(a) => reg.profiles.iter().find(|p| &p.name == a).or(reg.profiles.first()),
+        None => reg.profiles.first(),
+    };
+
+    let profile = match chosen {
+        Some(p) => p.clone(),
+        None => create_profile(worker_root, &default_name())?,
+    };
+
+    let key = load_key(worker_root, &profile.name)?;
+    if key_fingerprint(key.verifying_key().as_bytes()) != profile.fingerprint {
+        bail!("signing key for operator '{}' does not match its profile", profile.name);
+    }
+
+    Ok(Operator {
+        name: profile.name,
+        fingerprint: profile.fingerprint,
+        key,
+    })
+}
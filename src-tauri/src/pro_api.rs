@@ -4,6 +4,7 @@ use std::{
     fs,
     path::{Path, PathBuf},
 };
+use std::time::Duration;
 use chrono::Utc;
 use reqwest::blocking::Client;
 
@@ -13,8 +14,15 @@ use base64::{engine::general_purpose::STANDARD, Engine as _};
 use rand::rngs::OsRng;
 use rand::RngCore;
 
+use crate::atomic_fs::{write_atomic, write_private, Expect};
+use crate::license::{self, LicenseClaims};
+use crate::process_registry::{self, KillReport};
+use crate::quarantine::{self, QuarantineRules};
+use crate::submission::sha256_hex;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ProLicenseFile {
+    /// Vendor-signed `NCL1.` token, see [`license`].
     pub license_key: String,
     pub tier: String,
     pub activated_at: String,
@@ -26,6 +34,15 @@ pub struct ProLicenseFile {
 
     #[serde(default)]
     pub signature: Option<String>,
+
+    /// Last successful online refresh, if any.
+    #[serde(default)]
+    pub refreshed_at: Option<String>,
+
+    /// Entries in `revoked.json` when this file was written. A shorter
+    /// or missing list means revocations were removed by hand.
+    #[serde(default)]
+    pub revocations: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -33,12 +50,25 @@ pub struct ProStatus {
     pub is_pro: bool,
     pub tier: String,
     pub activated_at: Option<String>,
+
+    pub license_id: Option<String>,
+    pub licensee: Option<String>,
+    pub expires_at: Option<String>,
+    pub seats: Option<u32>,
+    pub refreshed_at: Option<String>,
+    /// Why a stored license does not unlock PRO.
+    pub detail: Option<String>,
+    /// What the vendor needs to bind a license to this device.
+    pub device_fingerprint: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PolicyFile {
     pub allow: Vec<String>,
     pub block: Vec<String>,
+
+    #[serde(default)]
+    pub quarantine: QuarantineRules,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -47,18 +77,51 @@ pub struct QuarantineEntry {
     pub tenant: String,
     pub timestamp: String,
     pub path: String,
+
+    pub threat_score: u8,
+    pub reason: String,
+    /// Quarantine rules the decision matched.
+    pub rules: Vec<String>,
+}
+
+/// Environment lookup. Passed in where the environment matters, so
+/// tests can vary it without changing variables other threads read.
+type EnvVar<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+fn process_env(key: &str) -> Option<String> {
+    env::var(key).ok()
 }
 
 fn home_dir() -> PathBuf {
+    home_dir_in(&process_env)
+}
+
+fn home_dir_in(var: EnvVar) -> PathBuf {
     PathBuf::from(
-        env::var("HOME")
-            .or_else(|_| env::var("USERPROFILE"))
-            .unwrap_or_else(|_| ".".into()),
+        var("HOME")
+            .or_else(|| var("USERPROFILE"))
+            .unwrap_or_else(|| ".".into()),
     )
 }
 
 fn pro_root() -> PathBuf {
-    home_dir().join(".nightcore").join("pro")
+    pro_root_in(&process_env)
+}
+
+#[cfg(not(test))]
+fn pro_root_in(var: EnvVar) -> PathBuf {
+    home_dir_in(var).join(".nightcore").join("pro")
+}
+
+// Tests point each thread at its own directory.
+#[cfg(test)]
+thread_local! {
+    static TEST_PRO_ROOT: std::cell::RefCell<PathBuf> = const { std::cell::RefCell::new(PathBuf::new()) };
+}
+
+#[cfg(test)]
+fn pro_root_in(_: EnvVar) -> PathBuf {
+    TEST_PRO_ROOT.with(|p| p.borrow().clone())
 }
 
 fn license_path() -> PathBuf {
@@ -70,7 +133,23 @@ fn policies_path() -> PathBuf {
 }
 
 fn device_secret_path() -> PathBuf {
-    pro_root().join("device_secret.b64")
+    device_secret_path_in(&process_env)
+}
+
+fn device_secret_path_in(var: EnvVar) -> PathBuf {
+    pro_root_in(var).join("device_secret.b64")
+}
+
+fn refresh_config_path() -> PathBuf {
+    pro_root().join("license_refresh.json")
+}
+
+fn revoked_path() -> PathBuf {
+    pro_root().join("revoked.json")
+}
+
+fn revocation_mark_path() -> PathBuf {
+    pro_root().join("revocation_mark.json")
 }
 
 fn mask_worker_path(worker_root: &Path, full: &str) -> String {
@@ -83,27 +162,81 @@ fn mask_worker_path(worker_root: &Path, full: &str) -> String {
     }
 }
 
-fn current_device_id() -> String {
-    let user = env::var("USER")
-        .or_else(|_| env::var("USERNAME"))
-        .unwrap_or_else(|_| "unknown-user".into())
-        .to_lowercase();
+/// Machine id assigned by the OS: `/etc/machine-id` on Linux, the
+/// platform UUID on macOS, `MachineGuid` on Windows. Environment
+/// variables are never consulted; any process can set them.
+fn os_machine_id() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    let id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|p| fs::read_to_string(p).ok());
+
+    #[cfg(target_os = "macos")]
+    let id = std::process::Command::new("/usr/sbin/ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()
+        .and_then(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .find(|l| l.contains("\"IOPlatformUUID\""))
+                .and_then(|l| l.rsplit('"').nth(1).map(|s| s.to_string()))
+        });
 
-    let host = env::var("HOSTNAME")
-        .or_else(|_| env::var("COMPUTERNAME"))
-        .unwrap_or_else(|_| "unknown-host".into())
-        .to_lowercase();
+    #[cfg(windows)]
+    let id = std::process::Command::new("reg")
+        .args(["query", r"HKLM\SOFTWARE\Microsoft\Cryptography", "/v", "MachineGuid"])
+        .output()
+        .ok()
+        .and_then(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .find(|l| l.contains("MachineGuid"))
+                .and_then(|l| l.split_whitespace().last().map(|s| s.to_string()))
+        });
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    let id: Option<String> = None;
+
+    id.map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty())
+}
+
+fn current_device_id() -> Result<String, String> {
+    device_id_in(&process_env)
+}
+
+/// What a license is bound to. The OS machine id where there is one,
+/// otherwise a hash of the persisted device secret. `var` is only used
+/// to find the home directory holding that secret.
+fn device_id_in(var: EnvVar) -> Result<String, String> {
+    if let Some(id) = os_machine_id() {
+        return Ok(format!("machine:{id}"));
+    }
 
-    format!("{user}@{host}")
+    let secret = load_or_create_device_secret_at(&device_secret_path_in(var))?;
+    Ok(format!("secret:{}", &sha256_hex(&secret)[..32]))
+}
+
+fn current_device_fingerprint() -> Result<String, String> {
+    current_device_id().map(|id| license::device_fingerprint(&id))
 }
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Keychain entry holding the device secret. Tests keep to the file.
+fn device_secret_entry() -> Option<keyring::Entry> {
+    if cfg!(test) {
+        return None;
+    }
+    keyring::Entry::new("Night Core Console", "pro-hmac-key").ok()
+}
+
 fn load_or_create_device_secret() -> Result<Vec<u8>, String> {
-    let service = "Night Core Console";
-    let account = format!("pro-hmac-key:{}", current_device_id());
+    load_or_create_device_secret_at(&device_secret_path())
+}
 
-    if let Ok(entry) = keyring::Entry::new(service, &account) {
+fn load_or_create_device_secret_at(p: &Path) -> Result<Vec<u8>, String> {
+    if let Some(entry) = device_secret_entry() {
         if let Ok(b64) = entry.get_password() {
             if let Ok(bytes) = STANDARD.decode(b64.trim()) {
                 if bytes.len() >= 32 {
@@ -113,9 +246,8 @@ fn load_or_create_device_secret() -> Result<Vec<u8>, String> {
         }
     }
 
-    let p = device_secret_path();
     if p.exists() {
-        let raw = fs::read_to_string(&p)
+        let raw = fs::read_to_string(p)
             .map_err(|e| format!("Failed to read device secret: {e}"))?;
         let bytes = STANDARD
             .decode(raw.trim())
@@ -124,7 +256,7 @@ fn load_or_create_device_secret() -> Result<Vec<u8>, String> {
             return Err("Device secret too short".into());
         }
 
-        if let Ok(entry) = keyring::Entry::new(service, &account) {
+        if let Some(entry) = device_secret_entry() {
             let _ = entry.set_password(raw.trim());
         }
 
@@ -135,18 +267,10 @@ fn load_or_create_device_secret() -> Result<Vec<u8>, String> {
     OsRng.fill_bytes(&mut secret);
     let b64 = STANDARD.encode(&secret);
 
-    fs::create_dir_all(&pro_root())
-        .map_err(|e| format!("Failed to create pro directory: {e}"))?;
-    fs::write(&p, &b64)
-        .map_err(|e| format!("Failed to write device secret: {e}"))?;
-
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let _ = fs::set_permissions(&p, fs::Permissions::from_mode(0o600));
-    }
+    write_private(p, b64.as_bytes(), Expect::Absent)
+        .map_err(|e| format!("Failed to write device secret: {e:#}"))?;
 
-    if let Ok(entry) = keyring::Entry::new(service, &account) {
+    if let Some(entry) = device_secret_entry() {
         let _ = entry.set_password(&b64);
     }
 
@@ -154,187 +278,503 @@ fn load_or_create_device_secret() -> Result<Vec<u8>, String> {
 }
 
 fn canonical_license_string(lic: &ProLicenseFile) -> String {
-    format!(
+    let mut out = format!(
         "license_key={}\n\
 tier={}\n\
 activated_at={}\n\
 valid={}\n\
 device_id={}\n\
-provider={}\n",
+provider={}\n\
+refreshed_at={}\n",
         lic.license_key.trim(),
         lic.tier.trim(),
         lic.activated_at.trim(),
         lic.valid,
         lic.device_id.trim(),
         lic.provider.trim(),
-    )
+        lic.refreshed_at.as_deref().unwrap_or(""),
+    );
+
+    // Only when set, so activations from before the counter still verify.
+    if lic.revocations > 0 {
+        out.push_str(&format!("revocations={}\n", lic.revocations));
+    }
+    out
 }
 
-fn sign_license(lic: &ProLicenseFile) -> Result<String, String> {
+fn device_mac(msg: &str) -> Result<HmacSha256, String> {
     let secret = load_or_create_device_secret()?;
-    let msg = canonical_license_string(lic);
 
     let mut mac = HmacSha256::new_from_slice(&secret)
         .map_err(|_| "HMAC init failed".to_string())?;
     mac.update(msg.as_bytes());
+    Ok(mac)
+}
 
+fn mac_matches(msg: &str, sig_b64: &str) -> Result<bool, String> {
+    let sig = STANDARD
+        .decode(sig_b64.trim())
+        .map_err(|e| format!("Invalid signature encoding: {e}"))?;
+
+    Ok(device_mac(msg)?.verify_slice(&sig).is_ok())
+}
+
+fn sign_license(lic: &ProLicenseFile) -> Result<String, String> {
+    let mac = device_mac(&canonical_license_string(lic))?;
     Ok(STANDARD.encode(mac.finalize().into_bytes()))
 }
 
+/// Every license the console writes is signed, so an unsigned file was
+/// written by hand and does not verify.
 fn verify_license(lic: &ProLicenseFile) -> Result<bool, String> {
     let Some(sig_b64) = lic.signature.as_deref() else {
-        return Ok(true);
+        return Ok(false);
     };
+    mac_matches(&canonical_license_string(lic), sig_b64)
+}
 
-    let secret = load_or_create_device_secret()?;
-    let msg = canonical_license_string(lic);
-
-    let sig = STANDARD
-        .decode(sig_b64.trim())
-        .map_err(|e| format!("Invalid signature encoding: {e}"))?;
+/// A license the licensing server reported as revoked or expired. Kept
+/// in `pro/revoked.json`, HMAC-signed like the license itself, so
+/// applying the same token again does not bring it back.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct RevokedLicense {
+    license_id: String,
+    /// revoked | expired
+    status: String,
+    at: String,
+}
 
-    let mut mac = HmacSha256::new_from_slice(&secret)
-        .map_err(|_| "HMAC init failed".to_string())?;
-    mac.update(msg.as_bytes());
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct RevokedFile {
+    #[serde(default)]
+    licenses: Vec<RevokedLicense>,
+    #[serde(default)]
+    signature: Option<String>,
+}
 
-    Ok(mac.verify_slice(&sig).is_ok())
+fn canonical_revoked_string(list: &[RevokedLicense]) -> String {
+    let mut out = String::from("nightcore-revoked:v1\n");
+    for r in list {
+        out.push_str(&format!("{} {} {}\n", r.license_id.trim(), r.status, r.at));
+    }
+    out
 }
 
-#[derive(Debug, Deserialize)]
-struct LemonLicenseKey {
-    status: String,
+/// Revoked licenses. A list that is present but does not verify, or
+/// holds fewer than the `expected` entries the stored license counted,
+/// is an error, not an empty list.
+fn load_revoked(expected: u64) -> Result<Vec<RevokedLicense>, String> {
+    let p = revoked_path();
+    if !p.exists() {
+        if expected > 0 {
+            return Err(format!(
+                "revoked.json is missing but {expected} revoked license(s) were recorded; revoked licenses cannot be checked"
+            ));
+        }
+        return Ok(vec![]);
+    }
+
+    let raw = fs::read_to_string(&p)
+        .map_err(|e| format!("Failed to read revoked.json: {e}"))?;
+    let file: RevokedFile = serde_json::from_str(&raw)
+        .map_err(|e| format!("Invalid revoked.json: {e}"))?;
+
+    let signed = match file.signature.as_deref() {
+        Some(sig) => mac_matches(&canonical_revoked_string(&file.licenses), sig)?,
+        None => false,
+    };
+    if !signed {
+        return Err("revoked.json was modified; revoked licenses cannot be checked".into());
+    }
+    if (file.licenses.len() as u64) < expected {
+        return Err(format!(
+            "revoked.json lists {} of {expected} recorded revocations; revoked licenses cannot be checked",
+            file.licenses.len()
+        ));
+    }
+
+    Ok(file.licenses)
 }
 
-#[derive(Debug, Deserialize)]
-struct LemonValidateResponse {
-    valid: bool,
-    error: Option<String>,
-    license_key: Option<LemonLicenseKey>,
+/// Keychain copy of the revocation count. Tests keep to the file.
+fn revocation_mark_entry() -> Option<keyring::Entry> {
+    if cfg!(test) {
+        return None;
+    }
+    keyring::Entry::new("Night Core Console", "pro-revocations").ok()
 }
 
-fn validate_with_lemon(license_key: &str) -> Result<(), String> {
-    let client = Client::new();
+/// How many revocations `revoked.json` has held, kept apart from the
+/// license so deactivating does not forget them: in the keychain and in
+/// `pro/revocation_mark.json`, signed with the device HMAC secret.
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct RevocationMark {
+    revocations: u64,
+    #[serde(default)]
+    signature: Option<String>,
+}
 
-    let resp = client
-        .post("https://api.lemonsqueezy.com/v1/licenses/validate")
-        .form(&[
-            ("license_key", license_key),
-            ("activation_name", "Night Core Console"),
-        ])
-        .send()
-        .map_err(|e| format!("Network error contacting Lemon Squeezy: {e}"))?;
+fn canonical_mark_string(revocations: u64) -> String {
+    format!("nightcore-revocation-mark:v1\nrevocations={revocations}\n")
+}
 
-    let body: LemonValidateResponse =
-        resp.json().map_err(|e| format!("Invalid Lemon response: {e}"))?;
+/// Highest revocation count recorded outside the license.
+fn revocation_mark() -> Result<u64, String> {
+    let keychain = revocation_mark_entry()
+        .and_then(|e| e.get_password().ok())
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0);
 
-    if !body.valid {
-        return Err(body.error.unwrap_or_else(|| "License invalid".into()));
+    let p = revocation_mark_path();
+    if !p.exists() {
+        return Ok(keychain);
     }
 
-    if let Some(lic) = body.license_key {
-        if lic.status != "active" {
-            return Err(format!("License status is '{}'", lic.status));
-        }
+    let raw = fs::read_to_string(&p)
+        .map_err(|e| format!("Failed to read revocation_mark.json: {e}"))?;
+    let mark: RevocationMark = serde_json::from_str(&raw)
+        .map_err(|e| format!("Invalid revocation_mark.json: {e}"))?;
+
+    let signed = match mark.signature.as_deref() {
+        Some(sig) => mac_matches(&canonical_mark_string(mark.revocations), sig)?,
+        None => false,
+    };
+    if !signed {
+        return Err("revocation_mark.json was modified; revoked licenses cannot be checked".into());
     }
 
+    Ok(mark.revocations.max(keychain))
+}
+
+fn write_revocation_mark(revocations: u64) -> Result<(), String> {
+    let mac = device_mac(&canonical_mark_string(revocations))?;
+    let mark = RevocationMark {
+        revocations,
+        signature: Some(STANDARD.encode(mac.finalize().into_bytes())),
+    };
+
+    write_atomic(
+        &revocation_mark_path(),
+        serde_json::to_string_pretty(&mark).unwrap().as_bytes(),
+        Expect::Any,
+    )
+    .map_err(|e| format!("Failed to write revocation_mark.json: {e}"))?;
+
+    if let Some(entry) = revocation_mark_entry() {
+        let _ = entry.set_password(&revocations.to_string());
+    }
     Ok(())
 }
 
-fn validate_with_gumroad(license_key: &str) -> Result<(), String> {
-    let key = license_key.trim().to_uppercase();
-    let parts: Vec<&str> = key.split('-').collect();
+/// Revocations recorded on this device: the mark, or the count carried
+/// by the stored license if it verifies and is higher (activations from
+/// before the mark).
+fn stored_revocations() -> Result<u64, String> {
+    let from_license = match load_license_file()? {
+        Some(lic) if verify_license(&lic)? => lic.revocations,
+        _ => 0,
+    };
+    Ok(from_license.max(revocation_mark()?))
+}
 
-    if parts.len() != 4 {
-        return Err("Invalid Gumroad license format".into());
+/// Add `license_id` to the list; returns the new entry count.
+fn record_revoked(license_id: &str, status: &str, expected: u64) -> Result<u64, String> {
+    let mut licenses = load_revoked(expected)?;
+    if licenses.iter().any(|r| r.license_id == license_id) {
+        return Ok(licenses.len() as u64);
     }
 
-    for part in &parts {
-        if part.len() != 8 || !part.chars().all(|c| c.is_ascii_hexdigit()) {
-            return Err("Invalid Gumroad license format".into());
-        }
+    licenses.push(RevokedLicense {
+        license_id: license_id.into(),
+        status: status.into(),
+        at: Utc::now().to_rfc3339(),
+    });
+
+    let count = licenses.len() as u64;
+    let mac = device_mac(&canonical_revoked_string(&licenses))?;
+    let file = RevokedFile {
+        licenses,
+        signature: Some(STANDARD.encode(mac.finalize().into_bytes())),
+    };
+
+    write_atomic(
+        &revoked_path(),
+        serde_json::to_string_pretty(&file).unwrap().as_bytes(),
+        Expect::Any,
+    )
+    .map_err(|e| format!("Failed to write revoked.json: {e}"))?;
+    write_revocation_mark(count)?;
+
+    Ok(count)
+}
+
+fn ensure_not_revoked(license_id: &str, expected: u64) -> Result<u64, String> {
+    let list = load_revoked(expected)?;
+    let count = list.len() as u64;
+
+    match list.into_iter().find(|r| r.license_id == license_id) {
+        Some(r) => Err(format!(
+            "License {license_id} was reported {} by the licensing server on {}",
+            r.status, r.at
+        )),
+        None => Ok(count),
     }
+}
 
-    Ok(())
+/// Activations from before offline licenses: a Lemon Squeezy or Gumroad
+/// key rather than a vendor-signed token. They cannot be converted here,
+/// since the token has to be issued for this device.
+fn legacy_activation(lic: &ProLicenseFile) -> Option<String> {
+    let provider = match lic.provider.as_str() {
+        "lemon" => "Lemon Squeezy",
+        "gumroad" => "Gumroad",
+        _ if !lic.license_key.trim().starts_with(&format!("{}.", license::TOKEN_PREFIX)) => "an earlier release",
+        _ => return None,
+    };
+
+    Some(format!(
+        "This license was activated through {provider}, which Night Core no longer checks. \
+Send your purchase receipt and this device's fingerprint ({}) to support to receive an \
+offline license, then apply it here.",
+        current_device_fingerprint().unwrap_or_else(|e| format!("unavailable: {e}"))
+    ))
 }
 
-#[tauri::command]
-pub fn get_pro_status() -> Result<ProStatus, String> {
+fn open_core(detail: Option<String>) -> ProStatus {
+    ProStatus {
+        is_pro: false,
+        tier: "Open Core".into(),
+        activated_at: None,
+        license_id: None,
+        licensee: None,
+        expires_at: None,
+        seats: None,
+        refreshed_at: None,
+        detail,
+        device_fingerprint: current_device_fingerprint().unwrap_or_default(),
+    }
+}
+
+fn load_license_file() -> Result<Option<ProLicenseFile>, String> {
     if !license_path().exists() {
-        return Ok(ProStatus {
-            is_pro: false,
-            tier: "Open Core".into(),
-            activated_at: None,
-        });
+        return Ok(None);
     }
 
     let raw = fs::read_to_string(license_path())
         .map_err(|e| format!("Failed to read license.json: {e}"))?;
 
-    let lic: ProLicenseFile =
-        serde_json::from_str(&raw).map_err(|e| format!("Invalid license.json: {e}"))?;
+    serde_json::from_str(&raw)
+        .map(Some)
+        .map_err(|e| format!("Invalid license.json: {e}"))
+}
 
-    if !lic.valid || lic.device_id != current_device_id() {
-        return Ok(ProStatus {
-            is_pro: false,
-            tier: "Open Core".into(),
-            activated_at: None,
-        });
-    }
+fn write_license_file(file: &mut ProLicenseFile) -> Result<(), String> {
+    file.signature = Some(sign_license(file)?);
 
-    if !verify_license(&lic)? {
-        return Ok(ProStatus {
-            is_pro: false,
-            tier: "Open Core".into(),
-            activated_at: None,
-        });
+    write_atomic(
+        &license_path(),
+        serde_json::to_string_pretty(file).unwrap().as_bytes(),
+        Expect::Any,
+    )
+    .map_err(|e| format!("Failed to write license.json: {e}"))
+}
+
+/// Stored license checked offline: local HMAC, the vendor token, then
+/// the revocation list.
+fn check_stored(lic: &ProLicenseFile) -> Result<LicenseClaims, String> {
+    if let Some(detail) = legacy_activation(lic) {
+        return Err(detail);
     }
+    if lic.device_id != current_device_id()? {
+        return Err("License was activated on another device".into());
+    }
+    if !verify_license(lic)? {
+        return Err("license.json was modified after activation".into());
+    }
+    if !lic.valid {
+        return Err("License was revoked by the licensing server".into());
+    }
+
+    let fp = license::device_fingerprint(&lic.device_id);
+    let claims = license::verify(&lic.license_key, &fp, Utc::now()).map_err(|e| format!("{e:#}"))?;
+    ensure_not_revoked(&claims.license_id, lic.revocations.max(revocation_mark()?))?;
+    Ok(claims)
+}
+
+#[tauri::command]
+pub fn get_pro_status() -> Result<ProStatus, String> {
+    let Some(lic) = load_license_file()? else {
+        return Ok(open_core(None));
+    };
+
+    let claims = match check_stored(&lic) {
+        Ok(c) => c,
+        Err(e) => return Ok(open_core(Some(e))),
+    };
 
     Ok(ProStatus {
         is_pro: true,
-        tier: lic.tier.clone(),
+        tier: claims.tier,
         activated_at: Some(lic.activated_at.clone()),
+        license_id: Some(claims.license_id),
+        licensee: Some(claims.licensee),
+        expires_at: claims.expires_at,
+        seats: Some(claims.seats),
+        refreshed_at: lic.refreshed_at.clone(),
+        detail: None,
+        device_fingerprint: license::device_fingerprint(&lic.device_id),
     })
 }
 
 #[tauri::command]
 pub fn pro_apply_license(license_key: String) -> Result<bool, String> {
-    let key = license_key.trim();
-    if key.is_empty() {
+    let token = license_key.trim();
+    if token.is_empty() {
         return Err("License key cannot be empty".into());
     }
 
-    let provider = if key.contains('-') {
-        validate_with_gumroad(key)?;
-        "gumroad"
-    } else {
-        validate_with_lemon(key)?;
-        "lemon"
-    };
+    let device_id = current_device_id()?;
+    let claims = license::verify(token, &license::device_fingerprint(&device_id), Utc::now())
+        .map_err(|e| format!("License rejected: {e:#}"))?;
+    // The license being replaced carries the revocation count forward.
+    let revocations = ensure_not_revoked(&claims.license_id, stored_revocations()?)
+        .map_err(|e| format!("License rejected: {e}"))?;
 
     let mut file = ProLicenseFile {
-        license_key: key.into(),
-        tier: "Guardian PRO".into(),
+        license_key: token.into(),
+        tier: claims.tier,
         activated_at: Utc::now().to_rfc3339(),
         valid: true,
-        device_id: current_device_id(),
-        provider: provider.into(),
+        device_id,
+        provider: "offline".into(),
         signature: None,
+        refreshed_at: None,
+        revocations,
     };
 
-    file.signature = Some(sign_license(&file)?);
+    write_license_file(&mut file)?;
 
-    fs::create_dir_all(&pro_root())
-        .map_err(|e| format!("Failed to create ~/.nightcore/pro: {e}"))?;
+    Ok(true)
+}
 
-    fs::write(
-        license_path(),
-        serde_json::to_string_pretty(&file).unwrap(),
-    )
-    .map_err(|e| format!("Failed to write license.json: {e}"))?;
+const REFRESH_TIMEOUT: Duration = Duration::from_secs(10);
 
-    Ok(true)
+#[derive(Debug, Deserialize)]
+struct RefreshConfig {
+    endpoint: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RefreshRequest<'a> {
+    license_id: &'a str,
+    token: &'a str,
+    device: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct RefreshResponse {
+    /// active | revoked | expired
+    status: String,
+    /// Reissued token, e.g. with a later expiry or new device list.
+    #[serde(default)]
+    token: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+/// `NIGHTCORE_LICENSE_ENDPOINT`, else `pro/license_refresh.json`. No
+/// endpoint means licenses are only ever checked offline.
+fn refresh_endpoint() -> Result<Option<String>, String> {
+    if let Ok(url) = env::var("NIGHTCORE_LICENSE_ENDPOINT") {
+        if !url.trim().is_empty() {
+            return Ok(Some(url.trim().to_string()));
+        }
+    }
+
+    let p = refresh_config_path();
+    if !p.exists() {
+        return Ok(None);
+    }
+
+    let raw = fs::read_to_string(&p)
+        .map_err(|e| format!("Failed to read license_refresh.json: {e}"))?;
+    let cfg: RefreshConfig = serde_json::from_str(&raw)
+        .map_err(|e| format!("Invalid license_refresh.json: {e}"))?;
+
+    Ok(Some(cfg.endpoint))
+}
+
+/// Optional online check of the stored license. The server may revoke
+/// it or hand back a reissued token, which must verify offline before
+/// it replaces the stored one. Network failures leave the license as
+/// it was.
+#[tauri::command]
+pub fn pro_refresh_license() -> Result<ProStatus, String> {
+    let Some(mut lic) = load_license_file()? else {
+        return Err("No license is activated".into());
+    };
+    let claims = check_stored(&lic)?;
+
+    let Some(endpoint) = refresh_endpoint()? else {
+        return Err("No license refresh endpoint is configured; the license is checked offline only".into());
+    };
+
+    let fp = license::device_fingerprint(&lic.device_id);
+
+    let client = Client::builder()
+        .timeout(REFRESH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("HTTP client init failed: {e}"))?;
+
+    let resp: RefreshResponse = client
+        .post(&endpoint)
+        .json(&RefreshRequest {
+            license_id: &claims.license_id,
+            token: &lic.license_key,
+            device: &fp,
+        })
+        .send()
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Refresh failed, offline license still applies: {e}"))?
+        .json()
+        .map_err(|e| format!("Invalid refresh response: {e}"))?;
+
+    match resp.status.as_str() {
+        "active" => {
+            if let Some(token) = resp.token.as_deref().map(str::trim) {
+                let renewed = license::verify(token, &fp, Utc::now())
+                    .map_err(|e| format!("Refreshed license rejected: {e:#}"))?;
+                if renewed.license_id != claims.license_id {
+                    return Err(format!(
+                        "Refresh returned license {} for {}",
+                        renewed.license_id, claims.license_id
+                    ));
+                }
+                lic.license_key = token.into();
+                lic.tier = renewed.tier;
+            }
+        }
+        status @ ("revoked" | "expired") => {
+            let expected = lic.revocations.max(revocation_mark()?);
+            lic.revocations = record_revoked(&claims.license_id, status, expected)?;
+            lic.valid = false;
+        }
+        other => {
+            return Err(format!(
+                "Unknown refresh status '{other}'{}",
+                resp.message.map(|m| format!(": {m}")).unwrap_or_default()
+            ));
+        }
+    }
+
+    lic.refreshed_at = Some(Utc::now().to_rfc3339());
+    write_license_file(&mut lic)?;
+
+    get_pro_status()
 }
 
+/// Remove the stored license. The revocation list and its mark stay,
+/// so a revoked token cannot be applied again afterwards.
 #[tauri::command]
 pub fn pro_deactivate() -> Result<bool, String> {
     if license_path().exists() {
@@ -350,6 +790,7 @@ pub fn pro_load_policies() -> Result<PolicyFile, String> {
         return Ok(PolicyFile {
             allow: vec![],
             block: vec![],
+            quarantine: QuarantineRules::default(),
         });
     }
 
@@ -360,84 +801,70 @@ pub fn pro_load_policies() -> Result<PolicyFile, String> {
         .map_err(|e| format!("Invalid policies.json: {e}"))
 }
 
+/// Operator-signed audit entry for PRO actions.
+fn audit_pro_action(action: &str, detail: serde_json::Value) -> Result<(), String> {
+    let root = crate::resolve_worker_runtime_root()?;
+    let op = crate::operator::active(&root).map_err(|e| e.to_string())?;
+
+    crate::audit::record_signed(&root, &op, action, None, detail)
+        .map_err(|e| format!("Audit write failed: {e}"))
+}
+
 #[tauri::command]
 pub fn pro_save_policies(policies: PolicyFile) -> Result<bool, String> {
-    fs::create_dir_all(&pro_root())
-        .map_err(|e| format!("Failed to create ~/.nightcore/pro: {e}"))?;
+    policies
+        .quarantine
+        .validate()
+        .map_err(|e| format!("Invalid quarantine rules: {e}"))?;
 
-    fs::write(
-        policies_path(),
-        serde_json::to_string_pretty(&policies).unwrap(),
-    )
-    .map_err(|e| format!("Failed to write policies.json: {e}"))?;
+    let body = serde_json::to_string_pretty(&policies).unwrap();
 
-    Ok(true)
-}
+    write_atomic(&policies_path(), body.as_bytes(), Expect::Any)
+        .map_err(|e| format!("Failed to write policies.json: {e}"))?;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct GuardianDecisionLiteLog {
-    pub timestamp: String,
-    pub tenant: String,
-    pub backend: String,
-    pub proof_mode: bool,
-    pub decision: String,
-    pub reason: String,
-    pub threat_score: u8,
-    pub threat_label: String,
-    pub threat_color: String,
-    pub sha256: String,
-    pub first_seen: bool,
-    pub known_sha: bool,
-    pub wasm_size_bytes: u64,
-    pub memory_request_mb: u64,
-    pub runtime_request_ms: u64,
-    pub wasi_fs_access: bool,
-    pub wasi_net_access: bool,
-    pub wasi_imports: Vec<String>,
-    pub policy_exists: bool,
-    pub backend_allowed: bool,
-    pub trusted_signer: bool,
+    audit_pro_action(
+        "policies_saved",
+        serde_json::json!({ "sha256": crate::submission::sha256_hex(body.as_bytes()) }),
+    )?;
+
+    Ok(true)
 }
 
+/// Decisions matching the quarantine rules in the PRO policies. The
+/// same rules drive auto-quarantine (see `quarantine::apply_to_run`).
 #[tauri::command]
 pub fn pro_list_quarantine(app: tauri::AppHandle) -> Result<Vec<QuarantineEntry>, String> {
     // ✅ Read from authoritative runtime guardian log (beta: log-only quarantine)
-    let raw = match crate::read_runtime_file("logs/guardian_decisions.jsonl".into()) {
-        Ok(v) => v,
-        Err(_) => return Ok(vec![]),
-    };
-
     let worker_root = crate::resolve_worker_root(&app)
         .map_err(|e| format!("resolve_worker_root failed: {e}"))?;
 
-    let mut out = vec![];
-
-    for line in raw.lines() {
-        if let Ok(parsed) = serde_json::from_str::<GuardianDecisionLiteLog>(line) {
-            let is_quarantined =
-                parsed.threat_score >= 85
-                || parsed.reason.to_lowercase().contains("quarantine");
-
-            if !is_quarantined {
-                continue;
-            }
+    let rules = quarantine::load_rules(&worker_root).map_err(|e| format!("{e:#}"))?;
 
-            let name = format!("{}-{}", parsed.tenant, parsed.timestamp);
+    let history = crate::decisions::load_all(&worker_root);
+    let hits = rules.evaluate(&history);
 
-            let runtime_path = home_dir()
-                .join(".nightcore")
-                .join("logs")
-                .join("guardian_decisions.jsonl");
+    let runtime_path = home_dir()
+        .join(".nightcore")
+        .join("logs")
+        .join("guardian_decisions.jsonl");
+    let masked = mask_worker_path(&worker_root, &runtime_path.to_string_lossy());
 
-            let masked = mask_worker_path(&worker_root, &runtime_path.to_string_lossy());
+    let mut out = vec![];
 
-            out.push(QuarantineEntry {
-                name,
-                tenant: parsed.tenant,
-                timestamp: parsed.timestamp,
-                path: masked,
-            });
+    for (d, matched) in history.into_iter().zip(hits) {
+        if matched.is_empty() {
+            continue;
         }
+
+        out.push(QuarantineEntry {
+            name: format!("{}-{}", d.tenant, d.timestamp),
+            tenant: d.tenant,
+            timestamp: d.timestamp,
+            path: masked.clone(),
+            threat_score: d.threat_score,
+            reason: d.reason,
+            rules: matched,
+        });
     }
 
     Ok(out)
@@ -446,34 +873,308 @@ pub fn pro_list_quarantine(app: tauri::AppHandle) -> Result<Vec<QuarantineEntry>
 #[tauri::command]
 pub fn pro_restore_quarantine(
     _app: tauri::AppHandle,
-    _name: String,
+    name: String,
 ) -> Result<bool, String> {
+    audit_pro_action(
+        "quarantine_restore",
+        serde_json::json!({ "name": name, "result": "disabled" }),
+    )?;
+
     Err("Restore is disabled in log-only quarantine mode.".into())
 }
 
 #[tauri::command]
 pub fn pro_delete_quarantine(
     _app: tauri::AppHandle,
-    _name: String,
+    name: String,
 ) -> Result<bool, String> {
+    audit_pro_action(
+        "quarantine_delete",
+        serde_json::json!({ "name": name, "result": "disabled" }),
+    )?;
+
     Err("Delete is disabled in log-only quarantine mode.".into())
 }
 
+/// Stop every run the console started (and what those runs spawned),
+/// escalating to a forced kill after a grace period. Processes this
+/// console did not start are left alone. The grace period is waited out
+/// on a blocking thread, off both the main thread and the async runtime.
 #[tauri::command]
-pub fn pro_kill_all_running() -> Result<bool, String> {
-    use std::process::Command;
+pub async fn pro_kill_all_running() -> Result<Vec<KillReport>, String> {
+    let root = crate::resolve_worker_runtime_root()?;
+
+    let reports = tauri::async_runtime::spawn_blocking(move || process_registry::kill_all(&root))
+        .await
+        .map_err(|e| format!("Kill switch failed: {e}"))?
+        .map_err(|e| format!("Kill switch failed: {e}"))?;
+
+    audit_pro_action(
+        "kill_switch",
+        serde_json::json!({
+            "targeted": reports.len(),
+            "exited": reports.iter().filter(|r| r.exited).count(),
+            "processes": reports,
+        }),
+    )?;
+
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+    use serde_json::{json, Value};
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
 
-    #[cfg(target_os = "windows")]
-    {
-        let _ = Command::new("taskkill").args(["/IM", "wasmtime.exe", "/F"]).output();
-        let _ = Command::new("taskkill").args(["/IM", "firecracker.exe", "/F"]).output();
+    use crate::license::test_vendor;
+
+    /// Point this thread's PRO directory at a fresh temp dir.
+    fn pro_home() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        TEST_PRO_ROOT.with(|p| *p.borrow_mut() = dir.path().join("pro"));
+        dir
     }
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        let _ = Command::new("pkill").arg("-f").arg("wasmtime").output();
-        let _ = Command::new("pkill").arg("-f").arg("firecracker").output();
+    fn token(license_id: &str, days: i64, device: &str) -> String {
+        let expires = Utc::now() + TimeDelta::days(days);
+        test_vendor::issue(&test_vendor::claims(license_id, Some(expires), &[device]))
     }
 
-    Ok(true)
+    fn this_device() -> String {
+        current_device_fingerprint().unwrap()
+    }
+
+    /// Refresh endpoint answering each request with the next reply. The
+    /// handle yields the request bodies it received.
+    fn stub_server(replies: Vec<Value>) -> thread::JoinHandle<Vec<Value>> {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/refresh", listener.local_addr().unwrap());
+        write_atomic(&refresh_config_path(), json!({ "endpoint": endpoint }).to_string().as_bytes(), Expect::Any)
+            .unwrap();
+
+        thread::spawn(move || {
+            let mut seen = vec![];
+            for reply in replies {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(&stream);
+
+                let mut len = 0;
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                    if let Some((k, v)) = line.split_once(':') {
+                        if k.eq_ignore_ascii_case("content-length") {
+                            len = v.trim().parse().unwrap();
+                        }
+                    }
+                    line.clear();
+                }
+                let mut body = vec![0; len];
+                reader.read_exact(&mut body).unwrap();
+                seen.push(serde_json::from_slice(&body).unwrap());
+
+                let reply = reply.to_string();
+                write!(
+                    &stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+                    reply.len()
+                )
+                .unwrap();
+            }
+            seen
+        })
+    }
+
+    #[test]
+    fn refresh_takes_a_reissued_token() {
+        let _home = pro_home();
+        let first = token("L1", 1, &this_device());
+        pro_apply_license(first.clone()).unwrap();
+        let before = get_pro_status().unwrap();
+        assert!(before.is_pro);
+        assert!(before.refreshed_at.is_none());
+
+        let reissued = token("L1", 365, &this_device());
+        let server = stub_server(vec![json!({ "status": "active", "token": reissued })]);
+
+        let after = pro_refresh_license().unwrap();
+        assert!(after.is_pro);
+        assert!(after.refreshed_at.is_some());
+        assert!(after.expires_at > before.expires_at);
+        assert_eq!(load_license_file().unwrap().unwrap().license_key, reissued);
+
+        let seen = server.join().unwrap();
+        assert_eq!(seen[0]["license_id"], "L1");
+        assert_eq!(seen[0]["token"], first.as_str());
+        assert_eq!(seen[0]["device"], this_device().as_str());
+    }
+
+    #[test]
+    fn revocation_survives_reapplying_the_token() {
+        let _home = pro_home();
+        let t = token("L1", 30, &this_device());
+        pro_apply_license(t.clone()).unwrap();
+
+        stub_server(vec![json!({ "status": "revoked" })]);
+        let status = pro_refresh_license().unwrap();
+        assert!(!status.is_pro);
+        assert!(status.detail.unwrap().contains("revoked"));
+
+        let again = pro_apply_license(t.clone()).unwrap_err();
+        assert!(again.contains("L1 was reported revoked"), "{again}");
+
+        pro_deactivate().unwrap();
+        assert!(pro_apply_license(t.clone()).is_err());
+        assert!(!get_pro_status().unwrap().is_pro);
+
+        // Deactivating removes the license, not the count of revocations.
+        let saved = fs::read(revoked_path()).unwrap();
+        fs::remove_file(revoked_path()).unwrap();
+        let missing = pro_apply_license(t.clone()).unwrap_err();
+        assert!(missing.contains("revoked.json is missing"), "{missing}");
+        fs::write(revoked_path(), saved).unwrap();
+
+        // Another license is unaffected.
+        pro_apply_license(token("L2", 30, &this_device())).unwrap();
+        assert!(get_pro_status().unwrap().is_pro);
+
+        // Emptying the list by hand does not clear the revocation.
+        let mut file: Value = serde_json::from_str(&fs::read_to_string(revoked_path()).unwrap()).unwrap();
+        file["licenses"] = json!([]);
+        fs::write(revoked_path(), file.to_string()).unwrap();
+        let tampered = pro_apply_license(t).unwrap_err();
+        assert!(tampered.contains("revoked.json was modified"), "{tampered}");
+        assert!(!get_pro_status().unwrap().is_pro);
+    }
+
+    #[test]
+    fn deleting_the_revocation_list_is_detected() {
+        let _home = pro_home();
+        let t = token("L1", 30, &this_device());
+        pro_apply_license(t.clone()).unwrap();
+
+        stub_server(vec![json!({ "status": "revoked" })]);
+        pro_refresh_license().unwrap();
+        assert_eq!(load_license_file().unwrap().unwrap().revocations, 1);
+
+        fs::remove_file(revoked_path()).unwrap();
+        let again = pro_apply_license(t).unwrap_err();
+        assert!(again.contains("revoked.json is missing"), "{again}");
+        assert!(pro_apply_license(token("L2", 30, &this_device())).is_err());
+
+        // Nor can the counter be edited down.
+        let mut file: Value = serde_json::from_str(&fs::read_to_string(license_path()).unwrap()).unwrap();
+        file["revocations"] = json!(0);
+        fs::write(license_path(), file.to_string()).unwrap();
+        assert!(get_pro_status().unwrap().detail.unwrap().contains("modified"));
+
+        // Or the mark kept beside it.
+        let mut mark: Value = serde_json::from_str(&fs::read_to_string(revocation_mark_path()).unwrap()).unwrap();
+        mark["revocations"] = json!(0);
+        fs::write(revocation_mark_path(), mark.to_string()).unwrap();
+        let edited = pro_apply_license(token("L2", 30, &this_device())).unwrap_err();
+        assert!(edited.contains("revocation_mark.json was modified"), "{edited}");
+    }
+
+    #[test]
+    fn refresh_rejects_bad_tokens_and_keeps_the_license() {
+        let _home = pro_home();
+        let t = token("L1", 30, &this_device());
+        pro_apply_license(t.clone()).unwrap();
+
+        let forged = {
+            let good = token("L1", 365, &this_device());
+            let (signed, _) = good.rsplit_once('.').unwrap();
+            format!("{signed}.{}", "A".repeat(86))
+        };
+        let server = stub_server(vec![
+            json!({ "status": "active", "token": "not-a-token" }),
+            json!({ "status": "active", "token": forged }),
+            json!({ "status": "active", "token": token("L2", 365, &this_device()) }),
+            json!({ "status": "active", "token": token("L1", 365, "another-device") }),
+            json!({ "status": "suspended", "message": "ask support" }),
+        ]);
+
+        for expect in ["rejected", "rejected", "Refresh returned license L2", "rejected", "ask support"] {
+            let e = pro_refresh_license().unwrap_err();
+            assert!(e.contains(expect), "{e}");
+        }
+        assert_eq!(server.join().unwrap().len(), 5);
+
+        let stored = load_license_file().unwrap().unwrap();
+        assert_eq!(stored.license_key, t);
+        assert!(stored.refreshed_at.is_none());
+        assert!(get_pro_status().unwrap().is_pro);
+    }
+
+    #[test]
+    fn refresh_needs_an_endpoint_and_a_license() {
+        let _home = pro_home();
+        assert!(pro_refresh_license().unwrap_err().contains("No license is activated"));
+
+        pro_apply_license(token("L1", 30, &this_device())).unwrap();
+        assert!(pro_refresh_license().unwrap_err().contains("checked offline only"));
+    }
+
+    #[test]
+    fn legacy_activations_explain_what_to_do() {
+        let _home = pro_home();
+        let legacy = json!({
+            "license_key": "ABCD1234-EF567890-11223344-55667788",
+            "tier": "pro",
+            "activated_at": "2025-01-01T00:00:00Z",
+            "valid": true,
+            "device_id": current_device_id().unwrap(),
+            "provider": "gumroad",
+            "signature": "c2lnbmF0dXJl",
+        });
+        write_atomic(&license_path(), legacy.to_string().as_bytes(), Expect::Any).unwrap();
+
+        let status = get_pro_status().unwrap();
+        assert!(!status.is_pro);
+        let detail = status.detail.unwrap();
+        assert!(detail.contains("Gumroad"), "{detail}");
+        assert!(detail.contains(&this_device()), "{detail}");
+    }
+
+    #[test]
+    fn device_id_ignores_user_and_host_variables() {
+        let _home = pro_home();
+        let before = current_device_id().unwrap();
+
+        let after = device_id_in(&|k| match k {
+            "USER" => Some("mallory".into()),
+            "HOSTNAME" => Some("elsewhere".into()),
+            _ => process_env(k),
+        })
+        .unwrap();
+
+        assert_eq!(before, after);
+        assert!(!after.contains("mallory"));
+    }
+
+    #[test]
+    fn edited_license_file_is_refused() {
+        let _home = pro_home();
+        pro_apply_license(token("L1", 30, &this_device())).unwrap();
+
+        let mut file: Value = serde_json::from_str(&fs::read_to_string(license_path()).unwrap()).unwrap();
+        file["tier"] = json!("enterprise");
+        fs::write(license_path(), file.to_string()).unwrap();
+
+        assert!(get_pro_status().unwrap().detail.unwrap().contains("modified"));
+
+        // Dropping the signature does not get around the check.
+        file["tier"] = json!("pro");
+        file.as_object_mut().unwrap().remove("signature");
+        fs::write(license_path(), file.to_string()).unwrap();
+        let status = get_pro_status().unwrap();
+        assert!(!status.is_pro);
+        assert!(status.detail.unwrap().contains("modified"));
+    }
 }
@@ -0,0 +1,525 @@
+use serde::{Serialize, Deserialize};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::{Child, Command, Output},
+    thread,
+    time::{Duration, Instant},
+};
+use anyhow::{Context, Result};
+use chrono::Utc;
+
+use crate::atomic_fs;
+
+/// Grace period between the polite signal and the forced one.
+pub const TERM_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait for the forced signal to take effect.
+const KILL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A process the console started. On Unix it leads its own process group,
+/// so whatever it spawns (wasmtime, firecracker) is signalled with it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrackedProcess {
+    pub pid: u32,
+    #[serde(default)]
+    pub pgid: Option<u32>,
+    /// Kernel start time of the pid (Linux), to tell a recycled pid apart.
+    #[serde(default)]
+    pub start_ticks: Option<u64>,
+    pub started_at: String,
+    #[serde(default)]
+    pub tenant: Option<String>,
+    #[serde(default)]
+    pub backend: Option<String>,
+    pub command: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct Registry {
+    #[serde(default)]
+    processes: Vec<TrackedProcess>,
+}
+
+/// What the kill switch did to one tracked process.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KillReport {
+    pub pid: u32,
+    #[serde(default)]
+    pub pgid: Option<u32>,
+    #[serde(default)]
+    pub tenant: Option<String>,
+    #[serde(default)]
+    pub backend: Option<String>,
+    pub command: Vec<String>,
+    pub started_at: String,
+    /// Signals sent, in order.
+    pub signals: Vec<String>,
+    pub exited: bool,
+    /// already_exited | pid_reused | terminated | killed | survived | signal_failed
+    pub outcome: String,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+fn registry_path(worker_root: &Path) -> PathBuf {
+    worker_root.join("state").join("processes.json")
+}
+
+/// Read-modify-write of the registry, retried when a concurrent launch
+/// or exit changed it underneath.
+fn update<F>(worker_root: &Path, mut f: F) -> Result<()>
+where
+    F: FnMut(&mut Registry),
+{
+    atomic_fs::update_versioned(&registry_path(worker_root), |reg: &mut Registry| {
+        f(reg);
+        Ok(())
+    })
+}
+
+pub fn list(worker_root: &Path) -> Result<Vec<TrackedProcess>> {
+    let (reg, _) = atomic_fs::load_versioned::<Registry>(&registry_path(worker_root))?;
+    Ok(reg.processes)
+}
+
+fn unregister(worker_root: &Path, pid: u32) -> Result<()> {
+    update(worker_root, |reg| reg.processes.retain(|p| p.pid != pid))
+}
+
+/// Value of `flag` in a worker argument list, given either as the next
+/// argument or as `flag=value`.
+pub fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter().enumerate().find_map(|(i, a)| {
+        if a == flag {
+            args.get(i + 1).map(|s| s.as_str())
+        } else {
+            a.strip_prefix(flag).and_then(|rest| rest.strip_prefix('='))
+        }
+    })
+}
+
+/// A running child that stays in the registry until it is waited on or
+/// dropped.
+pub struct TrackedChild {
+    child: Option<Child>,
+    worker_root: PathBuf,
+    pid: u32,
+}
+
+impl TrackedChild {
+    pub fn child_mut(&mut self) -> &mut Child {
+        self.child.as_mut().expect("child present until consumed")
+    }
+
+    pub fn wait_with_output(mut self) -> io::Result<Output> {
+        self.child.take().expect("child present until consumed").wait_with_output()
+    }
+}
+
+impl Drop for TrackedChild {
+    fn drop(&mut self) {
+        let _ = unregister(&self.worker_root, self.pid);
+    }
+}
+
+/// Spawn `cmd` in a new process group and record it under `state/`.
+/// The caller sets stdio before calling.
+pub fn spawn(
+    worker_root: &Path,
+    cmd: &mut Command,
+    tenant: Option<&str>,
+    backend: Option<&str>,
+) -> Result<TrackedChild> {
+    sys::new_group(cmd);
+
+    let command = std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|s| s.to_string_lossy().to_string())
+        .collect();
+
+    let mut child = cmd
+        .spawn()
+        .with_context(|| format!("starting {}", cmd.get_program().to_string_lossy()))?;
+    let pid = child.id();
+
+    let entry = TrackedProcess {
+        pid,
+        pgid: sys::GROUPS.then_some(pid),
+        start_ticks: sys::start_ticks(pid),
+        started_at: Utc::now().to_rfc3339(),
+        tenant: tenant.map(|t| t.to_string()),
+        backend: backend.map(|b| b.to_string()),
+        command,
+    };
+
+    let recorded = update(worker_root, |reg| {
+        reg.processes.retain(|p| p.pid != pid);
+        reg.processes.push(entry.clone());
+    });
+
+    // An unrecorded run is out of the kill switch's reach, so it does
+    // not get to run at all.
+    if let Err(e) = recorded {
+        let _ = sys::signal(&entry, true);
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(e.context("recording the worker process; it was stopped"));
+    }
+
+    Ok(TrackedChild { child: Some(child), worker_root: worker_root.to_path_buf(), pid })
+}
+
+/// Wait until none of `ps` is alive, or `timeout` passes; whichever
+/// comes first. Returns which of them are gone.
+fn wait_gone(ps: &[&TrackedProcess], timeout: Duration) -> Vec<bool> {
+    let start = Instant::now();
+    loop {
+        let gone: Vec<bool> = ps.iter().map(|p| !sys::alive(p)).collect();
+        if gone.iter().all(|g| *g) || start.elapsed() >= timeout {
+            return gone;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Report for `p`, already settled if it needs no signal at all.
+fn begin(p: &TrackedProcess) -> KillReport {
+    let mut r = KillReport {
+        pid: p.pid,
+        pgid: p.pgid,
+        tenant: p.tenant.clone(),
+        backend: p.backend.clone(),
+        command: p.command.clone(),
+        started_at: p.started_at.clone(),
+        signals: vec![],
+        exited: false,
+        outcome: String::new(),
+        error: None,
+    };
+
+    if !sys::alive(p) {
+        r.exited = true;
+        r.outcome = "already_exited".into();
+        return r;
+    }
+
+    // The leader may be gone while its group lives on; only a leader that
+    // is present with a different start time is someone else's process
+    // (and then the old group is gone too: a pid still in use as a group
+    // id is never handed out again).
+    if let (Some(then), Some(now)) = (p.start_ticks, sys::start_ticks(p.pid)) {
+        if then != now {
+            r.outcome = "pid_reused".into();
+        }
+    }
+    r
+}
+
+/// Stop every target together: the polite signal to all of them, one
+/// shared grace period, then the forced signal to whoever is left. A
+/// polite signal that fails (Windows refuses a `taskkill` without `/F`
+/// for many processes) goes straight to the forced one.
+fn stop_all(targets: &[TrackedProcess]) -> Vec<KillReport> {
+    let mut reports: Vec<KillReport> = targets.iter().map(begin).collect();
+
+    for (forced, label, timeout, outcome) in [
+        (false, sys::TERM_LABEL, TERM_TIMEOUT, "terminated"),
+        (true, sys::KILL_LABEL, KILL_TIMEOUT, "killed"),
+    ] {
+        let mut signalled = vec![];
+
+        for (p, r) in targets.iter().zip(reports.iter_mut()) {
+            if !r.outcome.is_empty() {
+                continue;
+            }
+            match sys::signal(p, forced) {
+                Ok(()) => {
+                    r.signals.push(label.into());
+                    signalled.push(p);
+                }
+                // Exited between the check and the signal.
+                Err(_) if !sys::alive(p) => {
+                    r.exited = true;
+                    r.outcome = "already_exited".into();
+                }
+                Err(e) => {
+                    r.error = Some(e.to_string());
+                    if forced {
+                        r.outcome = "signal_failed".into();
+                    }
+                }
+            }
+        }
+
+        let gone = wait_gone(&signalled, timeout);
+        for (p, gone) in signalled.iter().zip(gone) {
+            if let Some(r) = reports.iter_mut().find(|r| r.pid == p.pid) {
+                if gone {
+                    r.exited = true;
+                    r.outcome = outcome.into();
+                }
+            }
+        }
+    }
+
+    for r in reports.iter_mut().filter(|r| r.outcome.is_empty()) {
+        r.outcome = "survived".into();
+    }
+    reports
+}
+
+/// Signal every tracked process matching `pred`: the polite signal first,
+/// the forced one after [`TERM_TIMEOUT`]. Processes that are gone (or whose
+/// pid now belongs to something else) leave the registry.
+pub fn terminate<F>(worker_root: &Path, pred: F) -> Result<Vec<KillReport>>
+where
+    F: Fn(&TrackedProcess) -> bool,
+{
+    let targets: Vec<TrackedProcess> = list(worker_root)?.into_iter().filter(|p| pred(p)).collect();
+
+    let reports = stop_all(&targets);
+
+    let done: Vec<u32> = reports
+        .iter()
+        .filter(|r| r.exited || r.outcome == "pid_reused")
+        .map(|r| r.pid)
+        .collect();
+    update(worker_root, |reg| reg.processes.retain(|p| !done.contains(&p.pid)))?;
+
+    Ok(reports)
+}
+
+pub fn kill_all(worker_root: &Path) -> Result<Vec<KillReport>> {
+    terminate(worker_root, |_| true)
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::{io, os::unix::process::CommandExt, process::Command};
+
+    use super::TrackedProcess;
+
+    pub const GROUPS: bool = true;
+    pub const TERM_LABEL: &str = "SIGTERM";
+    pub const KILL_LABEL: &str = "SIGKILL";
+
+    pub fn new_group(cmd: &mut Command) {
+        cmd.process_group(0);
+    }
+
+    fn target(p: &TrackedProcess) -> libc::pid_t {
+        match p.pgid {
+            Some(g) => -(g as libc::pid_t),
+            None => p.pid as libc::pid_t,
+        }
+    }
+
+    pub fn alive(p: &TrackedProcess) -> bool {
+        // Signal 0 only checks existence. EPERM means it exists but is
+        // not ours to signal.
+        let rc = unsafe { libc::kill(target(p), 0) };
+        rc == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+
+    pub fn signal(p: &TrackedProcess, forced: bool) -> io::Result<()> {
+        let sig = if forced { libc::SIGKILL } else { libc::SIGTERM };
+        if unsafe { libc::kill(target(p), sig) } == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    /// Field 22 of `/proc/<pid>/stat`, counted after the parenthesized
+    /// command name (which may itself contain spaces).
+    #[cfg(target_os = "linux")]
+    pub fn start_ticks(pid: u32) -> Option<u64> {
+        let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+        let rest = &stat[stat.rfind(')')? + 1..];
+        rest.split_whitespace().nth(19)?.parse().ok()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn start_ticks(_pid: u32) -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::{io, process::Command};
+
+    use super::TrackedProcess;
+
+    pub const GROUPS: bool = false;
+    pub const TERM_LABEL: &str = "taskkill /T";
+    pub const KILL_LABEL: &str = "taskkill /T /F";
+
+    pub fn new_group(_cmd: &mut Command) {}
+
+    pub fn alive(p: &TrackedProcess) -> bool {
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", p.pid), "/FO", "CSV", "/NH"])
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).contains(&format!("\"{}\"", p.pid)))
+            .unwrap_or(false)
+    }
+
+    /// `/T` takes the whole tree the worker started.
+    pub fn signal(p: &TrackedProcess, forced: bool) -> io::Result<()> {
+        let pid = p.pid.to_string();
+        let mut args = vec!["/PID", pid.as_str(), "/T"];
+        if forced {
+            args.push("/F");
+        }
+
+        let out = Command::new("taskkill").args(&args).output()?;
+        if out.status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(String::from_utf8_lossy(&out.stderr).trim().to_string()))
+        }
+    }
+
+    pub fn start_ticks(_pid: u32) -> Option<u64> {
+        None
+    }
+}
+
+/// Remove entries whose process is gone, e.g. after a console crash.
+pub fn prune(worker_root: &Path) -> Result<()> {
+    if !registry_path(worker_root).exists() {
+        return Ok(());
+    }
+    update(worker_root, |reg| reg.processes.retain(sys::alive))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// Spawn a tracked `sh -c script` and reap it in the background, so a
+    /// killed child does not linger as a zombie that still looks alive.
+    fn start(root: &Path, script: &str, tenant: &str) -> u32 {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", script]);
+        let mut tracked = spawn(root, &mut cmd, Some(tenant), None).unwrap();
+        let pid = tracked.child_mut().id();
+        thread::spawn(move || {
+            let _ = tracked.child_mut().wait();
+        });
+        pid
+    }
+
+    #[test]
+    fn kill_all_shares_one_grace_period() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        let polite = start(root, "sleep 30", "a");
+        // `exec`, so no orphaned sleep is left for init to reap after the
+        // group is killed; the ignored TERM survives the exec.
+        let stubborn: Vec<u32> = ["b", "c"].iter().map(|t| start(root, "trap '' TERM; exec sleep 30", t)).collect();
+        // Give the shells time to install the trap.
+        thread::sleep(Duration::from_millis(300));
+
+        let began = Instant::now();
+        let reports = kill_all(root).unwrap();
+        let took = began.elapsed();
+
+        assert_eq!(reports.len(), 3);
+        let outcome = |pid| reports.iter().find(|r| r.pid == pid).unwrap();
+        assert_eq!(outcome(polite).outcome, "terminated");
+        assert_eq!(outcome(polite).signals, vec!["SIGTERM"]);
+        for pid in stubborn {
+            assert_eq!(outcome(pid).outcome, "killed");
+            assert_eq!(outcome(pid).signals, vec!["SIGTERM", "SIGKILL"]);
+        }
+        assert!(reports.iter().all(|r| r.exited));
+
+        // Stopped together: one grace period, not one per process.
+        assert!(took < TERM_TIMEOUT + KILL_TIMEOUT, "took {took:?}");
+        assert!(list(root).unwrap().is_empty());
+    }
+
+    #[test]
+    fn terminate_only_touches_matching_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        let keep = start(root, "sleep 30", "keep");
+        let stop = start(root, "sleep 30", "stop");
+
+        let reports = terminate(root, |p| p.tenant.as_deref() == Some("stop")).unwrap();
+        assert_eq!(reports.iter().map(|r| r.pid).collect::<Vec<_>>(), vec![stop]);
+
+        let left = list(root).unwrap();
+        assert_eq!(left.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![keep]);
+        assert!(sys::alive(&left[0]));
+
+        let reports = kill_all(root).unwrap();
+        assert_eq!(reports[0].outcome, "terminated");
+        assert_eq!(kill_all(root).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn gone_processes_are_reported_and_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        let mut child = Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+
+        update(root, |reg| {
+            reg.processes.push(TrackedProcess {
+                pid,
+                pgid: Some(pid),
+                start_ticks: None,
+                started_at: Utc::now().to_rfc3339(),
+                tenant: None,
+                backend: None,
+                command: vec!["true".into()],
+            })
+        })
+        .unwrap();
+
+        let reports = kill_all(root).unwrap();
+        assert_eq!(reports[0].outcome, "already_exited");
+        assert!(reports[0].signals.is_empty());
+        assert!(list(root).unwrap().is_empty());
+    }
+
+    #[test]
+    fn unrecorded_runs_are_stopped() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("state")).unwrap();
+        std::fs::write(registry_path(root), "not json").unwrap();
+
+        let pidfile = root.join("pid");
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", &format!("sleep 30 & echo $! > {}; wait", pidfile.display())]);
+        assert!(spawn(root, &mut cmd, Some("t"), None).is_err());
+
+        // The shell and anything it started went with the group, so a
+        // pid it managed to record is not running.
+        thread::sleep(Duration::from_millis(300));
+        if let Ok(pid) = std::fs::read_to_string(&pidfile) {
+            let pid: libc::pid_t = pid.trim().parse().unwrap();
+            assert_ne!(unsafe { libc::kill(pid, 0) }, 0);
+        }
+    }
+
+    #[test]
+    fn arg_value_reads_the_following_argument() {
+        let args: Vec<String> = ["run", "--tenant", "t1", "--backend"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(arg_value(&args, "--tenant"), Some("t1"));
+        assert_eq!(arg_value(&args, "--backend"), None);
+        assert_eq!(arg_value(&args, "--all"), None);
+
+        let args: Vec<String> = ["run", "--tenant=t2", "--tenants=x"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(arg_value(&args, "--tenant"), Some("t2"));
+    }
+}
@@ -0,0 +1,336 @@
+use serde::{Serialize, Deserialize};
+use std::{collections::HashMap, fs, path::{Path, PathBuf}};
+use serde_json::Value;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::audit;
+use crate::decisions;
+use crate::operator;
+use crate::runs::RunRecord;
+use crate::tenant_state;
+use crate::GuardianDecisionLite;
+
+/// Key in the PRO `policies.json` holding the rule set.
+pub const POLICY_KEY: &str = "quarantine";
+
+/// Longest `repeated_denies` window accepted: one year.
+pub const MAX_WINDOW_MINUTES: u64 = 366 * 24 * 60;
+
+/// Deny decisions for one tenant counted over a sliding window.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RepeatedDenies {
+    pub count: usize,
+    pub window_minutes: u64,
+}
+
+/// When a decision counts as quarantined. Any rule that matches is
+/// enough. The defaults match the worker's own quarantine band and
+/// reason text.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuarantineRules {
+    /// Scores at or above this. `null` turns the rule off.
+    #[serde(default = "default_threshold")]
+    pub score_threshold: Option<u8>,
+    /// Reasons containing any of these, case-insensitively.
+    #[serde(default = "default_reasons")]
+    pub reasons: Vec<String>,
+    /// Modules without a trusted signer that import WASI sockets.
+    #[serde(default)]
+    pub untrusted_signer_with_net: bool,
+    #[serde(default)]
+    pub repeated_denies: Option<RepeatedDenies>,
+    /// Suspend a tenant when a console-started run produces a matching
+    /// decision.
+    #[serde(default)]
+    pub auto_quarantine: bool,
+}
+
+fn default_threshold() -> Option<u8> {
+    Some(85)
+}
+
+fn default_reasons() -> Vec<String> {
+    vec!["quarantine".into()]
+}
+
+impl Default for QuarantineRules {
+    fn default() -> Self {
+        QuarantineRules {
+            score_threshold: default_threshold(),
+            reasons: default_reasons(),
+            untrusted_signer_with_net: false,
+            repeated_denies: None,
+            auto_quarantine: false,
+        }
+    }
+}
+
+fn policies_path(worker_root: &Path) -> PathBuf {
+    worker_root.join("pro").join("policies.json")
+}
+
+fn parse_ts(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Rules from the PRO policies, or the defaults when none are saved.
+pub fn load_rules(worker_root: &Path) -> Result<QuarantineRules> {
+    let p = policies_path(worker_root);
+    let Ok(raw) = fs::read_to_string(&p) else {
+        return Ok(QuarantineRules::default());
+    };
+
+    let v: Value = serde_json::from_str(&raw).with_context(|| format!("parsing {}", p.display()))?;
+    match v.get(POLICY_KEY) {
+        Some(r) => serde_json::from_value(r.clone())
+            .with_context(|| format!("parsing {POLICY_KEY} rules in {}", p.display())),
+        None => Ok(QuarantineRules::default()),
+    }
+}
+
+impl QuarantineRules {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(t) = self.score_threshold {
+            if t > 100 {
+                bail!("score_threshold must be 0-100, got {t}");
+            }
+        }
+        if self.reasons.iter().any(|r| r.trim().is_empty()) {
+            bail!("reasons must not be empty strings");
+        }
+        if let Some(r) = &self.repeated_denies {
+            if r.count == 0 || r.window_minutes == 0 {
+                bail!("repeated_denies needs a count and window of at least 1");
+            }
+            if r.window_minutes > MAX_WINDOW_MINUTES {
+                bail!("repeated_denies window must be at most {MAX_WINDOW_MINUTES} minutes");
+            }
+        }
+        Ok(())
+    }
+
+    /// Rules `d` trips on its own, without looking at other decisions.
+    fn own_matches(&self, d: &GuardianDecisionLite) -> Vec<String> {
+        let mut out = vec![];
+
+        if let Some(t) = self.score_threshold {
+            if d.threat_score >= t {
+                out.push(format!("score {} >= {t}", d.threat_score));
+            }
+        }
+
+        let reason = d.reason.to_lowercase();
+        if let Some(r) = self.reasons.iter().find(|r| reason.contains(&r.to_lowercase())) {
+            out.push(format!("reason mentions '{r}'"));
+        }
+
+        if self.untrusted_signer_with_net && !d.trusted_signer && d.wasi_net_access {
+            out.push("untrusted signer with network access".into());
+        }
+
+        out
+    }
+
+    fn denies_rule(&self, n: usize) -> Option<String> {
+        let r = self.repeated_denies.as_ref()?;
+        (n >= r.count).then(|| format!("{n} denies within {} min", r.window_minutes))
+    }
+
+    /// Matched rules for every decision in `history` (oldest first),
+    /// index for index. Empty means not quarantined.
+    pub fn evaluate(&self, history: &[GuardianDecisionLite]) -> Vec<Vec<String>> {
+        // `None` inside: a window too long to represent, which reaches
+        // back to the tenant's first deny.
+        let window = self
+            .repeated_denies
+            .as_ref()
+            .map(|r| i64::try_from(r.window_minutes).ok().and_then(TimeDelta::try_minutes));
+
+        // tenant -> deny instants seen so far, oldest first
+        let mut denies: HashMap<&str, Vec<DateTime<Utc>>> = HashMap::new();
+
+        history
+            .iter()
+            .map(|d| {
+                let mut hits = self.own_matches(d);
+
+                if let (Some(window), "deny", Some(ts)) = (window, d.decision.as_str(), parse_ts(&d.timestamp)) {
+                    let seen = denies.entry(d.tenant.as_str()).or_default();
+                    seen.push(ts);
+                    let since = window.and_then(|w| ts.checked_sub_signed(w));
+                    let n = seen.iter().filter(|t| since.is_none_or(|s| **t > s) && **t <= ts).count();
+                    hits.extend(self.denies_rule(n));
+                }
+
+                hits
+            })
+            .collect()
+    }
+}
+
+/// Latest decision for `tenant` and the rules it matched, if it did.
+pub fn latest_hit(worker_root: &Path, tenant: &str) -> Result<Option<(GuardianDecisionLite, Vec<String>)>> {
+    let rules = load_rules(worker_root)?;
+    let history: Vec<GuardianDecisionLite> = decisions::load_all(worker_root)
+        .into_iter()
+        .filter(|d| d.tenant == tenant)
+        .collect();
+
+    let hits = rules.evaluate(&history);
+
+    Ok(history
+        .into_iter()
+        .zip(hits)
+        .max_by_key(|(d, _)| parse_ts(&d.timestamp))
+        .filter(|(_, h)| !h.is_empty()))
+}
+
+/// With `auto_quarantine` on, suspend each tenant whose decision in
+/// `run` matches the rules. Returns the tenants suspended.
+pub fn apply_to_run(worker_root: &Path, run: &RunRecord) -> Result<Vec<String>> {
+    let rules = load_rules(worker_root)?;
+    if !rules.auto_quarantine || run.decisions.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let history = decisions::load_all(worker_root);
+    let hits = rules.evaluate(&history);
+
+    let mut suspended = vec![];
+    for (d, matched) in history.iter().zip(hits) {
+        let in_run = run.decisions.iter().any(|x| x.tenant == d.tenant && x.timestamp == d.timestamp);
+        if !in_run || matched.is_empty() || suspended.contains(&d.tenant) {
+            continue;
+        }
+        if tenant_state::suspension(worker_root, &d.tenant)?.is_some() {
+            continue;
+        }
+
+        let op = operator::active(worker_root)?;
+        tenant_state::mark_suspended(
+            worker_root,
+            &d.tenant,
+            &op,
+            Some(format!("auto-quarantine: {}", matched.join("; "))),
+        )?;
+
+        audit::record_signed(
+            worker_root,
+            &op,
+            "quarantine_auto",
+            Some(&d.tenant),
+            serde_json::json!({
+                "run_id": run.run_id,
+                "decision_timestamp": d.timestamp,
+                "threat_score": d.threat_score,
+                "rules": matched,
+            }),
+        )?;
+
+        suspended.push(d.tenant.clone());
+    }
+
+    Ok(suspended)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn decision(minute: i64, tenant: &str, verdict: &str, score: u8, reason: &str) -> GuardianDecisionLite {
+        let ts = parse_ts("2026-03-01T00:00:00Z").unwrap() + TimeDelta::minutes(minute);
+        serde_json::from_value(decisions::decision_json(json!({
+            "timestamp": ts.to_rfc3339(), "tenant": tenant, "decision": verdict, "reason": reason,
+            "threat_score": score, "wasi_net_access": true, "trusted_signer": false,
+        })))
+        .unwrap()
+    }
+
+    fn denies(count: usize, window_minutes: u64) -> QuarantineRules {
+        QuarantineRules {
+            score_threshold: None,
+            reasons: vec![],
+            repeated_denies: Some(RepeatedDenies { count, window_minutes }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn own_rules_match_score_reason_and_signer() {
+        let history = [
+            decision(0, "t", "allow", 84, "fine"),
+            decision(1, "t", "allow", 85, "fine"),
+            decision(2, "t", "deny", 10, "Guardian: QUARANTINE band"),
+        ];
+        let hits = QuarantineRules::default().evaluate(&history);
+        assert!(hits[0].is_empty());
+        assert_eq!(hits[1], vec!["score 85 >= 85"]);
+        assert_eq!(hits[2], vec!["reason mentions 'quarantine'"]);
+
+        let rules = QuarantineRules { untrusted_signer_with_net: true, ..Default::default() };
+        assert!(rules.evaluate(&history)[0].contains(&"untrusted signer with network access".to_string()));
+    }
+
+    #[test]
+    fn repeated_denies_slide_per_tenant() {
+        let history = [
+            decision(0, "a", "deny", 0, ""),
+            decision(10, "b", "deny", 0, ""),
+            decision(20, "a", "deny", 0, ""),
+            decision(61, "a", "deny", 0, ""),
+            decision(62, "a", "allow", 0, ""),
+            decision(70, "a", "deny", 0, ""),
+        ];
+        let hits = denies(3, 60).evaluate(&history);
+        // At 61 the deny at 0 has left the window; at 70 three remain.
+        assert!(hits[..5].iter().all(|h| h.is_empty()), "{hits:?}");
+        assert_eq!(hits[5], vec!["3 denies within 60 min"]);
+    }
+
+    #[test]
+    fn huge_windows_do_not_overflow() {
+        let history = [decision(0, "a", "deny", 0, ""), decision(500_000, "a", "deny", 0, "")];
+        for window in [MAX_WINDOW_MINUTES, u64::MAX, i64::MAX as u64 / 60] {
+            assert_eq!(denies(2, window).evaluate(&history)[1].len(), 1, "{window}");
+        }
+    }
+
+    #[test]
+    fn latest_hit_orders_by_instant() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("logs")).unwrap();
+        let write = |history: &[&GuardianDecisionLite]| {
+            let lines: String = history.iter().map(|d| serde_json::to_string(d).unwrap() + "\n").collect();
+            fs::write(root.join("logs/guardian_decisions.jsonl"), lines).unwrap();
+        };
+
+        // 00:30+02:00 is 22:30 the day before, so the clean decision is the latest.
+        let mut flagged = decision(0, "t", "allow", 90, "");
+        flagged.timestamp = "2026-03-01T00:30:00+02:00".into();
+        let mut clean = decision(0, "t", "allow", 10, "");
+        clean.timestamp = "2026-02-28T23:00:00Z".into();
+        write(&[&flagged, &clean]);
+        assert!(latest_hit(root, "t").unwrap().is_none());
+
+        clean.timestamp = "2026-02-28T22:00:00Z".into();
+        write(&[&flagged, &clean]);
+        let (d, hits) = latest_hit(root, "t").unwrap().unwrap();
+        assert_eq!(d.threat_score, 90);
+        assert_eq!(hits, vec!["score 90 >= 85"]);
+    }
+
+    #[test]
+    fn validate_bounds_the_rules() {
+        QuarantineRules::default().validate().unwrap();
+        denies(1, MAX_WINDOW_MINUTES).validate().unwrap();
+
+        assert!(denies(1, MAX_WINDOW_MINUTES + 1).validate().is_err());
+        assert!(denies(0, 60).validate().is_err());
+        assert!(denies(1, 0).validate().is_err());
+        assert!(QuarantineRules { score_threshold: Some(101), ..Default::default() }.validate().is_err());
+        assert!(QuarantineRules { reasons: vec![" ".into()], ..Default::default() }.validate().is_err());
+    }
+}
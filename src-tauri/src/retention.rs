@@ -0,0 +1,845 @@
+use serde::{Serialize, Deserialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+use crate::atomic_fs::{self, write_atomic, Expect};
+use crate::audit;
+use crate::operator::{self, Operator};
+use crate::submission::sha256_hex;
+
+/// Logs under retention, by the stem of `logs/<stem>.jsonl`.
+pub const GUARDIAN_DECISIONS: &str = "guardian_decisions";
+pub const CONSOLE_AUDIT: &str = "console_audit";
+pub const RUN_RECORDS: &str = "execution_runs";
+
+const MANAGED_LOGS: [&str; 3] = [GUARDIAN_DECISIONS, CONSOLE_AUDIT, RUN_RECORDS];
+
+/// Largest `rotate_days` / `keep_days` accepted, about a century.
+const MAX_DAYS: u64 = 36_500;
+
+/// When a log is rotated and how long its segments are kept. Every
+/// limit is optional; a rule with none set never rotates.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RetentionRule {
+    /// Rotate once the active file is larger than this.
+    #[serde(default)]
+    pub rotate_bytes: Option<u64>,
+    /// Rotate once the oldest line in the active file is this old.
+    #[serde(default)]
+    pub rotate_days: Option<u64>,
+    /// Delete segments whose date is older than this.
+    #[serde(default)]
+    pub keep_days: Option<u64>,
+    /// Keep at most this many segments.
+    #[serde(default)]
+    pub keep_segments: Option<usize>,
+}
+
+/// `state/retention.json`. Logs missing from the file use the defaults.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RetentionConfig {
+    #[serde(default)]
+    pub logs: BTreeMap<String, RetentionRule>,
+}
+
+/// Signed record of one rotation, in `logs/seals/<segment>.seal.json`.
+/// Seals of a log chain through `previous_seal_sha256`, so a removed or
+/// edited seal breaks the chain even after its segment was pruned.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SegmentSeal {
+    pub log: String,
+    pub segment: String,
+    pub sealed_at: String,
+    pub sealed_by: String,
+    pub signer_fingerprint: String,
+
+    pub lines: usize,
+    #[serde(default)]
+    pub first_timestamp: Option<String>,
+    #[serde(default)]
+    pub last_timestamp: Option<String>,
+
+    /// Hash of the uncompressed lines.
+    pub content_sha256: String,
+    /// Hash of the `.jsonl.gz` file.
+    pub segment_sha256: String,
+    #[serde(default)]
+    pub previous_seal_sha256: Option<String>,
+
+    /// Operator signature over this seal serialized with `signature`
+    /// set to null.
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SegmentCheck {
+    pub log: String,
+    pub segment: String,
+    /// ok | modified | unreadable | unsealed | pruned, or for the seal
+    /// itself bad_signature | chain_broken
+    pub status: String,
+}
+
+fn default_rule(log: &str) -> RetentionRule {
+    match log {
+        CONSOLE_AUDIT => RetentionRule {
+            rotate_bytes: Some(8 * 1024 * 1024),
+            rotate_days: Some(30),
+            keep_days: Some(365),
+            keep_segments: None,
+        },
+        _ => RetentionRule {
+            rotate_bytes: Some(16 * 1024 * 1024),
+            rotate_days: Some(7),
+            keep_days: Some(90),
+            keep_segments: None,
+        },
+    }
+}
+
+fn config_path(worker_root: &Path) -> PathBuf {
+    worker_root.join("state").join("retention.json")
+}
+
+fn logs_dir(worker_root: &Path) -> PathBuf {
+    worker_root.join("logs")
+}
+
+fn seals_dir(worker_root: &Path) -> PathBuf {
+    logs_dir(worker_root).join("seals")
+}
+
+pub fn active_path(worker_root: &Path, log: &str) -> PathBuf {
+    logs_dir(worker_root).join(format!("{log}.jsonl"))
+}
+
+/// Where the active file is moved while it is being sealed.
+fn staging_path(worker_root: &Path, log: &str) -> PathBuf {
+    logs_dir(worker_root).join(format!("{log}.jsonl.rotating"))
+}
+
+fn seal_path(worker_root: &Path, segment: &str) -> PathBuf {
+    seals_dir(worker_root).join(format!("{segment}.seal.json"))
+}
+
+pub fn load_config(worker_root: &Path) -> Result<RetentionConfig> {
+    let p = config_path(worker_root);
+    let mut cfg: RetentionConfig = match fs::read_to_string(&p) {
+        Ok(raw) => serde_json::from_str(&raw).with_context(|| format!("parsing {}", p.display()))?,
+        Err(_) => RetentionConfig::default(),
+    };
+
+    for log in MANAGED_LOGS {
+        cfg.logs.entry(log.into()).or_insert_with(|| default_rule(log));
+    }
+    Ok(cfg)
+}
+
+pub fn save_config(worker_root: &Path, cfg: &RetentionConfig) -> Result<()> {
+    if let Some(unknown) = cfg.logs.keys().find(|k| !MANAGED_LOGS.contains(&k.as_str())) {
+        bail!("unknown log type '{unknown}'");
+    }
+    for (log, rule) in &cfg.logs {
+        for (field, days) in [("rotate_days", rule.rotate_days), ("keep_days", rule.keep_days)] {
+            if days.is_some_and(|d| d > MAX_DAYS) {
+                bail!("{log}: {field} must be at most {MAX_DAYS}");
+            }
+        }
+    }
+    write_atomic(
+        &config_path(worker_root),
+        serde_json::to_string_pretty(cfg)?.as_bytes(),
+        Expect::Any,
+    )
+}
+
+/// `(date, sequence)` from `<log>.<date>[.<n>].jsonl.gz`.
+fn segment_key(log: &str, name: &str) -> Option<(NaiveDate, u32)> {
+    let middle = name.strip_prefix(log)?.strip_prefix('.')?.strip_suffix(".jsonl.gz")?;
+    let (date, seq) = match middle.split_once('.') {
+        Some((d, n)) => (d, n.parse().ok()?),
+        None => (middle, 0),
+    };
+    Some((NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?, seq))
+}
+
+fn seal_key(log: &str, segment: &str) -> Option<(NaiveDate, u32)> {
+    segment_key(log, &format!("{segment}.jsonl.gz"))
+}
+
+/// Rotated segments of `log`, oldest first.
+pub fn segments(worker_root: &Path, log: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(logs_dir(worker_root)) else {
+        return vec![];
+    };
+
+    let mut found: Vec<((NaiveDate, u32), PathBuf)> = entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            segment_key(log, &name).map(|k| (k, e.path()))
+        })
+        .collect();
+
+    found.sort_by_key(|(k, _)| *k);
+    found.into_iter().map(|(_, p)| p).collect()
+}
+
+fn read_segment(path: &Path) -> Result<String> {
+    let gz = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let mut out = String::new();
+    GzDecoder::new(gz.as_slice())
+        .read_to_string(&mut out)
+        .with_context(|| format!("decompressing {}", path.display()))?;
+    Ok(out)
+}
+
+/// Non-empty lines of one rotated segment.
+pub fn read_segment_lines(path: &Path) -> Result<Vec<String>> {
+    Ok(read_segment(path)?
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.to_string())
+        .collect())
+}
+
+/// Every non-empty line of `log`, across rotated segments, a file being
+/// rotated and the active file, oldest first. Unreadable segments are
+/// skipped rather than hiding the rest of the history.
+pub fn read_lines(worker_root: &Path, log: &str) -> Vec<String> {
+    let mut chunks: Vec<String> = segments(worker_root, log)
+        .iter()
+        .filter_map(|p| read_segment(p).ok())
+        .collect();
+
+    if let Ok(staged) = fs::read_to_string(staging_path(worker_root, log)) {
+        chunks.push(staged);
+    }
+
+    if let Ok(active) = fs::read_to_string(active_path(worker_root, log)) {
+        chunks.push(active);
+    }
+
+    chunks
+        .iter()
+        .flat_map(|c| c.lines())
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.to_string())
+        .collect()
+}
+
+fn line_timestamp(line: &str) -> Option<DateTime<Utc>> {
+    let v: serde_json::Value = serde_json::from_str(line).ok()?;
+    let ts = v.get("timestamp")?.as_str()?;
+    DateTime::parse_from_rfc3339(ts).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// `days` as a duration; `None` past what chrono can represent, which
+/// callers treat as "never".
+fn days_delta(days: u64) -> Option<TimeDelta> {
+    i64::try_from(days).ok().and_then(TimeDelta::try_days)
+}
+
+fn rotation_due(rule: &RetentionRule, bytes: &[u8], now: DateTime<Utc>) -> bool {
+    if bytes.is_empty() {
+        return false;
+    }
+    if rule.rotate_bytes.is_some_and(|max| bytes.len() as u64 > max) {
+        return true;
+    }
+
+    let oldest = String::from_utf8_lossy(bytes).lines().find_map(line_timestamp);
+    match (rule.rotate_days, oldest) {
+        (Some(days), Some(ts)) => days_delta(days).is_some_and(|max| now - ts > max),
+        _ => false,
+    }
+}
+
+fn next_segment_name(worker_root: &Path, log: &str, date: NaiveDate) -> String {
+    let base = format!("{log}.{}", date.format("%Y-%m-%d"));
+    let taken = |n: &str| logs_dir(worker_root).join(format!("{n}.jsonl.gz")).exists();
+
+    if !taken(&base) {
+        return format!("{base}.jsonl.gz");
+    }
+    let n = (1..).find(|i| !taken(&format!("{base}.{i}"))).unwrap_or(1);
+    format!("{base}.{n}.jsonl.gz")
+}
+
+fn last_seal_sha(worker_root: &Path, log: &str) -> Option<String> {
+    let entries = fs::read_dir(seals_dir(worker_root)).ok()?;
+
+    entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let segment = name.strip_suffix(".seal.json")?;
+            seal_key(log, segment).map(|k| (k, e.path()))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .and_then(|(_, p)| fs::read(p).ok())
+        .map(|b| sha256_hex(&b))
+}
+
+fn signed_seal(mut seal: SegmentSeal, op: &Operator) -> Result<SegmentSeal> {
+    seal.signature = None;
+    seal.signature = Some(op.sign(serde_json::to_string(&seal)?.as_bytes()));
+    Ok(seal)
+}
+
+/// Move the active file's contents into a compressed, sealed segment.
+/// The file is renamed aside under its lock and replaced by an empty
+/// one, so writers that lock or reopen the log per append carry on in
+/// the new file. A staging file left by an interrupted rotation is
+/// sealed first.
+pub(crate) fn rotate_one(worker_root: &Path, log: &str, op: &Operator) -> Result<Option<SegmentSeal>> {
+    let staging = staging_path(worker_root, log);
+
+    if !staging.exists() && !atomic_fs::move_aside(&active_path(worker_root, log), &staging)? {
+        return Ok(None);
+    }
+    seal_staged(worker_root, log, op)
+}
+
+/// The newest segment of `log` if the staged bytes start with its
+/// content: a rotation of this staging file that stopped before it
+/// finished. Its name and content are reused rather than sealing the
+/// same lines twice.
+fn interrupted_segment(worker_root: &Path, log: &str, staged: &[u8]) -> Option<(String, Vec<u8>)> {
+    let path = segments(worker_root, log).pop()?;
+    let content = read_segment(&path).ok()?.into_bytes();
+    if content.is_empty() || !staged.starts_with(&content) {
+        return None;
+    }
+
+    let name = path.file_name()?.to_string_lossy().trim_end_matches(".jsonl.gz").to_string();
+    Some((name, content))
+}
+
+fn seal_staged(worker_root: &Path, log: &str, op: &Operator) -> Result<Option<SegmentSeal>> {
+    let staging = staging_path(worker_root, log);
+
+    let bytes = fs::read(&staging).with_context(|| format!("reading {}", staging.display()))?;
+    if bytes.is_empty() {
+        fs::remove_file(&staging)?;
+        return Ok(None);
+    }
+
+    let now = Utc::now();
+    let (segment, content) = match interrupted_segment(worker_root, log, &bytes) {
+        Some(found) => found,
+        None => {
+            let name = next_segment_name(worker_root, log, now.date_naive());
+            let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+            enc.write_all(&bytes)?;
+            write_atomic(&logs_dir(worker_root).join(&name), &enc.finish()?, Expect::Absent)?;
+            (name.trim_end_matches(".jsonl.gz").to_string(), bytes)
+        }
+    };
+
+    let seal_file = seal_path(worker_root, &segment);
+    let seal = match fs::read(&seal_file) {
+        Ok(existing) => serde_json::from_slice(&existing)
+            .with_context(|| format!("parsing {}", seal_file.display()))?,
+        Err(_) => {
+            let gz = fs::read(logs_dir(worker_root).join(format!("{segment}.jsonl.gz")))?;
+            let text = String::from_utf8_lossy(&content);
+            let seal = signed_seal(
+                SegmentSeal {
+                    log: log.into(),
+                    segment: segment.clone(),
+                    sealed_at: now.to_rfc3339(),
+                    sealed_by: op.name.clone(),
+                    signer_fingerprint: op.fingerprint.clone(),
+                    lines: text.lines().filter(|l| !l.trim().is_empty()).count(),
+                    first_timestamp: text.lines().find_map(line_timestamp).map(|t| t.to_rfc3339()),
+                    last_timestamp: text.lines().rev().find_map(line_timestamp).map(|t| t.to_rfc3339()),
+                    content_sha256: sha256_hex(&content),
+                    segment_sha256: sha256_hex(&gz),
+                    previous_seal_sha256: last_seal_sha(worker_root, log),
+                    signature: None,
+                },
+                op,
+            )?;
+            write_atomic(&seal_file, serde_json::to_string_pretty(&seal)?.as_bytes(), Expect::Absent)?;
+            seal
+        }
+    };
+
+    // A writer still holding the old file open may have added to it
+    // after it was read. Those lines are older than anything in the
+    // active log, so they stay staged and are sealed by the next
+    // rotation, which keeps the log in order.
+    let after = fs::read(&staging).with_context(|| format!("reading {}", staging.display()))?;
+    match after.get(content.len()..).filter(|t| !t.is_empty()) {
+        Some(late) => write_atomic(&staging, late, Expect::Any)?,
+        None => fs::remove_file(&staging).with_context(|| format!("removing {}", staging.display()))?,
+    }
+
+    Ok(Some(seal))
+}
+
+/// Delete segments outside the rule. Seals are kept as the record that
+/// the segment existed.
+fn prune(worker_root: &Path, log: &str, rule: &RetentionRule, now: DateTime<Utc>) -> Vec<String> {
+    let all = segments(worker_root, log);
+    let mut removed = vec![];
+
+    let excess = rule
+        .keep_segments
+        .map(|keep| all.len().saturating_sub(keep))
+        .unwrap_or(0);
+
+    for (i, p) in all.iter().enumerate() {
+        let name = p.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let too_old = match (rule.keep_days, segment_key(log, &name)) {
+            (Some(days), Some((date, _))) => days_delta(days).is_some_and(|max| now.date_naive() - date > max),
+            _ => false,
+        };
+
+        if (i < excess || too_old) && fs::remove_file(p).is_ok() {
+            removed.push(name);
+        }
+    }
+
+    removed
+}
+
+/// Rotate and prune every managed log per its rule; `force` rotates any
+/// non-empty log regardless of size and age.
+pub fn run(worker_root: &Path, force: bool) -> Result<Vec<SegmentSeal>> {
+    let cfg = load_config(worker_root)?;
+    let now = Utc::now();
+
+    let mut sealed = vec![];
+    let mut pruned = vec![];
+    let mut op = None;
+
+    for (log, rule) in &cfg.logs {
+        let bytes = fs::read(active_path(worker_root, log)).unwrap_or_default();
+        let interrupted = staging_path(worker_root, log).exists();
+
+        if interrupted || (!bytes.is_empty() && (force || rotation_due(rule, &bytes, now))) {
+            let signer = match op.take() {
+                Some(o) => o,
+                None => operator::active(worker_root)?,
+            };
+            let result = rotate_one(worker_root, log, &signer);
+            op = Some(signer);
+
+            if let Some(seal) = result? {
+                sealed.push(seal);
+            }
+        }
+
+        pruned.extend(prune(worker_root, log, rule, now));
+    }
+
+    if !sealed.is_empty() || !pruned.is_empty() {
+        let op = match op {
+            Some(op) => op,
+            None => operator::active(worker_root)?,
+        };
+        audit::record_signed(
+            worker_root,
+            &op,
+            "logs_rotated",
+            None,
+            serde_json::json!({
+                "sealed": sealed.iter().map(|s| &s.segment).collect::<Vec<_>>(),
+                "pruned": pruned,
+            }),
+        )?;
+    }
+
+    Ok(sealed)
+}
+
+/// Problem with a log's seal itself, by segment: a seal that does not
+/// parse, is not signed by a registered operator, or does not name the
+/// seal before it. Walks the chain oldest first.
+fn seal_problems(worker_root: &Path, log: &str) -> HashMap<String, &'static str> {
+    let mut seals: Vec<((NaiveDate, u32), String, PathBuf)> = fs::read_dir(seals_dir(worker_root))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let segment = name.strip_suffix(".seal.json")?.to_string();
+            seal_key(log, &segment).map(|k| (k, segment, e.path()))
+        })
+        .collect();
+    seals.sort_by_key(|s| s.0);
+
+    let mut out = HashMap::new();
+    let mut previous: Option<String> = None;
+
+    for (_, segment, path) in seals {
+        let bytes = fs::read(&path).unwrap_or_default();
+
+        let problem = match serde_json::from_slice::<SegmentSeal>(&bytes) {
+            Err(_) => Some("unreadable"),
+            Ok(seal) => {
+                let mut unsigned = seal.clone();
+                unsigned.signature = None;
+                let signed = match (&seal.signature, serde_json::to_string(&unsigned)) {
+                    (Some(sig), Ok(msg)) => {
+                        operator::verify_signature(worker_root, &seal.signer_fingerprint, msg.as_bytes(), sig)
+                    }
+                    _ => false,
+                };
+
+                if !signed || seal.log != log || seal.segment != segment {
+                    Some("bad_signature")
+                } else if seal.previous_seal_sha256 != previous {
+                    Some("chain_broken")
+                } else {
+                    None
+                }
+            }
+        };
+
+        if let Some(p) = problem {
+            out.insert(segment, p);
+        }
+        previous = Some(sha256_hex(&bytes));
+    }
+
+    out
+}
+
+/// Check every segment against its seal, every seal's signature and its
+/// link to the seal before it, and report seals whose segment has since
+/// been pruned.
+pub fn verify(worker_root: &Path) -> Vec<SegmentCheck> {
+    let mut out = vec![];
+
+    for log in MANAGED_LOGS {
+        let problems = seal_problems(worker_root, log);
+        let mut seen = vec![];
+
+        for p in segments(worker_root, log) {
+            let segment = p
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .trim_end_matches(".jsonl.gz")
+                .to_string();
+
+            let seal: Option<SegmentSeal> = fs::read_to_string(seal_path(worker_root, &segment))
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok());
+
+            let status = match (seal, fs::read(&p)) {
+                _ if problems.contains_key(&segment) => problems[&segment],
+                (None, _) => "unsealed",
+                (Some(_), Err(_)) => "unreadable",
+                (Some(s), Ok(b)) if s.segment_sha256 == sha256_hex(&b) => "ok",
+                (Some(_), Ok(_)) => "modified",
+            };
+
+            seen.push(segment.clone());
+            out.push(SegmentCheck { log: log.into(), segment, status: status.into() });
+        }
+
+        if let Ok(entries) = fs::read_dir(seals_dir(worker_root)) {
+            for e in entries.flatten() {
+                let name = e.file_name().to_string_lossy().to_string();
+                let Some(segment) = name.strip_suffix(".seal.json") else {
+                    continue;
+                };
+                if seal_key(log, segment).is_some() && !seen.iter().any(|s| s == segment) {
+                    out.push(SegmentCheck {
+                        log: log.into(),
+                        segment: segment.into(),
+                        status: problems.get(segment).copied().unwrap_or("pruned").into(),
+                    });
+                }
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(ts: &str, n: u32) -> String {
+        format!("{{\"timestamp\":\"{ts}\",\"n\":{n}}}\n")
+    }
+
+    fn append(root: &Path, log: &str, text: &str) {
+        fs::create_dir_all(logs_dir(root)).unwrap();
+        let mut f = fs::OpenOptions::new().create(true).append(true).open(active_path(root, log)).unwrap();
+        f.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn rotation_seals_and_chains_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let op = Operator::ephemeral("ops");
+
+        append(root, GUARDIAN_DECISIONS, &(line("2026-01-01T00:00:00Z", 1) + &line("2026-01-02T00:00:00Z", 2)));
+        let first = rotate_one(root, GUARDIAN_DECISIONS, &op).unwrap().unwrap();
+        assert_eq!(first.lines, 2);
+        assert_eq!(first.first_timestamp.as_deref(), Some("2026-01-01T00:00:00+00:00"));
+        assert!(first.previous_seal_sha256.is_none());
+        assert_eq!(fs::read(active_path(root, GUARDIAN_DECISIONS)).unwrap(), b"");
+
+        append(root, GUARDIAN_DECISIONS, &line("2026-01-03T00:00:00Z", 3));
+        let second = rotate_one(root, GUARDIAN_DECISIONS, &op).unwrap().unwrap();
+        let first_seal = fs::read(seal_path(root, &first.segment)).unwrap();
+        assert_eq!(second.previous_seal_sha256, Some(sha256_hex(&first_seal)));
+        assert_ne!(first.segment, second.segment);
+
+        assert_eq!(read_lines(root, GUARDIAN_DECISIONS).len(), 3);
+        assert!(rotate_one(root, GUARDIAN_DECISIONS, &op).unwrap().is_none());
+
+        let mut unsigned = second.clone();
+        unsigned.signature = None;
+        assert_eq!(second.signature, Some(op.sign(serde_json::to_string(&unsigned).unwrap().as_bytes())));
+    }
+
+    #[test]
+    fn appends_during_rotation_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let op = Operator::registered(root, "ops");
+        let active = active_path(root, CONSOLE_AUDIT);
+
+        let writer = {
+            let active = active.clone();
+            std::thread::spawn(move || {
+                for n in 0..400 {
+                    atomic_fs::append_line(&active, line("2026-01-01T00:00:00Z", n).trim_end()).unwrap();
+                }
+            })
+        };
+        let mut rotations = 0;
+        while !writer.is_finished() {
+            if rotate_one(root, CONSOLE_AUDIT, &op).unwrap().is_some() {
+                rotations += 1;
+            }
+        }
+        writer.join().unwrap();
+        assert!(rotations > 0);
+
+        let seen: Vec<u64> = read_lines(root, CONSOLE_AUDIT)
+            .iter()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap()["n"].as_u64().unwrap())
+            .collect();
+        assert_eq!(seen, (0..400).collect::<Vec<_>>());
+        assert!(verify(root).iter().all(|c| c.status == "ok"));
+    }
+
+    #[test]
+    fn late_writes_to_the_moved_file_return_to_the_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let op = Operator::ephemeral("ops");
+
+        append(root, GUARDIAN_DECISIONS, &line("2026-01-01T00:00:00Z", 1));
+        let staging = staging_path(root, GUARDIAN_DECISIONS);
+        // A worker that opened the log before it was moved.
+        let mut held = fs::OpenOptions::new().append(true).open(active_path(root, GUARDIAN_DECISIONS)).unwrap();
+        assert!(atomic_fs::move_aside(&active_path(root, GUARDIAN_DECISIONS), &staging).unwrap());
+
+        held.write_all(line("2026-01-01T00:00:01Z", 2).as_bytes()).unwrap();
+        append(root, GUARDIAN_DECISIONS, &line("2026-01-01T00:00:02Z", 3));
+        assert_eq!(read_lines(root, GUARDIAN_DECISIONS).len(), 3);
+
+        // Rotation resumes from the staged file.
+        let seal = rotate_one(root, GUARDIAN_DECISIONS, &op).unwrap().unwrap();
+        assert_eq!(seal.lines, 2);
+        assert!(!staging.exists());
+        assert_eq!(
+            fs::read_to_string(active_path(root, GUARDIAN_DECISIONS)).unwrap(),
+            line("2026-01-01T00:00:02Z", 3)
+        );
+        assert_eq!(read_lines(root, GUARDIAN_DECISIONS).len(), 3);
+    }
+
+    #[test]
+    fn interrupted_rotation_reuses_its_segment() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let op = Operator::registered(root, "ops");
+        let staging = staging_path(root, GUARDIAN_DECISIONS);
+
+        append(root, GUARDIAN_DECISIONS, &line("2026-01-01T00:00:00Z", 1));
+        let first = rotate_one(root, GUARDIAN_DECISIONS, &op).unwrap().unwrap();
+        let sealed = line("2026-01-01T00:00:00Z", 1);
+
+        // Stopped after the segment was written, before its seal.
+        fs::remove_file(seal_path(root, &first.segment)).unwrap();
+        fs::write(&staging, &sealed).unwrap();
+        let resumed = rotate_one(root, GUARDIAN_DECISIONS, &op).unwrap().unwrap();
+        assert_eq!(resumed.segment, first.segment);
+        assert_eq!(segments(root, GUARDIAN_DECISIONS).len(), 1);
+        assert!(!staging.exists());
+        assert_eq!(read_lines(root, GUARDIAN_DECISIONS), vec![sealed.trim_end().to_string()]);
+
+        // Stopped after the seal, with a late line in the staged file.
+        let late = line("2026-01-01T00:00:01Z", 2);
+        fs::write(&staging, format!("{sealed}{late}")).unwrap();
+        append(root, GUARDIAN_DECISIONS, &line("2026-01-01T00:00:02Z", 3));
+        let again = rotate_one(root, GUARDIAN_DECISIONS, &op).unwrap().unwrap();
+        assert_eq!(again.segment, first.segment);
+        assert_eq!(fs::read_to_string(&staging).unwrap(), late);
+
+        let order = |root: &Path| -> Vec<u64> {
+            read_lines(root, GUARDIAN_DECISIONS)
+                .iter()
+                .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap()["n"].as_u64().unwrap())
+                .collect()
+        };
+        assert_eq!(order(root), vec![1, 2, 3]);
+
+        // The late line is sealed next, ahead of the active log.
+        let next = rotate_one(root, GUARDIAN_DECISIONS, &op).unwrap().unwrap();
+        assert_eq!(next.lines, 1);
+        assert_eq!(next.previous_seal_sha256, Some(sha256_hex(&fs::read(seal_path(root, &first.segment)).unwrap())));
+        rotate_one(root, GUARDIAN_DECISIONS, &op).unwrap().unwrap();
+        assert_eq!(segments(root, GUARDIAN_DECISIONS).len(), 3);
+        assert_eq!(order(root), vec![1, 2, 3]);
+        assert!(verify(root).iter().all(|c| c.status == "ok"));
+    }
+
+    #[test]
+    fn verify_reports_modified_and_pruned_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let op = Operator::registered(root, "ops");
+
+        append(root, CONSOLE_AUDIT, &line("2026-01-01T00:00:00Z", 1));
+        let a = rotate_one(root, CONSOLE_AUDIT, &op).unwrap().unwrap();
+        append(root, CONSOLE_AUDIT, &line("2026-01-02T00:00:00Z", 2));
+        let b = rotate_one(root, CONSOLE_AUDIT, &op).unwrap().unwrap();
+
+        let status = |checks: &[SegmentCheck], seg: &str| {
+            checks.iter().find(|c| c.segment == seg).map(|c| c.status.clone())
+        };
+        let checks = verify(root);
+        assert_eq!(status(&checks, &a.segment).as_deref(), Some("ok"));
+        assert_eq!(status(&checks, &b.segment).as_deref(), Some("ok"));
+
+        fs::write(logs_dir(root).join(format!("{}.jsonl.gz", a.segment)), b"tampered").unwrap();
+        let rule = RetentionRule { keep_segments: Some(1), ..Default::default() };
+        let pruned = prune(root, CONSOLE_AUDIT, &rule, Utc::now());
+        assert_eq!(pruned, vec![format!("{}.jsonl.gz", a.segment)]);
+
+        let checks = verify(root);
+        assert_eq!(status(&checks, &a.segment).as_deref(), Some("pruned"));
+        assert_eq!(status(&checks, &b.segment).as_deref(), Some("ok"));
+
+        fs::write(logs_dir(root).join(format!("{}.jsonl.gz", b.segment)), b"tampered").unwrap();
+        assert_eq!(status(&verify(root), &b.segment).as_deref(), Some("modified"));
+    }
+
+    #[test]
+    fn verify_checks_seal_signatures_and_the_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let op = Operator::registered(root, "ops");
+
+        let mut sealed = vec![];
+        for n in 1..=3 {
+            append(root, GUARDIAN_DECISIONS, &line("2026-01-01T00:00:00Z", n));
+            sealed.push(rotate_one(root, GUARDIAN_DECISIONS, &op).unwrap().unwrap());
+        }
+        let status = |seg: &SegmentSeal| {
+            verify(root).into_iter().find(|c| c.segment == seg.segment).map(|c| c.status)
+        };
+        assert!(sealed.iter().all(|s| status(s).as_deref() == Some("ok")));
+
+        // Edit a segment and recompute the hash in its seal.
+        let seg = logs_dir(root).join(format!("{}.jsonl.gz", sealed[0].segment));
+        let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+        enc.write_all(line("2026-01-01T00:00:00Z", 99).as_bytes()).unwrap();
+        let forged = enc.finish().unwrap();
+        fs::write(&seg, &forged).unwrap();
+        let mut seal = sealed[0].clone();
+        seal.segment_sha256 = sha256_hex(&forged);
+        let seal_file = seal_path(root, &seal.segment);
+        let original = fs::read(&seal_file).unwrap();
+        fs::write(&seal_file, serde_json::to_string_pretty(&seal).unwrap()).unwrap();
+        assert_eq!(status(&sealed[0]).as_deref(), Some("bad_signature"));
+        // The next seal no longer links to the edited one.
+        assert_eq!(status(&sealed[1]).as_deref(), Some("chain_broken"));
+
+        // Re-signed by someone who is not a registered operator.
+        let outsider = Operator::ephemeral("ops");
+        let resigned = signed_seal(SegmentSeal { signer_fingerprint: outsider.fingerprint.clone(), ..seal }, &outsider).unwrap();
+        fs::write(&seal_file, serde_json::to_string_pretty(&resigned).unwrap()).unwrap();
+        assert_eq!(status(&sealed[0]).as_deref(), Some("bad_signature"));
+
+        fs::write(&seal_file, &original).unwrap();
+        assert_eq!(status(&sealed[0]).as_deref(), Some("modified"));
+        assert_eq!(status(&sealed[1]).as_deref(), Some("ok"));
+
+        // Removing a seal from the middle breaks the chain after it.
+        fs::remove_file(seal_path(root, &sealed[1].segment)).unwrap();
+        assert_eq!(status(&sealed[1]).as_deref(), Some("unsealed"));
+        assert_eq!(status(&sealed[2]).as_deref(), Some("chain_broken"));
+    }
+
+    #[test]
+    fn due_by_size_or_age() {
+        let now = DateTime::parse_from_rfc3339("2026-02-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let text = line("2026-01-01T00:00:00Z", 1);
+
+        let by_size = RetentionRule { rotate_bytes: Some(10), ..Default::default() };
+        assert!(rotation_due(&by_size, text.as_bytes(), now));
+        assert!(!rotation_due(&by_size, b"", now));
+
+        let by_age = |days| RetentionRule { rotate_days: Some(days), ..Default::default() };
+        assert!(rotation_due(&by_age(30), text.as_bytes(), now));
+        assert!(!rotation_due(&by_age(31), text.as_bytes(), now));
+        assert!(!rotation_due(&by_age(u64::MAX), text.as_bytes(), now));
+        assert!(!rotation_due(&RetentionRule::default(), text.as_bytes(), now));
+    }
+
+    #[test]
+    fn huge_keep_days_never_prunes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(logs_dir(root)).unwrap();
+        fs::write(logs_dir(root).join("execution_runs.2020-01-01.jsonl.gz"), b"").unwrap();
+
+        let keep = |days| RetentionRule { keep_days: Some(days), ..Default::default() };
+        assert!(prune(root, RUN_RECORDS, &keep(u64::MAX), Utc::now()).is_empty());
+        assert_eq!(prune(root, RUN_RECORDS, &keep(30), Utc::now()).len(), 1);
+    }
+
+    #[test]
+    fn save_rejects_unknown_logs_and_unbounded_days() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        let mut cfg = load_config(root).unwrap();
+        assert_eq!(cfg.logs.len(), MANAGED_LOGS.len());
+
+        cfg.logs.get_mut(CONSOLE_AUDIT).unwrap().keep_days = Some(MAX_DAYS + 1);
+        assert!(save_config(root, &cfg).is_err());
+        cfg.logs.get_mut(CONSOLE_AUDIT).unwrap().keep_days = Some(MAX_DAYS);
+        cfg.logs.get_mut(RUN_RECORDS).unwrap().rotate_days = Some(u64::MAX);
+        assert!(save_config(root, &cfg).is_err());
+        cfg.logs.get_mut(RUN_RECORDS).unwrap().rotate_days = Some(1);
+        save_config(root, &cfg).unwrap();
+        assert_eq!(load_config(root).unwrap().logs[CONSOLE_AUDIT].keep_days, Some(MAX_DAYS));
+
+        cfg.logs.insert("other".into(), RetentionRule::default());
+        assert!(save_config(root, &cfg).is_err());
+    }
+}
@@ -0,0 +1,398 @@
+use serde::{Serialize, Deserialize};
+use std::{fs, path::{Path, PathBuf}};
+use serde_json::Value;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+
+use crate::atomic_fs::{self, write_atomic, Expect};
+use crate::decisions;
+use crate::submission::sha256_hex;
+use crate::wasm_meta;
+
+/// Files that travel with a revision. The worker only ever reads the
+/// top-level copies, which mirror the active revision.
+const REVISION_FILES: [&str; 2] = ["module.sig", "pubkey.b64"];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RevisionRecord {
+    pub sha256: String,
+    pub added_at: String,
+    pub added_by: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RevisionInfo {
+    #[serde(flatten)]
+    pub record: RevisionRecord,
+    pub active: bool,
+    pub signed: bool,
+    pub decisions: usize,
+    pub denies: usize,
+    pub last_decision: Option<String>,
+    pub last_threat_score: Option<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RevisionDiff {
+    pub from: String,
+    pub to: String,
+    pub size_delta: i64,
+    pub imports_added: Vec<String>,
+    pub imports_removed: Vec<String>,
+    pub signed_from: bool,
+    pub signed_to: bool,
+}
+
+fn tenant_dir(worker_root: &Path, tenant: &str) -> PathBuf {
+    worker_root.join("modules").join(tenant)
+}
+
+fn revision_dir(worker_root: &Path, tenant: &str, sha: &str) -> Result<PathBuf> {
+    if sha.len() != 64 || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("invalid revision id '{sha}'");
+    }
+    Ok(tenant_dir(worker_root, tenant).join("revisions").join(sha.to_lowercase()))
+}
+
+fn read_manifest(dir: &Path) -> Result<Value> {
+    let p = dir.join("manifest.json");
+    let raw = fs::read_to_string(&p).with_context(|| format!("reading {}", p.display()))?;
+    serde_json::from_str(&raw).with_context(|| format!("parsing {}", p.display()))
+}
+
+fn active_sha(manifest: &Value) -> Option<String> {
+    manifest.get("active_revision").and_then(|s| s.as_str()).map(|s| s.to_string())
+}
+
+fn store_revision(
+    worker_root: &Path,
+    tenant: &str,
+    wasm: &[u8],
+    companions: Option<&Path>,
+    added_by: &str,
+) -> Result<RevisionRecord> {
+    let sha = sha256_hex(wasm);
+    let dir = revision_dir(worker_root, tenant, &sha)?;
+
+    let record = RevisionRecord {
+        sha256: sha.clone(),
+        added_at: Utc::now().to_rfc3339(),
+        added_by: added_by.into(),
+        size_bytes: wasm.len() as u64,
+    };
+
+    if dir.join("module.wasm").exists() {
+        bail!("revision {sha} already exists for tenant {tenant}");
+    }
+
+    write_atomic(&dir.join("module.wasm"), wasm, Expect::Absent)?;
+    if let Some(src) = companions {
+        for f in REVISION_FILES {
+            if let Ok(b) = fs::read(src.join(f)) {
+                write_atomic(&dir.join(f), &b, Expect::Absent)?;
+            }
+        }
+    }
+    write_atomic(
+        &dir.join("revision.json"),
+        serde_json::to_string_pretty(&record)?.as_bytes(),
+        Expect::Absent,
+    )?;
+
+    Ok(record)
+}
+
+/// Tenants created before revisions existed get their current module
+/// recorded as the first, active revision. Also copies a signature the
+/// worker wrote at top level after approval back into the revision.
+pub fn ensure_initialized(worker_root: &Path, tenant: &str) -> Result<String> {
+    let dir = tenant_dir(worker_root, tenant);
+    let wasm = fs::read(dir.join("module.wasm"))
+        .with_context(|| format!("tenant {tenant} has no module.wasm"))?;
+    let sha = sha256_hex(&wasm);
+
+    let manifest = read_manifest(&dir)?;
+    let rev = revision_dir(worker_root, tenant, &sha)?;
+
+    if !rev.join("module.wasm").exists() {
+        store_revision(worker_root, tenant, &wasm, Some(&dir), "import")?;
+    } else {
+        for f in REVISION_FILES {
+            if !rev.join(f).exists() {
+                if let Ok(b) = fs::read(dir.join(f)) {
+                    write_atomic(&rev.join(f), &b, Expect::Absent)?;
+                }
+            }
+        }
+    }
+
+    if active_sha(&manifest).as_deref() != Some(&sha) {
+        atomic_fs::update_json(&dir.join("manifest.json"), |m| {
+            m["active_revision"] = Value::String(sha.clone());
+            Ok(())
+        })?;
+    }
+
+    Ok(sha)
+}
+
+/// Stage a new module as an inactive revision of an existing tenant.
+pub fn add_revision(worker_root: &Path, tenant: &str, wasm: &[u8], added_by: &str) -> Result<RevisionRecord> {
+    if wasm.len() < 8 || &wasm[..4] != b"\0asm" {
+        bail!("not a WebAssembly module");
+    }
+    ensure_initialized(worker_root, tenant)?;
+
+    // A fresh upload carries no signature; it is signed on approval.
+    store_revision(worker_root, tenant, wasm, None, added_by)
+}
+
+fn read_record(dir: &Path) -> Option<RevisionRecord> {
+    let raw = fs::read_to_string(dir.join("revision.json")).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+/// Read-only: a tenant not yet initialized shows its current module as
+/// the single, active revision without anything being written.
+pub fn list_revisions(worker_root: &Path, tenant: &str) -> Result<Vec<RevisionInfo>> {
+    let dir = tenant_dir(worker_root, tenant);
+    let wasm = fs::read(dir.join("module.wasm"))
+        .with_context(|| format!("tenant {tenant} has no module.wasm"))?;
+    let active = sha256_hex(&wasm);
+
+    let mut found: Vec<(RevisionRecord, bool)> = vec![];
+    let root = dir.join("revisions");
+
+    if root.exists() {
+        for entry in fs::read_dir(&root).with_context(|| format!("reading {}", root.display()))? {
+            let rev = entry?.path();
+            if let Some(record) = read_record(&rev) {
+                found.push((record, rev.join("module.sig").exists()));
+            }
+        }
+    }
+
+    if !found.iter().any(|(r, _)| r.sha256 == active) {
+        let manifest = read_manifest(&dir)?;
+        found.push((
+            RevisionRecord {
+                sha256: active.clone(),
+                added_at: manifest
+                    .pointer("/ingestion/timestamp")
+                    .and_then(|s| s.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                added_by: "import".into(),
+                size_bytes: wasm.len() as u64,
+            },
+            dir.join("module.sig").exists(),
+        ));
+    }
+
+    let all_decisions = decisions::load_all(worker_root);
+    let mut out = vec![];
+
+    for (record, signed) in found {
+        // Decisions are linked to a revision through the hash the worker
+        // evaluated, not through the tenant name alone.
+        let linked: Vec<_> = all_decisions
+            .iter()
+            .filter(|d| d.tenant == tenant && d.sha256.eq_ignore_ascii_case(&record.sha256))
+            .collect();
+
+        let last = linked.iter().max_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+        out.push(RevisionInfo {
+            active: record.sha256 == active,
+            signed,
+            decisions: linked.len(),
+            denies: linked.iter().filter(|d| d.decision == "deny").count(),
+            last_decision: last.map(|d| d.timestamp.clone()),
+            last_threat_score: last.map(|d| d.threat_score),
+            record,
+        });
+    }
+
+    out.sort_by(|a, b| b.record.added_at.cmp(&a.record.added_at));
+    Ok(out)
+}
+
+/// Make `sha` the active revision. The top-level files the worker reads
+/// are replaced. Approval follows the hash it was granted for: it is
+/// withdrawn when moving away and restored when returning to it.
+pub fn promote(worker_root: &Path, tenant: &str, sha: &str, promoted_by: &str) -> Result<()> {
+    let current = ensure_initialized(worker_root, tenant)?;
+    let sha = sha.to_lowercase();
+    if current == sha {
+        return Ok(());
+    }
+
+    let dir = tenant_dir(worker_root, tenant);
+    let rev = revision_dir(worker_root, tenant, &sha)?;
+    let wasm = fs::read(rev.join("module.wasm"))
+        .map_err(|_| anyhow!("tenant {tenant} has no revision {sha}"))?;
+
+    write_atomic(&dir.join("module.wasm"), &wasm, Expect::Any)?;
+    write_atomic(&dir.join("module.sha256"), sha.as_bytes(), Expect::Any)?;
+
+    for f in REVISION_FILES {
+        match fs::read(rev.join(f)) {
+            Ok(b) => write_atomic(&dir.join(f), &b, Expect::Any)?,
+            Err(_) => {
+                // A signature for the previous module must not linger.
+                let _ = fs::remove_file(dir.join(f));
+            }
+        }
+    }
+
+    atomic_fs::update_json(&dir.join("manifest.json"), |m| {
+        let approved_for = m.get("approved_sha256").and_then(|s| s.as_str()) == Some(sha.as_str());
+        let rejected = m.get("rejected").and_then(|b| b.as_bool()).unwrap_or(false);
+        let approved = m.get("approved").and_then(|b| b.as_bool()).unwrap_or(false);
+
+        if approved_for && !rejected {
+            m["approved"] = Value::Bool(true);
+        } else if approved {
+            m["approved"] = Value::Bool(false);
+        }
+
+        m["active_revision"] = Value::String(sha.clone());
+
+        let mut history = m
+            .get("revision_history")
+            .and_then(|h| h.as_array().cloned())
+            .unwrap_or_default();
+        history.push(serde_json::json!({
+            "from": current,
+            "to": sha,
+            "at": Utc::now().to_rfc3339(),
+            "by": promoted_by,
+        }));
+        m["revision_history"] = Value::Array(history);
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+/// Previously active revision, from the promotion history.
+pub fn previous_revision(worker_root: &Path, tenant: &str) -> Result<String> {
+    let manifest = read_manifest(&tenant_dir(worker_root, tenant))?;
+
+    manifest
+        .get("revision_history")
+        .and_then(|h| h.as_array())
+        .and_then(|h| h.last())
+        .and_then(|e| e.get("from"))
+        .and_then(|s| s.as_str())
+        .map(|s| s.to_string())
+        .ok_or_else(|| anyhow!("tenant {tenant} has no earlier revision to roll back to"))
+}
+
+pub fn compare(worker_root: &Path, tenant: &str, from: &str, to: &str) -> Result<RevisionDiff> {
+    let a = revision_dir(worker_root, tenant, from)?;
+    let b = revision_dir(worker_root, tenant, to)?;
+
+    let fa = wasm_meta::inspect_module(&a.join("module.wasm"))?;
+    let fb = wasm_meta::inspect_module(&b.join("module.wasm"))?;
+
+    Ok(RevisionDiff {
+        from: fa.sha256.clone(),
+        to: fb.sha256.clone(),
+        size_delta: fb.size_bytes as i64 - fa.size_bytes as i64,
+        imports_added: fb.imports.iter().filter(|i| !fa.imports.contains(i)).cloned().collect(),
+        imports_removed: fa.imports.iter().filter(|i| !fb.imports.contains(i)).cloned().collect(),
+        signed_from: a.join("module.sig").exists(),
+        signed_to: b.join("module.sig").exists(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1: &[u8] = b"\0asm\x01\0\0\0";
+    // V1 plus an empty custom section
+    const V2: &[u8] = b"\0asm\x01\0\0\0\0\x01\0";
+
+    fn setup() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let t = dir.path().join("modules/t1");
+        fs::create_dir_all(&t).unwrap();
+        fs::write(t.join("module.wasm"), V1).unwrap();
+        fs::write(
+            t.join("manifest.json"),
+            serde_json::json!({ "approved": true, "approved_sha256": sha256_hex(V1) }).to_string(),
+        )
+        .unwrap();
+        dir
+    }
+
+    fn manifest(root: &Path) -> Value {
+        read_manifest(&tenant_dir(root, "t1")).unwrap()
+    }
+
+    #[test]
+    fn listing_does_not_write() {
+        let dir = setup();
+        let before = fs::read(dir.path().join("modules/t1/manifest.json")).unwrap();
+
+        let revs = list_revisions(dir.path(), "t1").unwrap();
+        assert_eq!(revs.len(), 1);
+        assert!(revs[0].active);
+        assert_eq!(revs[0].record.sha256, sha256_hex(V1));
+
+        assert!(!dir.path().join("modules/t1/revisions").exists());
+        assert_eq!(fs::read(dir.path().join("modules/t1/manifest.json")).unwrap(), before);
+    }
+
+    #[test]
+    fn approval_follows_the_approved_hash() {
+        let dir = setup();
+        let root = dir.path();
+        let v2 = add_revision(root, "t1", V2, "op").unwrap().sha256;
+
+        promote(root, "t1", &v2, "op").unwrap();
+        assert_eq!(manifest(root)["approved"], false);
+        assert_eq!(fs::read(root.join("modules/t1/module.wasm")).unwrap(), V2);
+
+        let back = previous_revision(root, "t1").unwrap();
+        promote(root, "t1", &back, "op").unwrap();
+        assert_eq!(manifest(root)["approved"], true);
+        assert_eq!(fs::read(root.join("modules/t1/module.wasm")).unwrap(), V1);
+
+        let revs = list_revisions(root, "t1").unwrap();
+        assert_eq!(revs.len(), 2);
+        assert_eq!(revs.iter().filter(|r| r.active).count(), 1);
+    }
+
+    #[test]
+    fn rejected_tenant_is_not_reapproved_on_rollback() {
+        let dir = setup();
+        let root = dir.path();
+        let v2 = add_revision(root, "t1", V2, "op").unwrap().sha256;
+        promote(root, "t1", &v2, "op").unwrap();
+
+        atomic_fs::update_json(&root.join("modules/t1/manifest.json"), |m| {
+            m["rejected"] = Value::Bool(true);
+            Ok(())
+        })
+        .unwrap();
+
+        promote(root, "t1", &sha256_hex(V1), "op").unwrap();
+        assert_eq!(manifest(root)["approved"], false);
+    }
+
+    #[test]
+    fn compare_reports_size_and_signature() {
+        let dir = setup();
+        let root = dir.path();
+        let v2 = add_revision(root, "t1", V2, "op").unwrap().sha256;
+
+        let diff = compare(root, "t1", &sha256_hex(V1), &v2).unwrap();
+        assert_eq!(diff.size_delta, 3);
+        assert!(!diff.signed_to);
+        assert!(compare(root, "t1", "nothex", &v2).is_err());
+    }
+}
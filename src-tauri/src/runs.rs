@@ -0,0 +1,381 @@
+use serde::{Serialize, Deserialize};
+use std::{
+    path::Path,
+    process::{Command, Output},
+    time::Instant,
+};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use rand::{rngs::OsRng, RngCore};
+
+use crate::atomic_fs;
+use crate::decisions;
+use crate::process_registry;
+use crate::retention;
+use crate::submission::sha256_hex;
+
+/// Bytes of each stream kept in a record. The hash and length always
+/// cover the full stream.
+pub const CAPTURE_MAX: usize = 16 * 1024;
+
+pub const MAX_PAGE: usize = 200;
+const DEFAULT_PAGE: usize = 50;
+
+/// Decisions the worker logs this long after the run ended still count
+/// as the run's own (the log is written before the process exits, but
+/// clocks are read separately).
+const DECISION_SLACK_SECS: i64 = 2;
+
+/// Environment variable giving the worker the run id, for it to echo as
+/// `run_id` in the decisions it logs.
+pub const RUN_ID_ENV: &str = "NIGHTCORE_RUN_ID";
+
+/// One captured stream.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CapturedOutput {
+    /// Leading [`CAPTURE_MAX`] bytes, lossily decoded.
+    pub text: String,
+    pub bytes: u64,
+    pub sha256: String,
+    pub truncated: bool,
+}
+
+impl CapturedOutput {
+    fn capture(raw: &[u8]) -> Self {
+        let kept = &raw[..raw.len().min(CAPTURE_MAX)];
+        CapturedOutput {
+            text: String::from_utf8_lossy(kept).to_string(),
+            bytes: raw.len() as u64,
+            sha256: sha256_hex(raw),
+            truncated: raw.len() > CAPTURE_MAX,
+        }
+    }
+}
+
+/// Guardian decision logged while the run was active.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LinkedDecision {
+    pub timestamp: String,
+    pub tenant: String,
+    pub decision: String,
+    pub reason: String,
+    pub threat_score: u8,
+    pub sha256: String,
+}
+
+/// One worker run started from the console, appended to
+/// `logs/execution_runs.jsonl`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunRecord {
+    pub run_id: String,
+    /// `None` for runs covering every tenant.
+    #[serde(default)]
+    pub tenant: Option<String>,
+    #[serde(default)]
+    pub backend: Option<String>,
+    pub args: Vec<String>,
+    /// console | schedule:<id>
+    pub trigger: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub duration_ms: u64,
+    /// `None` when the worker did not start or was killed by a signal.
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    /// Why the worker could not be started or waited on.
+    #[serde(default)]
+    pub error: Option<String>,
+    pub stdout: CapturedOutput,
+    pub stderr: CapturedOutput,
+    /// One per tenant the run covered; empty if the worker logged none.
+    #[serde(default)]
+    pub decisions: Vec<LinkedDecision>,
+}
+
+/// A run that has started but not been recorded yet.
+pub struct PendingRun {
+    run_id: String,
+    started_at: DateTime<Utc>,
+    started: Instant,
+    tenant: Option<String>,
+    backend: Option<String>,
+    args: Vec<String>,
+    trigger: String,
+}
+
+pub fn start(args: &[String], trigger: &str) -> PendingRun {
+    let mut id = [0u8; 8];
+    OsRng.fill_bytes(&mut id);
+
+    PendingRun {
+        run_id: id.iter().map(|b| format!("{b:02x}")).collect(),
+        started_at: Utc::now(),
+        started: Instant::now(),
+        tenant: process_registry::arg_value(args, "--tenant").map(|s| s.to_string()),
+        backend: process_registry::arg_value(args, "--backend").map(|s| s.to_string()),
+        args: args.to_vec(),
+        trigger: trigger.to_string(),
+    }
+}
+
+fn parse_ts(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Tenant and active window of another run. `until` is `None` for a
+/// run that is still going.
+struct OtherRun {
+    tenant: Option<String>,
+    from: DateTime<Utc>,
+    until: Option<DateTime<Utc>>,
+}
+
+impl OtherRun {
+    fn covers(&self, tenant: &str, ts: DateTime<Utc>) -> bool {
+        self.tenant.as_deref().is_none_or(|t| t == tenant)
+            && ts >= self.from
+            && self.until.is_none_or(|u| ts <= u)
+    }
+}
+
+/// Recorded and still-running runs other than this one that overlap
+/// `from..=to`.
+fn overlapping_runs(worker_root: &Path, run_id: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<OtherRun> {
+    let slack = Duration::seconds(DECISION_SLACK_SECS);
+
+    let recorded = load_all(worker_root).into_iter().filter(|r| r.run_id != run_id).filter_map(|r| {
+        Some(OtherRun {
+            tenant: r.tenant,
+            from: parse_ts(&r.started_at)?,
+            until: Some(parse_ts(&r.finished_at)? + slack),
+        })
+    });
+
+    // This run left the registry when it was waited on.
+    let running = process_registry::list(worker_root).unwrap_or_default().into_iter().filter_map(|p| {
+        Some(OtherRun { tenant: p.tenant, from: parse_ts(&p.started_at)?, until: None })
+    });
+
+    recorded
+        .chain(running)
+        .filter(|o| o.from <= to && o.until.is_none_or(|u| u >= from))
+        .collect()
+}
+
+/// Decisions this run produced. Decisions carrying a run id belong to
+/// that run alone. Without one, a decision is matched by tenant and time,
+/// and left out when another run covering the same tenant was active at
+/// that moment, since it could be either run's.
+fn linked_decisions(
+    worker_root: &Path,
+    run_id: &str,
+    tenant: Option<&str>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<LinkedDecision> {
+    let to = to + Duration::seconds(DECISION_SLACK_SECS);
+    let others = overlapping_runs(worker_root, run_id, from, to);
+
+    decisions::load_all(worker_root)
+        .into_iter()
+        .filter(|d| match &d.run_id {
+            Some(id) => id == run_id,
+            None => {
+                tenant.is_none_or(|t| d.tenant == t)
+                    && parse_ts(&d.timestamp).is_some_and(|ts| {
+                        ts >= from && ts <= to && !others.iter().any(|o| o.covers(&d.tenant, ts))
+                    })
+            }
+        })
+        .map(|d| LinkedDecision {
+            timestamp: d.timestamp,
+            tenant: d.tenant,
+            decision: d.decision,
+            reason: d.reason,
+            threat_score: d.threat_score,
+            sha256: d.sha256,
+        })
+        .collect()
+}
+
+impl PendingRun {
+    /// Pass the run id to the worker.
+    pub fn tag(&self, cmd: &mut Command) {
+        cmd.env(RUN_ID_ENV, &self.run_id);
+    }
+
+    /// Record how the run ended, including runs that never started.
+    pub fn finish(self, worker_root: &Path, outcome: &Result<Output>) -> Result<RunRecord> {
+        let finished_at = Utc::now();
+
+        let (exit_code, success, error, stdout, stderr) = match outcome {
+            Ok(out) => (
+                out.status.code(),
+                out.status.success(),
+                None,
+                CapturedOutput::capture(&out.stdout),
+                CapturedOutput::capture(&out.stderr),
+            ),
+            Err(e) => (
+                None,
+                false,
+                Some(format!("{e:#}")),
+                CapturedOutput::capture(&[]),
+                CapturedOutput::capture(&[]),
+            ),
+        };
+
+        let record = RunRecord {
+            decisions: linked_decisions(
+                worker_root,
+                &self.run_id,
+                self.tenant.as_deref(),
+                self.started_at,
+                finished_at,
+            ),
+            run_id: self.run_id,
+            tenant: self.tenant,
+            backend: self.backend,
+            args: self.args,
+            trigger: self.trigger,
+            started_at: self.started_at.to_rfc3339(),
+            finished_at: finished_at.to_rfc3339(),
+            duration_ms: self.started.elapsed().as_millis() as u64,
+            exit_code,
+            success,
+            error,
+            stdout,
+            stderr,
+        };
+
+        atomic_fs::append_line(
+            &retention::active_path(worker_root, retention::RUN_RECORDS),
+            &serde_json::to_string(&record)?,
+        )?;
+
+        Ok(record)
+    }
+}
+
+/// Every record, oldest first, across rotated segments.
+pub fn load_all(worker_root: &Path) -> Vec<RunRecord> {
+    retention::read_lines(worker_root, retention::RUN_RECORDS)
+        .iter()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RunPage {
+    pub total: usize,
+    pub offset: usize,
+    pub runs: Vec<RunRecord>,
+}
+
+/// Records newest first. With `tenant`, runs that covered every tenant
+/// are included when they logged a decision for it.
+pub fn query(worker_root: &Path, tenant: Option<&str>, offset: usize, limit: Option<usize>) -> RunPage {
+    let mut runs: Vec<RunRecord> = load_all(worker_root)
+        .into_iter()
+        .filter(|r| match tenant {
+            None => true,
+            Some(t) => match &r.tenant {
+                Some(rt) => rt == t,
+                None => r.decisions.iter().any(|d| d.tenant == t),
+            },
+        })
+        .collect();
+    runs.reverse();
+
+    let total = runs.len();
+    let limit = limit.unwrap_or(DEFAULT_PAGE).clamp(1, MAX_PAGE);
+
+    RunPage {
+        total,
+        offset,
+        runs: runs.into_iter().skip(offset).take(limit).collect(),
+    }
+}
+
+pub fn get(worker_root: &Path, run_id: &str) -> Option<RunRecord> {
+    load_all(worker_root).into_iter().find(|r| r.run_id == run_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision(ts: DateTime<Utc>, tenant: &str, run_id: Option<&str>) -> String {
+        decisions::decision_json(serde_json::json!({ "timestamp": ts.to_rfc3339(), "tenant": tenant, "run_id": run_id }))
+            .to_string()
+    }
+
+    fn pending(run_id: &str, tenant: Option<&str>, started_at: DateTime<Utc>) -> PendingRun {
+        PendingRun {
+            run_id: run_id.into(),
+            started_at,
+            started: Instant::now(),
+            tenant: tenant.map(|t| t.to_string()),
+            backend: None,
+            args: vec!["run".into()],
+            trigger: "console".into(),
+        }
+    }
+
+    fn finish(root: &Path, run: PendingRun) -> RunRecord {
+        run.finish(root, &Err(anyhow::anyhow!("not started"))).unwrap()
+    }
+
+    fn linked(r: &RunRecord) -> Vec<String> {
+        r.decisions.iter().map(|d| d.tenant.clone()).collect()
+    }
+
+    #[test]
+    fn overlapping_runs_do_not_share_decisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let t0 = Utc::now() - Duration::seconds(60);
+        let log = retention::active_path(root, retention::GUARDIAN_DECISIONS);
+
+        // A scheduled run of `a` from t0 to about now.
+        let scheduled = finish(root, pending("sched", Some("a"), t0));
+        assert!(scheduled.decisions.is_empty());
+
+        for (secs, tenant) in [(5, "a"), (6, "b"), (7, "c")] {
+            atomic_fs::append_line(&log, &decision(t0 + Duration::seconds(secs), tenant, None)).unwrap();
+        }
+
+        // A `run --all` over the same window: `a` could be either run's.
+        let all = finish(root, pending("all", None, t0));
+        assert_eq!(linked(&all), vec!["b", "c"]);
+
+        // A run of `b` after both: nothing overlaps it.
+        let later = finish(root, pending("b-run", Some("b"), t0));
+        assert!(linked(&later).is_empty(), "{:?}", linked(&later));
+    }
+
+    #[test]
+    fn run_ids_decide_when_the_worker_logs_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let t0 = Utc::now() - Duration::seconds(60);
+        let log = retention::active_path(root, retention::GUARDIAN_DECISIONS);
+
+        finish(root, pending("other", None, t0));
+        atomic_fs::append_line(&log, &decision(t0 + Duration::seconds(5), "a", Some("mine"))).unwrap();
+        atomic_fs::append_line(&log, &decision(t0 + Duration::seconds(6), "b", Some("other"))).unwrap();
+        atomic_fs::append_line(&log, &decision(t0 - Duration::seconds(600), "c", Some("mine"))).unwrap();
+
+        let mine = finish(root, pending("mine", None, t0));
+        assert_eq!(linked(&mine), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn tag_passes_the_run_id() {
+        let run = pending("abc", None, Utc::now());
+        let mut cmd = Command::new("worker");
+        run.tag(&mut cmd);
+        assert!(cmd.get_envs().any(|(k, v)| k == RUN_ID_ENV && v == Some("abc".as_ref())));
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+use anyhow::{anyhow, bail, Context, Result};
+
+/// Prefix the console uses when showing runtime paths to the UI.
+pub const MASK_PREFIX: &str = "worker://";
+
+/// Subtrees of the runtime root the UI may read. Keys, operator state
+/// and module bytes are deliberately absent.
+pub const READABLE_SUBTREES: [&str; 3] = ["logs", "proof", "guardian"];
+
+/// Largest runtime file handed to the UI in one read.
+pub const MAX_READ_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Largest file accepted for import, and largest total a zip may expand to.
+pub const MAX_IMPORT_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Exports written inside the runtime root must go here.
+const EXPORTS_DIR: &str = "exports";
+
+/// File types the console will hand to the OS opener.
+const OPENABLE_EXTENSIONS: [&str; 4] = ["html", "htm", "json", "log"];
+
+/// Parse a caller-supplied relative path. Backslashes are treated as
+/// separators on every platform, and anything other than plain names is
+/// refused: `..`, `.`, roots, drive letters, UNC and verbatim prefixes,
+/// and `:` (drive-relative paths, NTFS streams).
+fn relative(rel: &str) -> Result<PathBuf> {
+    let rel = rel.replace('\\', "/");
+    if rel.contains(':') {
+        bail!("path must not contain ':'");
+    }
+
+    let p = PathBuf::from(&rel);
+    if rel.starts_with('/') || !p.components().all(|c| matches!(c, Component::Normal(_))) {
+        bail!("path must be relative and must not contain '..'");
+    }
+    Ok(p)
+}
+
+fn canonical_root(worker_root: &Path) -> Result<PathBuf> {
+    fs::canonicalize(worker_root)
+        .with_context(|| format!("resolving {}", worker_root.display()))
+}
+
+/// Resolve `rel` to a real, existing path inside the runtime root.
+/// Both sides are canonicalized, which follows every symlink and adds the
+/// same verbatim prefix on Windows, so the prefix check is exact.
+pub fn confine(worker_root: &Path, rel: &str) -> Result<PathBuf> {
+    let root = canonical_root(worker_root)?;
+    let real = fs::canonicalize(root.join(relative(rel)?))
+        .with_context(|| format!("resolving {rel}"))?;
+
+    if !real.starts_with(&root) {
+        bail!("{rel} resolves outside the runtime root");
+    }
+    Ok(real)
+}
+
+/// [`confine`], further limited to the given subtrees of the root.
+pub fn confine_in(worker_root: &Path, rel: &str, subtrees: &[&str]) -> Result<PathBuf> {
+    let real = confine(worker_root, rel)?;
+    let root = canonical_root(worker_root)?;
+
+    // Subtrees are canonicalized too: a symlinked `logs/` must not widen
+    // the allow-list to wherever it points, inside the root or not.
+    let allowed = subtrees
+        .iter()
+        .filter(|s| fs::canonicalize(root.join(s)).is_ok_and(|c| c == root.join(s)))
+        .any(|s| real.starts_with(root.join(s)));
+
+    if !allowed {
+        bail!("{rel} is not in a readable location");
+    }
+    Ok(real)
+}
+
+fn check_size(path: &Path, max: u64) -> Result<u64> {
+    let meta = fs::metadata(path).with_context(|| format!("reading {}", path.display()))?;
+    if !meta.is_file() {
+        bail!("{} is not a file", path.display());
+    }
+    if meta.len() > max {
+        bail!("{} is {} bytes, over the {} byte limit", path.display(), meta.len(), max);
+    }
+    Ok(meta.len())
+}
+
+/// Text of a readable runtime file, within [`MAX_READ_BYTES`].
+pub fn read_runtime_text(worker_root: &Path, rel: &str) -> Result<String> {
+    let real = confine_in(worker_root, rel, &READABLE_SUBTREES)?;
+    check_size(&real, MAX_READ_BYTES)?;
+
+    fs::read_to_string(&real).with_context(|| format!("reading {rel}"))
+}
+
+/// Relative part of a `worker://` path.
+///
+/// Masks produced by the console look like `worker:///logs/x.html` (the
+/// root is replaced, its separator stays), so leading slashes after the
+/// prefix are ignored.
+fn unmask(masked: &str) -> Result<&str> {
+    Ok(masked
+        .strip_prefix(MASK_PREFIX)
+        .ok_or_else(|| anyhow!("only {MASK_PREFIX} paths are accepted"))?
+        .trim_start_matches(['/', '\\']))
+}
+
+/// Resolve a `worker://` path for the OS opener: a regular file of an
+/// openable type in a readable subtree, or the root folder itself.
+pub fn resolve_openable(worker_root: &Path, masked: &str) -> Result<PathBuf> {
+    let rel = unmask(masked)?;
+    if rel.is_empty() {
+        return canonical_root(worker_root);
+    }
+
+    let real = confine_in(worker_root, rel, &READABLE_SUBTREES)?;
+
+    if !real.is_file() {
+        bail!("{masked} is not a file");
+    }
+
+    let ext = real
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    if !OPENABLE_EXTENSIONS.contains(&ext.as_str()) {
+        bail!("opening .{ext} files is not allowed");
+    }
+
+    Ok(real)
+}
+
+/// Resolve a `worker://` path to a regular file in a readable subtree,
+/// within [`MAX_READ_BYTES`]. Returns the relative path as well.
+pub fn resolve_readable(worker_root: &Path, masked: &str) -> Result<(String, PathBuf)> {
+    let rel = unmask(masked)?;
+    let real = confine_in(worker_root, rel, &READABLE_SUBTREES)?;
+    check_size(&real, MAX_READ_BYTES)?;
+
+    Ok((rel.replace('\\', "/"), real))
+}
+
+/// Tenant names are a single plain path component.
+pub fn check_tenant(tenant: &str) -> Result<()> {
+    let ok = !tenant.is_empty()
+        && tenant.len() <= 128
+        && !tenant.starts_with('.')
+        && tenant.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.');
+
+    if !ok {
+        bail!("invalid tenant name '{tenant}'");
+    }
+    Ok(())
+}
+
+/// A file the operator picked outside the runtime (import sources).
+/// Must be a regular file within `max_bytes`.
+pub fn external_input(path: &str, max_bytes: u64) -> Result<PathBuf> {
+    let real = fs::canonicalize(path).with_context(|| format!("resolving {path}"))?;
+    check_size(&real, max_bytes)?;
+    Ok(real)
+}
+
+/// Destination for an export. Must be absolute with an existing parent;
+/// inside the runtime root only `exports/` is writable this way.
+pub fn external_output(worker_root: &Path, dest: &str) -> Result<PathBuf> {
+    let p = Path::new(dest);
+    if !p.is_absolute() {
+        bail!("export destination must be an absolute path");
+    }
+
+    let name = p
+        .file_name()
+        .ok_or_else(|| anyhow!("export destination has no file name"))?;
+    let parent = p
+        .parent()
+        .ok_or_else(|| anyhow!("export destination has no parent directory"))?;
+    let parent = fs::canonicalize(parent)
+        .with_context(|| format!("resolving {}", parent.display()))?;
+
+    let root = canonical_root(worker_root)?;
+    if parent.starts_with(&root) && !parent.starts_with(root.join(EXPORTS_DIR)) {
+        bail!("exports inside the runtime root must go to {EXPORTS_DIR}/");
+    }
+
+    Ok(parent.join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("root");
+        for d in ["logs", "state", "exports"] {
+            fs::create_dir_all(root.join(d)).unwrap();
+        }
+        fs::write(root.join("logs/report.json"), "{}").unwrap();
+        fs::write(root.join("logs/run.wasm"), "x").unwrap();
+        fs::write(root.join("state/operators.json"), "{}").unwrap();
+        fs::write(dir.path().join("outside.txt"), "secret").unwrap();
+        dir
+    }
+
+    #[test]
+    fn confine_refuses_escapes() {
+        let dir = setup();
+        let root = dir.path().join("root");
+
+        assert!(confine(&root, "logs/report.json").is_ok());
+        for bad in ["../outside.txt", "logs/../../outside.txt", "/etc/passwd", "C:\\Windows", "./logs/report.json"] {
+            assert!(confine(&root, bad).is_err(), "{bad}");
+        }
+        assert!(confine(&root, "logs\\..\\..\\outside.txt").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn confine_follows_symlinks() {
+        let dir = setup();
+        let root = dir.path().join("root");
+
+        std::os::unix::fs::symlink(dir.path().join("outside.txt"), root.join("logs/link.txt")).unwrap();
+        assert!(confine(&root, "logs/link.txt").is_err());
+
+        // a symlinked subtree does not widen the allow-list
+        std::os::unix::fs::symlink(root.join("state"), root.join("guardian")).unwrap();
+        assert!(confine_in(&root, "guardian/operators.json", &READABLE_SUBTREES).is_err());
+    }
+
+    #[test]
+    fn only_readable_subtrees() {
+        let dir = setup();
+        let root = dir.path().join("root");
+
+        assert!(read_runtime_text(&root, "logs/report.json").is_ok());
+        assert!(read_runtime_text(&root, "state/operators.json").is_err());
+    }
+
+    #[test]
+    fn openable_requires_mask_and_type() {
+        let dir = setup();
+        let root = dir.path().join("root");
+
+        assert!(resolve_openable(&root, "worker:///logs/report.json").is_ok());
+        assert!(resolve_openable(&root, "worker://").is_ok());
+        assert!(resolve_openable(&root, "worker://logs/run.wasm").is_err());
+        assert!(resolve_openable(&root, "worker://state/operators.json").is_err());
+        assert!(resolve_openable(&root, root.join("logs/report.json").to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn tenant_names() {
+        assert!(check_tenant("demo-abcdef012345").is_ok());
+        for bad in ["", ".hidden", "../x", "a/b", "a\\b", &"x".repeat(129)] {
+            assert!(check_tenant(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn outputs_inside_root_only_under_exports() {
+        let dir = setup();
+        let root = dir.path().join("root");
+        let s = |p: PathBuf| p.to_string_lossy().to_string();
+
+        assert!(external_output(&root, &s(root.join("exports/a.zip"))).is_ok());
+        assert!(external_output(&root, &s(dir.path().join("a.zip"))).is_ok());
+        assert!(external_output(&root, &s(root.join("state/a.zip"))).is_err());
+        assert!(external_output(&root, &s(root.join("exports/../state/a.zip"))).is_err());
+        assert!(external_output(&root, "relative.zip").is_err());
+    }
+}
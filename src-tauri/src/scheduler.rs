@@ -0,0 +1,543 @@
+use serde::{Serialize, Deserialize};
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    str::FromStr,
+    time::{Duration as StdDuration, Instant},
+};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, Days, Duration, TimeZone, Timelike, Utc};
+use rand::{rngs::OsRng, RngCore};
+
+use crate::approvals;
+use crate::atomic_fs;
+use crate::backends;
+use crate::process_registry;
+use crate::quarantine;
+use crate::runs;
+use crate::runtime_path;
+use crate::tenant_state;
+
+/// How often the background loop looks for due schedules. Schedules have
+/// minute resolution, so a run may start up to this late.
+pub const TICK_INTERVAL: StdDuration = StdDuration::from_secs(30);
+
+/// Policy key that turns scheduled runs off for a tenant (or, in
+/// `_default.json`, for every tenant without its own policy).
+pub const POLICY_KEY: &str = "scheduled_runs";
+
+/// Furthest ahead a next run is searched for; expressions that never
+/// match in that window (`0 0 31 2 *`) are rejected.
+const SEARCH_YEARS: u64 = 5;
+
+const DETAIL_MAX: usize = 300;
+
+/// Five-field cron expression (minute hour day-of-month month
+/// day-of-week), evaluated in UTC. Fields take `*`, numbers, `a-b`
+/// ranges, `/n` steps and comma lists; months and weekdays also take
+/// three-letter names. `@hourly`, `@daily`, `@weekly`, `@monthly` and
+/// `@yearly` are accepted as shorthands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSpec {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_any: bool,
+    weekdays_any: bool,
+}
+
+const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+fn parse_value(s: &str, min: u32, max: u32, names: &[&str], name_base: u32) -> Result<u32> {
+    let v = match s.parse::<u32>() {
+        Ok(n) => n,
+        Err(_) => names
+            .iter()
+            .position(|n| n.eq_ignore_ascii_case(s))
+            .map(|i| i as u32 + name_base)
+            .with_context(|| {
+                if names.is_empty() {
+                    format!("'{s}' is not a number")
+                } else {
+                    format!("'{s}' is not a number or name")
+                }
+            })?,
+    };
+
+    if v < min || v > max {
+        bail!("{v} is outside {min}-{max}");
+    }
+    Ok(v)
+}
+
+/// Bit mask of the values a field allows, and whether it was a bare `*`.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str], name_base: u32) -> Result<(u64, bool)> {
+    let mut mask = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => {
+                let step: u32 = s.parse().with_context(|| format!("bad step in '{part}'"))?;
+                if step == 0 {
+                    bail!("step must be at least 1 in '{part}'");
+                }
+                (r, step)
+            }
+            None => (part, 1),
+        };
+
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse_value(a, min, max, names, name_base)?, parse_value(b, min, max, names, name_base)?)
+        } else {
+            let v = parse_value(range, min, max, names, name_base)?;
+            // `5/15` means every 15 starting at 5.
+            (v, if part.contains('/') { max } else { v })
+        };
+
+        if lo > hi {
+            bail!("range '{range}' runs backwards");
+        }
+
+        for v in (lo..=hi).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+
+    Ok((mask, field == "*"))
+}
+
+impl FromStr for CronSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(expr: &str) -> Result<Self> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [min, hour, dom, month, dow] = fields[..] else {
+            bail!("expected 5 fields (minute hour day month weekday), got {}", fields.len());
+        };
+
+        let field = |label: &str, f: &str, lo, hi, names: &[&str], base| {
+            parse_field(f, lo, hi, names, base).with_context(|| format!("{label} field '{f}'"))
+        };
+
+        let (minutes, _) = field("minute", min, 0, 59, &[], 0)?;
+        let (hours, _) = field("hour", hour, 0, 23, &[], 0)?;
+        let (days, days_any) = field("day", dom, 1, 31, &[], 0)?;
+        let (months, _) = field("month", month, 1, 12, &MONTHS, 1)?;
+        let (mut weekdays, weekdays_any) = field("weekday", dow, 0, 7, &WEEKDAYS, 0)?;
+
+        // 7 is Sunday too.
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+
+        Ok(CronSpec { minutes, hours, days, months, weekdays, days_any, weekdays_any })
+    }
+}
+
+impl CronSpec {
+    /// Classic cron: when both day fields are restricted, either may match.
+    fn day_matches(&self, t: DateTime<Utc>) -> bool {
+        let dom = self.days & (1 << t.day()) != 0;
+        let dow = self.weekdays & (1 << t.weekday().num_days_from_sunday()) != 0;
+
+        match (self.days_any, self.weekdays_any) {
+            (true, true) => true,
+            (true, false) => dow,
+            (false, true) => dom,
+            (false, false) => dom || dow,
+        }
+    }
+
+    /// First matching minute strictly after `after`.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let end = after + Duration::days(366 * SEARCH_YEARS as i64);
+
+        while t < end {
+            if self.months & (1 << t.month()) == 0 {
+                let (y, m) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = Utc.with_ymd_and_hms(y, m, 1, 0, 0, 0).single()?;
+            } else if !self.day_matches(t) {
+                t = t.date_naive().checked_add_days(Days::new(1))?.and_hms_opt(0, 0, 0)?.and_utc();
+            } else if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + Duration::hours(1);
+            } else if self.minutes & (1 << t.minute()) == 0 {
+                t += Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+
+        None
+    }
+}
+
+/// Outcome of one due occurrence.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunResult {
+    pub at: String,
+    /// ok | failed | skipped
+    pub outcome: String,
+    pub detail: String,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    /// Record of the worker run, when one was started.
+    #[serde(default)]
+    pub run_id: Option<String>,
+}
+
+impl RunResult {
+    fn new(at: &str, outcome: &str, detail: impl Into<String>) -> Self {
+        RunResult {
+            at: at.to_string(),
+            outcome: outcome.into(),
+            detail: detail.into(),
+            exit_code: None,
+            duration_ms: None,
+            run_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Schedule {
+    pub id: String,
+    pub tenant: String,
+    pub cron: String,
+    pub backend: String,
+    pub enabled: bool,
+    pub created_at: String,
+    pub created_by: String,
+    /// Next occurrence, RFC3339. `None` while disabled.
+    #[serde(default)]
+    pub next_run: Option<String>,
+    #[serde(default)]
+    pub last_run: Option<RunResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct ScheduleFile {
+    #[serde(default)]
+    schedules: Vec<Schedule>,
+}
+
+fn schedules_path(worker_root: &Path) -> PathBuf {
+    worker_root.join("state").join("schedules.json")
+}
+
+/// Read-modify-write of the schedule file, retried on a concurrent change.
+/// `f` may refuse the change; nothing is written then.
+fn update<F, T>(worker_root: &Path, f: F) -> Result<T>
+where
+    F: FnMut(&mut ScheduleFile) -> Result<T>,
+{
+    atomic_fs::update_versioned(&schedules_path(worker_root), f)
+}
+
+fn next_run_after(cron: &str, after: DateTime<Utc>) -> Option<String> {
+    cron.parse::<CronSpec>().ok()?.next_after(after).map(|t| t.to_rfc3339())
+}
+
+pub fn list(worker_root: &Path) -> Result<Vec<Schedule>> {
+    let (file, _) = atomic_fs::load_versioned::<ScheduleFile>(&schedules_path(worker_root))?;
+    Ok(file.schedules)
+}
+
+pub fn add(worker_root: &Path, tenant: &str, cron: &str, backend: &str, created_by: &str) -> Result<Schedule> {
+    runtime_path::check_tenant(tenant)?;
+    if !worker_root.join("modules").join(tenant).is_dir() {
+        bail!("unknown tenant {tenant}");
+    }
+    if backends::get(backend).is_none() {
+        bail!("unknown backend '{backend}' (expected one of {})", backends::names().join(", "));
+    }
+
+    let spec: CronSpec = cron.parse().with_context(|| format!("invalid schedule '{cron}'"))?;
+    let now = Utc::now();
+    let Some(next) = spec.next_after(now) else {
+        bail!("schedule '{cron}' never fires");
+    };
+
+    let mut id = [0u8; 8];
+    OsRng.fill_bytes(&mut id);
+
+    let s = Schedule {
+        id: id.iter().map(|b| format!("{b:02x}")).collect(),
+        tenant: tenant.to_string(),
+        cron: cron.trim().to_string(),
+        backend: backend.to_string(),
+        enabled: true,
+        created_at: now.to_rfc3339(),
+        created_by: created_by.to_string(),
+        next_run: Some(next.to_rfc3339()),
+        last_run: None,
+    };
+
+    update(worker_root, |f| {
+        f.schedules.push(s.clone());
+        Ok(())
+    })?;
+
+    Ok(s)
+}
+
+pub fn remove(worker_root: &Path, id: &str) -> Result<Schedule> {
+    update(worker_root, |f| {
+        let Some(i) = f.schedules.iter().position(|s| s.id == id) else {
+            bail!("no schedule {id}");
+        };
+        Ok(f.schedules.remove(i))
+    })
+}
+
+/// Re-enabling counts from now, so occurrences missed while disabled
+/// are not run.
+pub fn set_enabled(worker_root: &Path, id: &str, enabled: bool) -> Result<Schedule> {
+    update(worker_root, |f| {
+        let Some(s) = f.schedules.iter_mut().find(|s| s.id == id) else {
+            bail!("no schedule {id}");
+        };
+        s.enabled = enabled;
+        s.next_run = if enabled { next_run_after(&s.cron, Utc::now()) } else { None };
+        Ok(s.clone())
+    })
+}
+
+/// Why `s` may not run now, or `None` if it may. The tenant must be
+/// cleared (approved, or imported by hand), not suspended, not in
+/// quarantine, and its policy must allow scheduled runs on the backend.
+pub fn skip_reason(worker_root: &Path, s: &Schedule) -> Result<Option<String>> {
+    let states = tenant_state::list_tenant_states(worker_root)?;
+    let Some(t) = states.iter().find(|t| t.id == s.tenant) else {
+        return Ok(Some("tenant no longer exists".into()));
+    };
+
+    if let Some(h) = &t.authorization.suspension {
+        return Ok(Some(format!("tenant suspended by {} at {}", h.suspended_by, h.suspended_at)));
+    }
+
+    match t.observation.state.as_str() {
+        "cleared" | "observed" => {}
+        other => return Ok(Some(format!("tenant is {}", other.replace('_', " ")))),
+    }
+    if t.ingestion.channel != "manual" && !t.authorization.approved {
+        return Ok(Some("tenant is not approved".into()));
+    }
+
+    if let Some((d, rules)) = quarantine::latest_hit(worker_root, &s.tenant)? {
+        return Ok(Some(format!(
+            "tenant quarantined by the decision at {} ({})",
+            d.timestamp,
+            rules.join("; ")
+        )));
+    }
+
+    if let Some((p, policy)) = approvals::load_policy(worker_root, &s.tenant)? {
+        let file = p.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+        if policy.get(POLICY_KEY).and_then(|v| v.as_bool()) == Some(false) {
+            return Ok(Some(format!("{file} disables scheduled runs")));
+        }
+
+        if let Some(list) = policy.get("allowed_backends").and_then(|v| v.as_array()) {
+            if !list.iter().any(|b| b.as_str() == Some(s.backend.as_str())) {
+                return Ok(Some(format!("{file} does not allow the {} backend", s.backend)));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+fn clip(s: &str) -> String {
+    let line = s.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+    match line.char_indices().nth(DETAIL_MAX) {
+        Some((i, _)) => format!("{}…", &line[..i]),
+        None => line.to_string(),
+    }
+}
+
+fn fire(worker_root: &Path, worker_bin: &Result<PathBuf, String>, s: &Schedule) -> RunResult {
+    let at = Utc::now().to_rfc3339();
+
+    match skip_reason(worker_root, s) {
+        Ok(None) => {}
+        Ok(Some(why)) => return RunResult::new(&at, "skipped", why),
+        Err(e) => return RunResult::new(&at, "failed", format!("checking tenant: {e:#}")),
+    }
+
+    let bin = match worker_bin {
+        Ok(b) => b,
+        Err(e) => return RunResult::new(&at, "failed", format!("worker binary: {e}")),
+    };
+
+    let args: Vec<String> = ["run", "--tenant", &s.tenant, "--backend", &s.backend]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+
+    let mut cmd = crate::worker_command(worker_root, bin, &args);
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    let run = runs::start(&args, &format!("schedule:{}", s.id));
+    run.tag(&mut cmd);
+
+    let started = Instant::now();
+    let output = process_registry::spawn(worker_root, &mut cmd, Some(&s.tenant), Some(&s.backend))
+        .and_then(|c| c.wait_with_output().context("waiting for the worker"));
+    let recorded = run.finish(worker_root, &output);
+
+    let mut r = match output {
+        Ok(out) if out.status.success() => {
+            let mut r = RunResult::new(&at, "ok", clip(&String::from_utf8_lossy(&out.stdout)));
+            r.exit_code = out.status.code();
+            r
+        }
+        Ok(out) => {
+            let mut r = RunResult::new(&at, "failed", clip(&String::from_utf8_lossy(&out.stderr)));
+            r.exit_code = out.status.code();
+            r
+        }
+        Err(e) => RunResult::new(&at, "failed", format!("{e:#}")),
+    };
+    r.duration_ms = Some(started.elapsed().as_millis() as u64);
+    match recorded {
+        Ok(rec) => {
+            match quarantine::apply_to_run(worker_root, &rec) {
+                Ok(held) if !held.is_empty() => {
+                    r.detail = format!("{} (auto-quarantined: {})", r.detail, held.join(", "))
+                }
+                Ok(_) => {}
+                Err(e) => r.detail = format!("{} (auto-quarantine failed: {e:#})", r.detail),
+            }
+            r.run_id = Some(rec.run_id);
+        }
+        Err(e) => r.detail = format!("{} (run not recorded: {e:#})", r.detail),
+    }
+    r
+}
+
+/// Run every enabled schedule whose next run has passed. Occurrences
+/// missed while the console was closed run once, not once each.
+pub fn tick(worker_root: &Path, worker_bin: &Result<PathBuf, String>) -> Result<Vec<(String, RunResult)>> {
+    if !schedules_path(worker_root).exists() {
+        return Ok(vec![]);
+    }
+
+    let now = Utc::now();
+
+    // Claimed by moving next_run forward before running, so a slow run
+    // is not started again by the next tick.
+    let due: Vec<Schedule> = update(worker_root, |f| {
+        let mut due = vec![];
+        for s in f.schedules.iter_mut().filter(|s| s.enabled) {
+            let passed = s
+                .next_run
+                .as_deref()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&Utc) <= now);
+
+            match passed {
+                Some(true) => {
+                    due.push(s.clone());
+                    s.next_run = next_run_after(&s.cron, now);
+                }
+                Some(false) => {}
+                None => s.next_run = next_run_after(&s.cron, now),
+            }
+        }
+        Ok(due)
+    })?;
+
+    let mut results = vec![];
+    for s in due {
+        let r = fire(worker_root, worker_bin, &s);
+
+        // The schedule may have been deleted while it ran.
+        update(worker_root, |f| {
+            if let Some(x) = f.schedules.iter_mut().find(|x| x.id == s.id) {
+                x.last_run = Some(r.clone());
+            }
+            Ok(())
+        })?;
+
+        results.push((s.id, r));
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(expr: &str, after: &str) -> Option<String> {
+        expr.parse::<CronSpec>().unwrap().next_after(at(after)).map(|t| t.to_rfc3339())
+    }
+
+    #[test]
+    fn next_is_strictly_after_and_minute_aligned() {
+        assert_eq!(next("* * * * *", "2026-03-01T10:00:00Z").as_deref(), Some("2026-03-01T10:01:00+00:00"));
+        assert_eq!(next("* * * * *", "2026-03-01T10:00:59.9Z").as_deref(), Some("2026-03-01T10:01:00+00:00"));
+        assert_eq!(next("*/15 * * * *", "2026-03-01T10:15:00Z").as_deref(), Some("2026-03-01T10:30:00+00:00"));
+        assert_eq!(next("5/20 * * * *", "2026-03-01T10:46:00Z").as_deref(), Some("2026-03-01T11:05:00+00:00"));
+    }
+
+    #[test]
+    fn rolls_over_days_months_and_years() {
+        assert_eq!(next("30 2 * * *", "2026-03-01T03:00:00Z").as_deref(), Some("2026-03-02T02:30:00+00:00"));
+        assert_eq!(next("@monthly", "2026-12-15T00:00:00Z").as_deref(), Some("2027-01-01T00:00:00+00:00"));
+        assert_eq!(next("0 0 31 * *", "2026-04-01T00:00:00Z").as_deref(), Some("2026-05-31T00:00:00+00:00"));
+        assert_eq!(next("0 12 29 feb *", "2026-03-01T00:00:00Z").as_deref(), Some("2028-02-29T12:00:00+00:00"));
+    }
+
+    #[test]
+    fn weekday_names_and_sunday_as_seven() {
+        // 2026-03-07 is a Saturday.
+        assert_eq!(next("0 9 * * mon-fri", "2026-03-07T08:00:00Z").as_deref(), Some("2026-03-09T09:00:00+00:00"));
+        assert_eq!(next("0 9 * * 7", "2026-03-07T08:00:00Z").as_deref(), Some("2026-03-08T09:00:00+00:00"));
+        assert_eq!(next("@weekly", "2026-03-07T08:00:00Z").as_deref(), Some("2026-03-08T00:00:00+00:00"));
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either() {
+        // The 15th, or any Monday: Monday 2026-03-09 comes first.
+        assert_eq!(next("0 0 15 * mon", "2026-03-07T00:00:00Z").as_deref(), Some("2026-03-09T00:00:00+00:00"));
+        assert_eq!(next("0 0 15 * mon", "2026-03-09T00:00:00Z").as_deref(), Some("2026-03-15T00:00:00+00:00"));
+    }
+
+    #[test]
+    fn impossible_dates_never_match() {
+        assert_eq!(next("0 0 31 2 *", "2026-01-01T00:00:00Z"), None);
+        assert_eq!(next("0 0 30 feb *", "2026-01-01T00:00:00Z"), None);
+    }
+
+    #[test]
+    fn malformed_expressions_are_rejected() {
+        for bad in ["", "* * * *", "* * * * * *", "60 * * * *", "* 24 * * *", "* * 0 * *", "*/0 * * * *",
+                    "10-5 * * * *", "* * * foo *", "* * * * 8", "a * * * *"] {
+            assert!(bad.parse::<CronSpec>().is_err(), "{bad:?} parsed");
+        }
+        assert_eq!("@daily".parse::<CronSpec>().unwrap(), "0 0 * * *".parse::<CronSpec>().unwrap());
+    }
+}
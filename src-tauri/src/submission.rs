@@ -0,0 +1,338 @@
+use serde::{Serialize, Deserialize};
+use std::{fs, path::Path};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::Utc;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+use crate::atomic_fs::{self, Expect};
+use crate::tenant_id;
+
+/// Metadata an agent attaches to a submitted module.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentMetadata {
+    pub agent_id: String,
+    pub model: String,
+    pub prompt_hash: String,
+    #[serde(default)]
+    pub parent_task: Option<String>,
+    /// Naming hint for the tenant; falls back to the agent id.
+    #[serde(default)]
+    pub module_name: Option<String>,
+}
+
+/// Provenance as persisted in `manifest.json` under `provenance`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgentProvenance {
+    pub agent_id: String,
+    pub model: String,
+    pub prompt_hash: String,
+    #[serde(default)]
+    pub parent_task: Option<String>,
+
+    pub sha256: String,
+    pub submitted_at: String,
+
+    #[serde(default)]
+    pub agent_pubkey: Option<String>,
+    #[serde(default)]
+    pub signature: Option<String>,
+    #[serde(default)]
+    pub signature_verified: bool,
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Canonical bytes the agent signs. Field order is fixed; absent
+/// parent task or module name is encoded as an empty value.
+pub fn canonical_submission_string(sha256: &str, meta: &AgentMetadata) -> String {
+    format!(
+        "sha256={}\n\
+agent_id={}\n\
+model={}\n\
+prompt_hash={}\n\
+parent_task={}\n\
+module_name={}\n",
+        sha256.trim(),
+        meta.agent_id.trim(),
+        meta.model.trim(),
+        meta.prompt_hash.trim(),
+        meta.parent_task.as_deref().unwrap_or("").trim(),
+        meta.module_name.as_deref().unwrap_or("").trim(),
+    )
+}
+
+fn verify_agent_signature(
+    sha256: &str,
+    meta: &AgentMetadata,
+    pubkey_b64: &str,
+    sig_b64: &str,
+) -> Result<()> {
+    let pk_bytes: [u8; 32] = STANDARD
+        .decode(pubkey_b64.trim())
+        .context("decoding agent public key")?
+        .try_into()
+        .map_err(|_| anyhow!("agent public key must be 32 bytes"))?;
+
+    let sig_bytes: [u8; 64] = STANDARD
+        .decode(sig_b64.trim())
+        .context("decoding agent signature")?
+        .try_into()
+        .map_err(|_| anyhow!("agent signature must be 64 bytes"))?;
+
+    let key = VerifyingKey::from_bytes(&pk_bytes)
+        .map_err(|e| anyhow!("invalid agent public key: {e}"))?;
+
+    key.verify(
+        canonical_submission_string(sha256, meta).as_bytes(),
+        &Signature::from_bytes(&sig_bytes),
+    )
+    .map_err(|_| anyhow!("agent signature does not match submission"))
+}
+
+/// Registered agent keys live in `keys/agents/<agent_id>.b64`.
+fn registered_agent_key(worker_root: &Path, agent_id: &str) -> Option<String> {
+    let p = worker_root
+        .join("keys")
+        .join("agents")
+        .join(format!("{agent_id}.b64"));
+
+    fs::read_to_string(p).ok().map(|s| s.trim().to_string())
+}
+
+fn valid_agent_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 64
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && !id.starts_with('.')
+}
+
+/// Ingest an agent-submitted module into `modules/` as a pending tenant.
+///
+/// The signature is optional. It only counts as verified against the key
+/// registered for the agent; a caller-supplied key is recorded as a claim
+/// and must match the registered one when both exist. A signature that
+/// fails against the registered key refuses the submission. Approval
+/// still goes through the normal inbox flow.
+pub fn submit_agent_module(
+    worker_root: &Path,
+    wasm: &[u8],
+    meta: &AgentMetadata,
+    signature: Option<&str>,
+    agent_pubkey: Option<&str>,
+) -> Result<String> {
+    if !valid_agent_id(&meta.agent_id) {
+        bail!("invalid agent id '{}'", meta.agent_id);
+    }
+    if meta.model.trim().is_empty() || meta.prompt_hash.trim().is_empty() {
+        bail!("model and prompt_hash are required");
+    }
+    if wasm.len() < 8 || &wasm[..4] != b"\0asm" {
+        bail!("submission is not a WebAssembly module");
+    }
+
+    let sha256 = sha256_hex(wasm);
+
+    let registered = registered_agent_key(worker_root, &meta.agent_id);
+    let claimed = agent_pubkey.map(|s| s.trim().to_string());
+
+    if let (Some(reg), Some(claim)) = (&registered, &claimed) {
+        if reg != claim {
+            bail!("supplied public key is not the one registered for agent '{}'", meta.agent_id);
+        }
+    }
+
+    // Without a registered key the signature proves nothing about who
+    // sent it, so it is kept for the record but left unverified.
+    let signature_verified = match (signature, &registered) {
+        (Some(sig), Some(pk)) => {
+            verify_agent_signature(&sha256, meta, pk, sig)?;
+            true
+        }
+        _ => false,
+    };
+
+    let pubkey = registered.or(claimed);
+
+    let modules = worker_root.join("modules");
+    fs::create_dir_all(&modules)
+        .with_context(|| format!("creating {}", modules.display()))?;
+
+    let hint = meta.module_name.as_deref().unwrap_or(&meta.agent_id);
+    let tenant = tenant_id::tenant_id(hint, &sha256);
+    let tenant_dir = modules.join(&tenant);
+    if tenant_dir.exists() {
+        bail!("module already submitted as tenant {tenant}");
+    }
+
+    fs::create_dir_all(&tenant_dir)
+        .with_context(|| format!("creating {}", tenant_dir.display()))?;
+
+    atomic_fs::write_atomic(&tenant_dir.join("module.wasm"), wasm, Expect::Absent)?;
+    atomic_fs::write_atomic(&tenant_dir.join("module.sha256"), sha256.as_bytes(), Expect::Absent)?;
+
+    let now = Utc::now().to_rfc3339();
+    let provenance = AgentProvenance {
+        agent_id: meta.agent_id.clone(),
+        model: meta.model.trim().to_string(),
+        prompt_hash: meta.prompt_hash.trim().to_string(),
+        parent_task: meta.parent_task.clone(),
+        sha256,
+        submitted_at: now.clone(),
+        agent_pubkey: pubkey,
+        signature: signature.map(|s| s.trim().to_string()),
+        signature_verified,
+    };
+
+    let manifest = serde_json::json!({
+        "tenant": tenant,
+        "display_name": meta.module_name,
+        "ingestion": {
+            "channel": "automated",
+            "source": "agent",
+            "timestamp": now
+        },
+        "provenance": provenance
+    });
+
+    atomic_fs::write_atomic(
+        &tenant_dir.join("manifest.json"),
+        serde_json::to_string_pretty(&manifest)?.as_bytes(),
+        Expect::Absent,
+    )?;
+
+    Ok(tenant)
+}
+
+/// Read provenance back from a tenant manifest, if it has any.
+pub fn read_provenance(manifest: &serde_json::Value) -> Option<AgentProvenance> {
+    manifest
+        .get("provenance")
+        .and_then(|p| serde_json::from_value(p.clone()).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const WASM: &[u8] = b"\0asm\x01\0\0\0";
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn pubkey(seed: u8) -> String {
+        STANDARD.encode(key(seed).verifying_key().as_bytes())
+    }
+
+    fn meta() -> AgentMetadata {
+        AgentMetadata {
+            agent_id: "builder-1".into(),
+            model: "m".into(),
+            prompt_hash: "p".into(),
+            parent_task: None,
+            module_name: None,
+        }
+    }
+
+    fn sign(seed: u8, wasm: &[u8], meta: &AgentMetadata) -> String {
+        let msg = canonical_submission_string(&sha256_hex(wasm), meta);
+        STANDARD.encode(key(seed).sign(msg.as_bytes()).to_bytes())
+    }
+
+    fn register(root: &Path, seed: u8) {
+        fs::create_dir_all(root.join("keys/agents")).unwrap();
+        fs::write(root.join("keys/agents/builder-1.b64"), pubkey(seed)).unwrap();
+    }
+
+    fn provenance(root: &Path, tenant: &str) -> AgentProvenance {
+        let raw = fs::read_to_string(root.join("modules").join(tenant).join("manifest.json")).unwrap();
+        read_provenance(&serde_json::from_str(&raw).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn verifies_against_the_registered_key() {
+        let dir = tempfile::tempdir().unwrap();
+        register(dir.path(), 1);
+
+        let t = submit_agent_module(dir.path(), WASM, &meta(), Some(&sign(1, WASM, &meta())), None).unwrap();
+        let p = provenance(dir.path(), &t);
+        assert!(p.signature_verified);
+        assert_eq!(p.agent_pubkey.as_deref(), Some(pubkey(1).as_str()));
+    }
+
+    #[test]
+    fn refuses_a_caller_key_that_is_not_registered() {
+        let dir = tempfile::tempdir().unwrap();
+        register(dir.path(), 1);
+
+        // Signed with the caller's own key and presented alongside it.
+        let err = submit_agent_module(
+            dir.path(),
+            WASM,
+            &meta(),
+            Some(&sign(2, WASM, &meta())),
+            Some(&pubkey(2)),
+        );
+        assert!(err.is_err());
+
+        // Same signature without the claimed key fails the registered one.
+        assert!(submit_agent_module(dir.path(), WASM, &meta(), Some(&sign(2, WASM, &meta())), None).is_err());
+        assert!(!dir.path().join("modules").exists());
+    }
+
+    #[test]
+    fn unregistered_agent_is_never_verified() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let t = submit_agent_module(
+            dir.path(),
+            WASM,
+            &meta(),
+            Some(&sign(2, WASM, &meta())),
+            Some(&pubkey(2)),
+        )
+        .unwrap();
+
+        let p = provenance(dir.path(), &t);
+        assert!(!p.signature_verified);
+        assert_eq!(p.agent_pubkey.as_deref(), Some(pubkey(2).as_str()));
+        assert!(p.signature.is_some());
+    }
+
+    #[test]
+    fn refuses_a_tampered_payload() {
+        let dir = tempfile::tempdir().unwrap();
+        register(dir.path(), 1);
+
+        let sig = sign(1, WASM, &meta());
+        let tampered = b"\0asm\x01\0\0\0\x00";
+        assert!(submit_agent_module(dir.path(), tampered, &meta(), Some(&sig), None).is_err());
+
+        let mut other = meta();
+        other.prompt_hash = "q".into();
+        assert!(submit_agent_module(dir.path(), WASM, &other, Some(&sig), None).is_err());
+
+        let mut renamed = meta();
+        renamed.module_name = Some("payroll".into());
+        assert!(submit_agent_module(dir.path(), WASM, &renamed, Some(&sig), None).is_err());
+    }
+
+    #[test]
+    fn canonical_string_covers_every_field() {
+        let mut m = meta();
+        m.module_name = Some("demo".into());
+
+        assert_eq!(
+            canonical_submission_string("aa", &m),
+            "sha256=aa\nagent_id=builder-1\nmodel=m\nprompt_hash=p\nparent_task=\nmodule_name=demo\n"
+        );
+    }
+}
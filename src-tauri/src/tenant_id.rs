@@ -0,0 +1,117 @@
+use std::{fs, path::Path};
+
+use crate::submission::sha256_hex;
+
+/// Characters kept from the module-name hint.
+fn sanitize_hint(raw: &str) -> String {
+    let mut out = String::new();
+    for c in raw.trim().to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c);
+        } else if !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+
+    let out: String = out.trim_matches('-').chars().take(32).collect();
+    let out = out.trim_end_matches('-').to_string();
+
+    if out.is_empty() { "module".into() } else { out }
+}
+
+/// `<hint>-<first 12 hex of sha256>`. The same module imported under the
+/// same hint always lands in the same directory.
+pub fn tenant_id(hint: &str, sha256: &str) -> String {
+    let short: String = sha256.chars().take(12).collect();
+    format!("{}-{}", sanitize_hint(hint), short)
+}
+
+/// SHA-256 of a tenant's active module, hashed from the wasm itself.
+/// Manifest fields and `module.sha256` can arrive inside an imported zip,
+/// so they are never trusted for identity.
+pub fn tenant_sha(tenant_dir: &Path) -> Option<String> {
+    fs::read(tenant_dir.join("module.wasm")).ok().map(|b| sha256_hex(&b))
+}
+
+/// [`tenant_id`], suffixed `-2`, `-3`, ... when that directory is taken,
+/// e.g. when the same module is deliberately imported a second time.
+pub fn unused_tenant_id(modules_dir: &Path, hint: &str, sha256: &str) -> String {
+    let base = tenant_id(hint, sha256);
+
+    std::iter::once(base.clone())
+        .chain((2..).map(|n| format!("{base}-{n}")))
+        .find(|id| !modules_dir.join(id).exists())
+        .expect("unbounded suffixes")
+}
+
+/// Existing tenant already holding this exact module, if any.
+pub fn find_by_sha(worker_root: &Path, sha256: &str) -> Option<String> {
+    let entries = fs::read_dir(worker_root.join("modules")).ok()?;
+
+    let mut hits: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter(|e| tenant_sha(&e.path()).as_deref() == Some(sha256))
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+
+    hits.sort();
+    hits.into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHA: &str = "abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789";
+
+    #[test]
+    fn ids_from_hint_and_short_sha() {
+        assert_eq!(tenant_id("My Module!.wasm", SHA), "my-module-wasm-abcdef012345");
+        assert_eq!(tenant_id("  ***  ", SHA), "module-abcdef012345");
+        assert_eq!(tenant_id("../../etc/passwd", SHA), "etc-passwd-abcdef012345");
+        assert_eq!(tenant_id(&"x".repeat(80), SHA), format!("{}-abcdef012345", "x".repeat(32)));
+    }
+
+    fn add_tenant(root: &Path, id: &str, wasm: &[u8], manifest: serde_json::Value) {
+        let dir = root.join("modules").join(id);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("module.wasm"), wasm).unwrap();
+        fs::write(dir.join("manifest.json"), manifest.to_string()).unwrap();
+    }
+
+    #[test]
+    fn duplicates_found_by_real_hash_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let real = sha256_hex(b"real");
+
+        // claims to hold `real` but does not
+        add_tenant(
+            root,
+            "liar",
+            b"other",
+            serde_json::json!({ "active_revision": real, "provenance": { "sha256": real } }),
+        );
+        fs::write(root.join("modules/liar/module.sha256"), &real).unwrap();
+        assert_eq!(find_by_sha(root, &real), None);
+
+        add_tenant(root, "honest", b"real", serde_json::json!({}));
+        assert_eq!(find_by_sha(root, &real).as_deref(), Some("honest"));
+    }
+
+    #[test]
+    fn unused_id_adds_a_suffix() {
+        let dir = tempfile::tempdir().unwrap();
+        let modules = dir.path().join("modules");
+
+        let first = unused_tenant_id(&modules, "demo", SHA);
+        assert_eq!(first, "demo-abcdef012345");
+
+        fs::create_dir_all(modules.join(&first)).unwrap();
+        assert_eq!(unused_tenant_id(&modules, "demo", SHA), "demo-abcdef012345-2");
+
+        fs::create_dir_all(modules.join("demo-abcdef012345-2")).unwrap();
+        assert_eq!(unused_tenant_id(&modules, "demo", SHA), "demo-abcdef012345-3");
+    }
+}
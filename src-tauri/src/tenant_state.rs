@@ -2,12 +2,19 @@ use serde::{Serialize, Deserialize};
 use std::{fs, path::Path};
 use serde_json::Value;
 use anyhow::{Context, Result};
-use chrono::Utc;
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::approvals;
+use crate::atomic_fs;
+use crate::decisions;
+use crate::operator::Operator;
+use crate::runs;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TenantState {
     pub id: String,
     pub name: String,
+    pub active_revision: Option<String>,
 
     pub ingestion: IngestionState,
     pub authorization: AuthorizationState,
@@ -27,18 +34,51 @@ pub struct AuthorizationState {
     pub approved: bool,
     pub approved_at: Option<String>,
     pub approved_by: Option<String>,
+    pub approvers: Vec<String>, // quorum approvers recorded in the manifest
+
+    pub expires_at: Option<String>,
+    pub max_executions: Option<u64>,
+    pub executions_since_approval: u64,
+    pub expired: bool,
+
+    pub suspension: Option<Suspension>,
+}
+
+/// Operator hold on a tenant, kept in the manifest under `suspended`.
+/// While present the tenant is not approved, renewed or run.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Suspension {
+    pub suspended_at: String,
+    pub suspended_by: String,
+    #[serde(default)]
+    pub suspended_by_fingerprint: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+/// Limits placed on an approval. Defaults come from the policy's
+/// `trust` block; both are optional.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TrustTerms {
+    pub ttl_hours: Option<u64>,
+    pub max_executions: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ExecutionState {
     pub has_executed: bool,
     pub last_execution_time: Option<String>,
+    /// Console-started runs on record for the tenant (see `runs`).
+    pub recorded_runs: u64,
+    pub last_run_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ObservationState {
     pub current_threat_score: Option<u8>,
-    pub state: String, // pending_approval | blocked | cleared | observed
+    pub state: String, // pending_approval | blocked | suspended | expired | cleared | observed
 }
 
 fn read_json(path: &Path) -> Result<Value> {
@@ -61,18 +101,21 @@ fn get_bool(v: &Value, key: &str) -> Option<bool> {
     v.get(key).and_then(|b| b.as_bool())
 }
 
-fn build_decision_index(worker_root: &Path)
--> std::collections::HashMap<String, (String, u8)> {
-    let mut out = std::collections::HashMap::new();
-    let p = worker_root.join("logs").join("guardian_decisions.jsonl");
+type DecisionIndex = std::collections::HashMap<String, (String, u8)>;
 
-    let raw = match fs::read_to_string(&p) {
-        Ok(s) => s,
-        Err(_) => return out,
-    };
+// tenant -> times of allowed executions
+type AllowIndex = std::collections::HashMap<String, Vec<DateTime<Utc>>>;
+
+fn parse_ts(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts).ok().map(|t| t.with_timezone(&Utc))
+}
+
+fn build_decision_index(worker_root: &Path) -> (DecisionIndex, AllowIndex) {
+    let mut out = DecisionIndex::new();
+    let mut allows = AllowIndex::new();
 
-    for line in raw.lines() {
-        let v: Value = match serde_json::from_str(line) {
+    for line in decisions::raw_lines(worker_root) {
+        let v: Value = match serde_json::from_str(&line) {
             Ok(x) => x,
             Err(_) => continue,
         };
@@ -91,24 +134,68 @@ fn build_decision_index(worker_root: &Path)
             .and_then(|n| n.as_u64())
             .unwrap_or(0) as u8;
 
+        let at = parse_ts(&ts);
+        if let (Some("allow"), Some(at)) = (v.get("decision").and_then(|d| d.as_str()), at) {
+            allows.entry(tenant.clone()).or_default().push(at);
+        }
+
+        // Compared as instants: the worker and the console do not write
+        // the same offset.
         match out.get(&tenant) {
             None => {
                 out.insert(tenant, (ts, score));
             }
             Some((prev_ts, _)) => {
-                if ts > *prev_ts {
+                if at > parse_ts(prev_ts) {
                     out.insert(tenant, (ts, score));
                 }
             }
         }
     }
 
+    (out, allows)
+}
+
+// tenant -> (runs on record, id of the latest)
+type RunIndex = std::collections::HashMap<String, (u64, String)>;
+
+/// Runs naming a tenant, and `--all` runs that logged a decision for it.
+fn build_run_index(worker_root: &Path) -> RunIndex {
+    let mut out = RunIndex::new();
+
+    for r in runs::load_all(worker_root) {
+        let mut tenants: Vec<&str> = match &r.tenant {
+            Some(t) => vec![t.as_str()],
+            None => r.decisions.iter().map(|d| d.tenant.as_str()).collect(),
+        };
+        tenants.sort();
+        tenants.dedup();
+
+        for t in tenants {
+            let e = out.entry(t.to_string()).or_insert((0, String::new()));
+            e.0 += 1;
+            e.1 = r.run_id.clone();
+        }
+    }
+
     out
 }
 
+fn is_expired(expires_at: Option<&str>, max_executions: Option<u64>, used: u64) -> bool {
+    let past_expiry = expires_at
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc) <= Utc::now())
+        .unwrap_or(false);
+
+    let over_budget = max_executions.map(|m| used >= m).unwrap_or(false);
+
+    past_expiry || over_budget
+}
+
 pub fn list_tenant_states(worker_root: &Path) -> Result<Vec<TenantState>> {
     let modules_dir = worker_root.join("modules");
-    let decision_index = build_decision_index(worker_root);
+    let (decision_index, allow_index) = build_decision_index(worker_root);
+    let mut run_index = build_run_index(worker_root);
 
     let mut tenants = vec![];
 
@@ -125,8 +212,7 @@ pub fn list_tenant_states(worker_root: &Path) -> Result<Vec<TenantState>> {
             continue;
         }
 
-        let name = entry.file_name().to_string_lossy().to_string();
-        let id = name.clone();
+        let id = entry.file_name().to_string_lossy().to_string();
 
         let manifest_path = path.join("manifest.json");
         let manifest = if manifest_path.exists() {
@@ -135,6 +221,13 @@ pub fn list_tenant_states(worker_root: &Path) -> Result<Vec<TenantState>> {
             None
         };
 
+        // Display name is cosmetic; the directory name stays the id.
+        let name = manifest
+            .as_ref()
+            .and_then(|m| get_str(m, &["display_name"]))
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| id.clone());
+
         let (channel, source, ts_ingest) = if let Some(m) = &manifest {
             (
                 get_str(m, &["ingestion", "channel"]).unwrap_or_else(|| "unknown".into()),
@@ -155,7 +248,43 @@ pub fn list_tenant_states(worker_root: &Path) -> Result<Vec<TenantState>> {
             (false, None, None)
         };
 
-        let observed = decision_index.get(&name).cloned();
+        // Only approvals of the module as it is now, as in the quorum check.
+        let approvers: Vec<String> = match manifest
+            .as_ref()
+            .and_then(|m| m.get("approvals"))
+            .and_then(|a| a.as_array())
+            .filter(|a| !a.is_empty())
+        {
+            Some(a) => {
+                let sha256 = approvals::current_module_sha(worker_root, &id).ok();
+                a.iter()
+                    .filter(|r| sha256.is_some() && r.get("sha256").and_then(|s| s.as_str()) == sha256.as_deref())
+                    .filter_map(|r| r.get("approver").and_then(|s| s.as_str()))
+                    .map(|s| s.to_string())
+                    .collect()
+            }
+            None => vec![],
+        };
+
+        let expires_at = manifest
+            .as_ref()
+            .and_then(|m| m.get("expires_at"))
+            .and_then(|s| s.as_str())
+            .map(|s| s.to_string());
+        let max_executions = manifest
+            .as_ref()
+            .and_then(|m| m.get("max_executions"))
+            .and_then(|n| n.as_u64());
+
+        let executions_since_approval = match (approved_at.as_deref().and_then(parse_ts), allow_index.get(&id)) {
+            (Some(at), Some(runs)) => runs.iter().filter(|ts| **ts >= at).count() as u64,
+            _ => 0,
+        };
+
+        let expired = approved
+            && is_expired(expires_at.as_deref(), max_executions, executions_since_approval);
+
+        let observed = decision_index.get(&id).cloned();
         let has_executed = observed.is_some();
 
         let (last_execution_time, current_threat_score) = match observed {
@@ -163,8 +292,19 @@ pub fn list_tenant_states(worker_root: &Path) -> Result<Vec<TenantState>> {
             None => (None, None),
         };
 
-        let state = if !manifest_path.exists() {
+        let rejected = manifest
+            .as_ref()
+            .and_then(|m| get_bool(m, "rejected"))
+            .unwrap_or(false);
+
+        let suspension = manifest.as_ref().and_then(read_suspension);
+
+        let state = if !manifest_path.exists() || rejected {
             "blocked".to_string()
+        } else if suspension.is_some() {
+            "suspended".to_string()
+        } else if expired {
+            "expired".to_string()
         } else if has_executed {
             "observed".to_string()
         } else if channel != "manual" && !approved {
@@ -173,9 +313,19 @@ pub fn list_tenant_states(worker_root: &Path) -> Result<Vec<TenantState>> {
             "cleared".to_string()
         };
 
+        let active_revision = manifest
+            .as_ref()
+            .and_then(|m| get_str(m, &["active_revision"]));
+
+        let (recorded_runs, last_run_id) = match run_index.remove(&id) {
+            Some((n, last)) => (n, Some(last)),
+            None => (0, None),
+        };
+
         tenants.push(TenantState {
             id,
             name,
+            active_revision,
             ingestion: IngestionState {
                 channel,
                 source,
@@ -185,10 +335,18 @@ pub fn list_tenant_states(worker_root: &Path) -> Result<Vec<TenantState>> {
                 approved,
                 approved_at,
                 approved_by,
+                approvers,
+                expires_at,
+                max_executions,
+                executions_since_approval,
+                expired,
+                suspension,
             },
             execution: ExecutionState {
                 has_executed,
                 last_execution_time,
+                recorded_runs,
+                last_run_id,
             },
             observation: ObservationState {
                 current_threat_score,
@@ -204,22 +362,543 @@ pub fn list_tenant_states(worker_root: &Path) -> Result<Vec<TenantState>> {
 //
 // 🔐 NEW — persist authorization so GUI + Watchtower update correctly
 //
-pub fn mark_authorized(worker_root: &Path, tenant: &str) -> Result<()> {
+/// Who cleared a tenant: the active operator, or a maintainer quorum
+/// whose individual signatures are already in `approvals`.
+pub enum ApprovedBy<'a> {
+    Operator(&'a Operator),
+    Quorum(&'a [String]),
+}
+
+pub fn mark_authorized(worker_root: &Path, tenant: &str, by: ApprovedBy) -> Result<()> {
+    let terms = policy_trust_terms(worker_root, tenant)?;
+    write_authorization(worker_root, tenant, by, &terms, false)
+}
+
+pub fn policy_trust_terms(worker_root: &Path, tenant: &str) -> Result<TrustTerms> {
+    let Some((p, v)) = approvals::load_policy(worker_root, tenant)? else {
+        return Ok(TrustTerms::default());
+    };
+
+    match v.get("trust") {
+        Some(t) => serde_json::from_value(t.clone())
+            .with_context(|| format!("parsing trust block in {}", p.display())),
+        None => Ok(TrustTerms::default()),
+    }
+}
+
+/// When an approval granted at `now` for `ttl_hours` runs out.
+fn expiry_after(now: DateTime<Utc>, ttl_hours: u64) -> Result<DateTime<Utc>> {
+    i64::try_from(ttl_hours)
+        .ok()
+        .and_then(TimeDelta::try_hours)
+        .and_then(|d| now.checked_add_signed(d))
+        .ok_or_else(|| anyhow::anyhow!("ttl_hours {ttl_hours} is out of range"))
+}
+
+fn write_authorization(
+    worker_root: &Path,
+    tenant: &str,
+    by: ApprovedBy,
+    terms: &TrustTerms,
+    renewal: bool,
+) -> Result<()> {
+    let manifest_path = worker_root
+        .join("modules")
+        .join(tenant)
+        .join("manifest.json");
+
+    let now = Utc::now();
+    let approved_at = now.to_rfc3339();
+    let sha256 = approvals::current_module_sha(worker_root, tenant)?;
+    let expires_at = terms.ttl_hours.map(|h| expiry_after(now, h)).transpose()?;
+
+    let attribution = match by {
+        ApprovedBy::Operator(op) => {
+            let msg = approvals::canonical_approval_string(tenant, &sha256, &op.name, &approved_at);
+
+            (
+                Value::String(op.name.clone()),
+                Value::String(op.fingerprint.clone()),
+                Value::String(op.sign(msg.as_bytes())),
+            )
+        }
+        ApprovedBy::Quorum(approvers) => (
+            Value::String(format!("quorum:{}", approvers.join(","))),
+            Value::Null,
+            Value::Null,
+        ),
+    };
+
+    atomic_fs::update_json(&manifest_path, |v| {
+        // Checked under the write so a concurrent suspend cannot slip past.
+        if let Some(s) = read_suspension(v) {
+            anyhow::bail!(
+                "tenant {tenant} is suspended (by {} at {}); resume it before approving",
+                s.suspended_by,
+                s.suspended_at
+            );
+        }
+        if get_bool(v, "rejected").unwrap_or(false) {
+            match renewal {
+                true => anyhow::bail!("tenant {tenant} was rejected; its trust cannot be renewed"),
+                false => anyhow::bail!("tenant {tenant} was rejected; it cannot be approved"),
+            }
+        }
+
+        let (by, fingerprint, signature) = attribution.clone();
+
+        // approved_by is always the signer of the current window. A
+        // renewal moves the approval it replaces into `renewals`.
+        if renewal {
+            if let Some(prev) = get_str(v, &["approved_by"]) {
+                let earlier = serde_json::json!({
+                    "approved_by": prev,
+                    "approved_by_fingerprint": v.get("approved_by_fingerprint").cloned().unwrap_or(Value::Null),
+                    "approved_at": v.get("approved_at").cloned().unwrap_or(Value::Null),
+                });
+                match v.get_mut("renewals").and_then(|r| r.as_array_mut()) {
+                    Some(list) => list.push(earlier),
+                    None => v["renewals"] = Value::Array(vec![earlier]),
+                }
+            }
+        }
+
+        v["approved"] = Value::Bool(true);
+        v["approved_at"] = Value::String(approved_at.clone());
+        v["approved_sha256"] = Value::String(sha256.clone());
+
+        v["approved_by"] = by;
+        v["approved_by_fingerprint"] = fingerprint;
+        v["approval_signature"] = signature;
+
+        v["expires_at"] = match expires_at {
+            Some(t) => Value::String(t.to_rfc3339()),
+            None => Value::Null,
+        };
+        v["max_executions"] = match terms.max_executions {
+            Some(n) => Value::from(n),
+            None => Value::Null,
+        };
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+/// Re-approve an already approved tenant. Restarts the approval window
+/// (and with it the execution count) under new or policy-default terms.
+/// Held to the same rules as a single-operator approval: tenants under a
+/// maintainer quorum renew by collecting fresh signed approvals, and
+/// rejected tenants cannot be renewed.
+pub fn renew_trust(
+    worker_root: &Path,
+    tenant: &str,
+    op: &Operator,
+    terms: Option<TrustTerms>,
+) -> Result<TrustTerms> {
+    let manifest_path = worker_root
+        .join("modules")
+        .join(tenant)
+        .join("manifest.json");
+
+    let v = read_json(&manifest_path)?;
+    if !get_bool(&v, "approved").unwrap_or(false) {
+        anyhow::bail!("tenant {tenant} was never approved; approve it first");
+    }
+    if get_bool(&v, "rejected").unwrap_or(false) {
+        anyhow::bail!("tenant {tenant} was rejected; its trust cannot be renewed");
+    }
+    if let Some(q) = approvals::load_quorum(worker_root, tenant)? {
+        anyhow::bail!(
+            "tenant {tenant} requires {} of {} maintainer approvals; submit signed approvals to renew it",
+            q.required,
+            q.maintainers.len()
+        );
+    }
+
+    let terms = match terms {
+        Some(t) => t,
+        None => policy_trust_terms(worker_root, tenant)?,
+    };
+
+    write_authorization(worker_root, tenant, ApprovedBy::Operator(op), &terms, true)?;
+    Ok(terms)
+}
+
+/// Rejections stay on the manifest so the same hash can be flagged
+/// the next time it shows up in the inbox.
+pub fn mark_rejected(worker_root: &Path, tenant: &str, op: &Operator) -> Result<()> {
     let manifest_path = worker_root
         .join("modules")
         .join(tenant)
         .join("manifest.json");
 
-    let raw = fs::read_to_string(&manifest_path)
-        .with_context(|| format!("reading {}", manifest_path.display()))?;
+    let rejected_at = Utc::now().to_rfc3339();
+    let sha256 = approvals::current_module_sha(worker_root, tenant).unwrap_or_default();
+    let msg = format!(
+        "nightcore-rejection:v1\ntenant={tenant}\nsha256={sha256}\noperator={}\nrejected_at={rejected_at}\n",
+        op.name
+    );
+
+    atomic_fs::update_json(&manifest_path, |v| {
+        v["rejected"] = Value::Bool(true);
+        v["rejected_at"] = Value::String(rejected_at.clone());
+        v["rejected_by"] = Value::String(op.name.clone());
+        v["rejected_by_fingerprint"] = Value::String(op.fingerprint.clone());
+        v["rejection_signature"] = Value::String(op.sign(msg.as_bytes()));
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+fn read_suspension(manifest: &Value) -> Option<Suspension> {
+    manifest
+        .get("suspended")
+        .filter(|v| !v.is_null())
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+}
+
+fn manifest_path(worker_root: &Path, tenant: &str) -> std::path::PathBuf {
+    worker_root.join("modules").join(tenant).join("manifest.json")
+}
+
+pub fn suspension(worker_root: &Path, tenant: &str) -> Result<Option<Suspension>> {
+    let p = manifest_path(worker_root, tenant);
+    if !p.exists() {
+        return Ok(None);
+    }
+    Ok(read_suspension(&read_json(&p)?))
+}
+
+/// Refuse an operation on a suspended tenant.
+pub fn ensure_not_suspended(worker_root: &Path, tenant: &str, what: &str) -> Result<()> {
+    if let Some(s) = suspension(worker_root, tenant)? {
+        anyhow::bail!(
+            "tenant {tenant} is suspended (by {} at {}); resume it before {what}",
+            s.suspended_by,
+            s.suspended_at
+        );
+    }
+    Ok(())
+}
+
+/// Refuse to approve a suspended or rejected tenant. Checked before the
+/// worker is asked to approve; `mark_authorized` checks again under its
+/// write.
+pub fn ensure_approvable(worker_root: &Path, tenant: &str) -> Result<()> {
+    ensure_not_suspended(worker_root, tenant, "approving")?;
+
+    let p = manifest_path(worker_root, tenant);
+    if p.exists() && get_bool(&read_json(&p)?, "rejected").unwrap_or(false) {
+        anyhow::bail!("tenant {tenant} was rejected; it cannot be approved");
+    }
+    Ok(())
+}
 
-    let mut v: Value = serde_json::from_str(&raw)
-        .with_context(|| format!("parsing {}", manifest_path.display()))?;
+/// Refuse to run a tenant whose approval window has lapsed, by time or
+/// by execution count.
+pub fn ensure_not_expired(worker_root: &Path, tenant: &str) -> Result<()> {
+    let states = list_tenant_states(worker_root)?;
+    let Some(t) = states.iter().find(|t| t.id == tenant) else {
+        return Ok(());
+    };
 
-    v["approved"] = Value::Bool(true);
-    v["approved_at"] = Value::String(Utc::now().to_rfc3339());
-    v["approved_by"] = Value::String("gui".into());
+    let a = &t.authorization;
+    if a.expired {
+        let why = match (&a.expires_at, a.max_executions) {
+            (_, Some(m)) if a.executions_since_approval >= m => {
+                format!("used {} of {m} allowed executions", a.executions_since_approval)
+            }
+            (Some(at), _) => format!("approval expired at {at}"),
+            _ => "approval expired".to_string(),
+        };
+        anyhow::bail!("tenant {tenant} trust has lapsed ({why}); renew it before running it");
+    }
+    Ok(())
+}
 
-    fs::write(&manifest_path, serde_json::to_string_pretty(&v)?)?;
+/// The same for `run --all`: refused while any tenant's trust has lapsed,
+/// since the worker would run it along with the rest.
+pub fn ensure_none_expired(worker_root: &Path) -> Result<()> {
+    let lapsed: Vec<String> = list_tenant_states(worker_root)?
+        .into_iter()
+        .filter(|t| t.authorization.expired)
+        .map(|t| t.id)
+        .collect();
+
+    if !lapsed.is_empty() {
+        anyhow::bail!(
+            "trust has lapsed for {}; renew or run tenants individually before running all",
+            lapsed.join(", ")
+        );
+    }
     Ok(())
 }
+
+pub fn mark_suspended(
+    worker_root: &Path,
+    tenant: &str,
+    op: &Operator,
+    reason: Option<String>,
+) -> Result<Suspension> {
+    let p = manifest_path(worker_root, tenant);
+
+    let suspended_at = Utc::now().to_rfc3339();
+    let msg = format!(
+        "nightcore-suspension:v1\ntenant={tenant}\noperator={}\nsuspended_at={suspended_at}\n",
+        op.name
+    );
+
+    let s = Suspension {
+        suspended_at,
+        suspended_by: op.name.clone(),
+        suspended_by_fingerprint: Some(op.fingerprint.clone()),
+        reason,
+        signature: Some(op.sign(msg.as_bytes())),
+    };
+
+    atomic_fs::update_json(&p, |v| {
+        if read_suspension(v).is_some() {
+            anyhow::bail!("tenant {tenant} is already suspended");
+        }
+        v["suspended"] = serde_json::to_value(&s)?;
+        Ok(())
+    })?;
+
+    Ok(s)
+}
+
+/// Lift a suspension. Approval and trust terms are left as they were.
+pub fn mark_resumed(worker_root: &Path, tenant: &str) -> Result<Suspension> {
+    let p = manifest_path(worker_root, tenant);
+    let mut lifted = None;
+
+    atomic_fs::update_json(&p, |v| {
+        lifted = read_suspension(v);
+        if lifted.is_none() {
+            anyhow::bail!("tenant {tenant} is not suspended");
+        }
+        if let Some(m) = v.as_object_mut() {
+            m.remove("suspended");
+        }
+        Ok(())
+    })?;
+
+    Ok(lifted.expect("checked above"))
+}
+
+/// Tenants whose manifest carries a suspension, sorted.
+pub fn suspended_tenants(worker_root: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(worker_root.join("modules")) else {
+        return vec![];
+    };
+
+    let mut out: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|t| suspension(worker_root, t).ok().flatten().is_some())
+        .collect();
+    out.sort();
+    out
+}
+
+/// Refuse a run that names no tenant (`run --all`) while any tenant is
+/// suspended. The worker does not know about suspensions, so such a run
+/// would include them.
+pub fn ensure_none_suspended(worker_root: &Path) -> Result<()> {
+    let held = suspended_tenants(worker_root);
+    if !held.is_empty() {
+        anyhow::bail!(
+            "{} suspended ({}); run tenants individually or resume them before running all",
+            if held.len() == 1 { "1 tenant is" } else { "tenants are" },
+            held.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::submission::sha256_hex;
+
+    const MODULE: &[u8] = b"\0asm\x01\0\0\0";
+
+    fn setup(manifest: Value) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let t = dir.path().join("modules/t1");
+        fs::create_dir_all(&t).unwrap();
+        fs::write(t.join("module.wasm"), MODULE).unwrap();
+        fs::write(t.join("manifest.json"), manifest.to_string()).unwrap();
+        dir
+    }
+
+    #[test]
+    fn approvers_only_lists_approvals_of_current_module() {
+        let dir = setup(serde_json::json!({
+            "approvals": [
+                { "approver": "alice", "sha256": sha256_hex(MODULE) },
+                { "approver": "bob", "sha256": "0".repeat(64) },
+            ]
+        }));
+
+        let states = list_tenant_states(dir.path()).unwrap();
+        assert_eq!(states[0].authorization.approvers, vec!["alice"]);
+    }
+
+    #[test]
+    fn expiry_rejects_out_of_range_ttl() {
+        let now = Utc::now();
+        assert_eq!(expiry_after(now, 2).unwrap(), now + TimeDelta::hours(2));
+        assert!(expiry_after(now, u64::MAX).is_err());
+        assert!(expiry_after(now, i64::MAX as u64).is_err());
+        assert!(expiry_after(now, 24 * 365 * 1_000_000).is_err());
+    }
+
+    #[test]
+    fn expired_by_time_or_execution_budget() {
+        let past = (Utc::now() - TimeDelta::hours(1)).to_rfc3339();
+        let future = (Utc::now() + TimeDelta::hours(1)).to_rfc3339();
+
+        assert!(is_expired(Some(&past), None, 0));
+        assert!(!is_expired(Some(&future), None, 0));
+        assert!(is_expired(Some(&future), Some(3), 3));
+        assert!(!is_expired(None, Some(3), 2));
+        assert!(!is_expired(None, None, 100));
+    }
+
+    #[test]
+    fn suspensions_hold_single_and_all_tenant_runs() {
+        let dir = setup(serde_json::json!({}));
+        let root = dir.path();
+        fs::create_dir_all(root.join("modules/t2")).unwrap();
+        fs::write(root.join("modules/t2/manifest.json"), "{}").unwrap();
+        let op = Operator::ephemeral("ops");
+
+        ensure_none_suspended(root).unwrap();
+
+        mark_suspended(root, "t1", &op, Some("incident".into())).unwrap();
+        assert_eq!(suspended_tenants(root), vec!["t1"]);
+        assert!(mark_suspended(root, "t1", &op, None).is_err());
+        assert!(ensure_not_suspended(root, "t1", "running it").is_err());
+        ensure_not_suspended(root, "t2", "running it").unwrap();
+        assert!(ensure_none_suspended(root).is_err());
+
+        mark_resumed(root, "t1").unwrap();
+        assert!(suspended_tenants(root).is_empty());
+        assert!(mark_resumed(root, "t1").is_err());
+        ensure_none_suspended(root).unwrap();
+
+        // A suspension written by hand is honoured too.
+        fs::write(root.join("modules/t2/manifest.json"), r#"{"suspended":{"suspended_at":"x","suspended_by":"y"}}"#)
+            .unwrap();
+        assert!(ensure_none_suspended(root).is_err());
+    }
+
+    #[test]
+    fn executions_count_by_instant_across_offsets() {
+        let dir = setup(serde_json::json!({
+            "approved": true,
+            "approved_at": "2026-01-01T12:00:00+02:00",
+        }));
+        let root = dir.path();
+        fs::create_dir_all(root.join("logs")).unwrap();
+        let lines: String = [
+            ("2026-01-01T09:00:00Z", 10),
+            ("2026-01-01T11:00:00Z", 20),
+            ("2026-01-01T12:30:00+03:00", 30),
+        ]
+        .iter()
+        .map(|(ts, score)| {
+            serde_json::json!({ "tenant": "t1", "timestamp": ts, "decision": "allow", "threat_score": score })
+                .to_string()
+                + "\n"
+        })
+        .collect();
+        fs::write(root.join("logs/guardian_decisions.jsonl"), lines).unwrap();
+
+        let t = &list_tenant_states(root).unwrap()[0];
+        // 09:00Z is before the approval at 10:00Z; 11:00Z is after it.
+        assert_eq!(t.authorization.executions_since_approval, 1);
+        assert_eq!(t.execution.last_execution_time.as_deref(), Some("2026-01-01T11:00:00Z"));
+        assert_eq!(t.observation.current_threat_score, Some(20));
+    }
+
+    #[test]
+    fn lapsed_trust_refuses_runs() {
+        let past = (Utc::now() - TimeDelta::hours(1)).to_rfc3339();
+        let dir = setup(serde_json::json!({
+            "approved": true,
+            "approved_at": "2026-01-01T00:00:00Z",
+            "expires_at": past,
+        }));
+        let root = dir.path();
+        assert!(ensure_not_expired(root, "t1").is_err());
+        assert!(ensure_none_expired(root).is_err());
+
+        // Still in its window, but out of executions.
+        fs::write(
+            root.join("modules/t1/manifest.json"),
+            serde_json::json!({
+                "approved": true,
+                "approved_at": "2026-01-01T00:00:00Z",
+                "max_executions": 1,
+            })
+            .to_string(),
+        )
+        .unwrap();
+        ensure_not_expired(root, "t1").unwrap();
+
+        fs::create_dir_all(root.join("logs")).unwrap();
+        fs::write(
+            root.join("logs/guardian_decisions.jsonl"),
+            r#"{"tenant":"t1","timestamp":"2026-01-02T00:00:00Z","decision":"allow"}"#.to_string() + "\n",
+        )
+        .unwrap();
+        assert!(ensure_not_expired(root, "t1").is_err());
+
+        // Renewal restarts the window.
+        renew_trust(root, "t1", &Operator::ephemeral("ops"), None).unwrap();
+        ensure_not_expired(root, "t1").unwrap();
+        ensure_none_expired(root).unwrap();
+    }
+
+    #[test]
+    fn renewal_keeps_earlier_approvers_and_follows_approval_rules() {
+        let dir = setup(serde_json::json!({ "approved": true, "approved_by": "alice" }));
+        let root = dir.path();
+
+        let bob = Operator::ephemeral("bob");
+        renew_trust(root, "t1", &bob, None).unwrap();
+        renew_trust(root, "t1", &bob, None).unwrap();
+        let v = read_json(&manifest_path(root, "t1")).unwrap();
+        assert_eq!(v["approved_by"], "bob");
+        assert_eq!(v["approved_by_fingerprint"], bob.fingerprint.as_str());
+        let earlier: Vec<&str> = v["renewals"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["approved_by"].as_str().unwrap())
+            .collect();
+        assert_eq!(earlier, ["alice", "bob"]);
+
+        mark_rejected(root, "t1", &Operator::ephemeral("carol")).unwrap();
+        assert!(renew_trust(root, "t1", &Operator::ephemeral("bob"), None).is_err());
+        assert!(ensure_approvable(root, "t1").is_err());
+        assert!(mark_authorized(root, "t1", ApprovedBy::Operator(&Operator::ephemeral("bob"))).is_err());
+
+        let dir = setup(serde_json::json!({ "approved": true, "approved_by": "quorum:alice,bob" }));
+        let root = dir.path();
+        fs::create_dir_all(root.join("guardian/policies")).unwrap();
+        fs::write(
+            root.join("guardian/policies/_default.json"),
+            serde_json::json!({ "approval": { "required": 2, "maintainers": ["alice", "bob"] } }).to_string(),
+        )
+        .unwrap();
+        assert!(renew_trust(root, "t1", &Operator::ephemeral("mallory"), None).is_err());
+        let v = read_json(&manifest_path(root, "t1")).unwrap();
+        assert_eq!(v["approved_by"], "quorum:alice,bob");
+    }
+}
@@ -0,0 +1,248 @@
+use serde::{Serialize, Deserialize};
+
+use crate::GuardianDecisionLite;
+
+/// One contribution to a threat score.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RiskFactor {
+    pub factor: String,
+    pub weight: u8,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RiskPrediction {
+    pub score: u8,
+    pub label: String,
+    pub factors: Vec<RiskFactor>,
+}
+
+/// Signals available before a module has ever run.
+#[derive(Debug, Clone)]
+pub struct StaticSignals {
+    pub first_seen: bool,
+    pub wasi_fs_access: bool,
+    pub wasi_net_access: bool,
+    pub wasm_size_bytes: u64,
+    pub policy_exists: bool,
+    pub trusted_signer: bool,
+}
+
+const LARGE_MODULE_BYTES: u64 = 5 * 1024 * 1024;
+const LARGE_MEMORY_MB: u64 = 256;
+const LONG_RUNTIME_MS: u64 = 10_000;
+
+/// Same bands the Guardian view colours by.
+pub fn threat_label(score: u8) -> &'static str {
+    match score {
+        85.. => "quarantine",
+        65.. => "high",
+        45.. => "elevated",
+        20.. => "safe",
+        _ => "low",
+    }
+}
+
+/// A factor and whether it applied.
+type Check = (bool, RiskFactor);
+
+fn check(on: bool, factor: &str, weight: u8, detail: &str) -> Check {
+    (
+        on,
+        RiskFactor {
+            factor: factor.into(),
+            weight,
+            detail: detail.into(),
+        },
+    )
+}
+
+fn static_checks(s: &StaticSignals) -> Vec<Check> {
+    vec![
+        check(s.first_seen, "first_seen", 20, "module hash never seen before"),
+        check(s.wasi_fs_access, "wasi_fs_access", 15, "imports WASI filesystem calls"),
+        check(s.wasi_net_access, "wasi_net_access", 25, "imports WASI socket calls"),
+        check(
+            s.wasm_size_bytes > LARGE_MODULE_BYTES,
+            "wasm_size_bytes",
+            10,
+            "module larger than 5 MiB",
+        ),
+        check(!s.policy_exists, "policy_exists", 10, "no Guardian policy applies"),
+        check(!s.trusted_signer, "trusted_signer", 20, "no verified signer"),
+    ]
+}
+
+pub fn score_factors(factors: Vec<RiskFactor>) -> RiskPrediction {
+    let total: u32 = factors.iter().map(|f| f.weight as u32).sum();
+    let score = total.min(100) as u8;
+
+    RiskPrediction {
+        score,
+        label: threat_label(score).into(),
+        factors,
+    }
+}
+
+/// Predicted Guardian score for a module that has not executed yet.
+/// The worker remains authoritative; this is a triage hint only.
+pub fn predict_static(s: &StaticSignals) -> RiskPrediction {
+    score_factors(static_checks(s).into_iter().filter(|(on, _)| *on).map(|(_, f)| f).collect())
+}
+
+/// A recorded decision broken down into weighted factors.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScoreExplanation {
+    pub tenant: String,
+    pub timestamp: String,
+    pub decision: String,
+    pub reason: String,
+
+    pub recorded_score: u8,
+    pub recorded_label: String,
+
+    /// Sum of the applied weights, capped at 100.
+    pub explained_score: u8,
+    /// Recorded minus explained: scoring the worker did that the
+    /// recorded fields do not account for (negative if it scored lower).
+    pub unexplained: i16,
+
+    /// Factors that applied, heaviest first.
+    pub factors: Vec<RiskFactor>,
+    /// Factors checked that did not apply, with the weight each would add.
+    pub not_applied: Vec<RiskFactor>,
+}
+
+/// Explain a worker decision from the signals it recorded. The weights
+/// are the console's; the worker's score stays authoritative, and any
+/// gap between the two is reported rather than hidden.
+pub fn explain(d: &GuardianDecisionLite) -> ScoreExplanation {
+    let mut checks = static_checks(&StaticSignals {
+        first_seen: d.first_seen,
+        wasi_fs_access: d.wasi_fs_access,
+        wasi_net_access: d.wasi_net_access,
+        wasm_size_bytes: d.wasm_size_bytes,
+        policy_exists: d.policy_exists,
+        trusted_signer: d.trusted_signer,
+    });
+
+    checks.extend([
+        // A first sighting is already counted above.
+        check(
+            !d.known_sha && !d.first_seen,
+            "known_sha",
+            15,
+            "hash seen before but not a known module version",
+        ),
+        check(
+            d.memory_request_mb > LARGE_MEMORY_MB,
+            "memory_request_mb",
+            10,
+            "requests more than 256 MiB of memory",
+        ),
+        check(
+            d.runtime_request_ms > LONG_RUNTIME_MS,
+            "runtime_request_ms",
+            10,
+            "requests more than 10 s of runtime",
+        ),
+        check(!d.backend_allowed, "backend_allowed", 20, "backend not allowed by policy"),
+    ]);
+
+    let (applied, skipped): (Vec<Check>, Vec<Check>) = checks.into_iter().partition(|(on, _)| *on);
+
+    let mut factors: Vec<RiskFactor> = applied.into_iter().map(|(_, f)| f).collect();
+    factors.sort_by_key(|f| std::cmp::Reverse(f.weight));
+
+    let explained = score_factors(factors);
+
+    ScoreExplanation {
+        tenant: d.tenant.clone(),
+        timestamp: d.timestamp.clone(),
+        decision: d.decision.clone(),
+        reason: d.reason.clone(),
+        recorded_score: d.threat_score,
+        recorded_label: threat_label(d.threat_score).into(),
+        explained_score: explained.score,
+        unexplained: d.threat_score as i16 - explained.score as i16,
+        factors: explained.factors,
+        not_applied: skipped.into_iter().map(|(_, f)| f).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::decisions;
+
+    fn decision(overrides: serde_json::Value) -> GuardianDecisionLite {
+        serde_json::from_value(decisions::decision_json(overrides)).unwrap()
+    }
+
+    fn names(fs: &[RiskFactor]) -> Vec<&str> {
+        fs.iter().map(|f| f.factor.as_str()).collect()
+    }
+
+    #[test]
+    fn labels_follow_score_bands() {
+        let cases = [(0, "low"), (19, "low"), (20, "safe"), (45, "elevated"), (65, "high"), (84, "high"), (85, "quarantine"), (255, "quarantine")];
+        for (score, label) in cases {
+            assert_eq!(threat_label(score), label, "{score}");
+        }
+    }
+
+    #[test]
+    fn clean_decision_explains_to_zero() {
+        let e = explain(&decision(json!({ "threat_score": 12 })));
+        assert_eq!(e.explained_score, 0);
+        assert_eq!(e.unexplained, 12);
+        assert!(e.factors.is_empty());
+        assert_eq!(e.not_applied.len(), 10);
+    }
+
+    #[test]
+    fn factors_sorted_by_weight_and_capped() {
+        let e = explain(&decision(json!({
+            "threat_score": 90, "first_seen": true, "known_sha": false, "wasi_net_access": true,
+            "wasi_fs_access": true, "trusted_signer": false, "backend_allowed": false, "memory_request_mb": 512,
+        })));
+
+        assert_eq!(
+            names(&e.factors),
+            vec!["wasi_net_access", "first_seen", "trusted_signer", "backend_allowed", "wasi_fs_access", "memory_request_mb"]
+        );
+        // 110 in weights, capped.
+        assert_eq!(e.explained_score, 100);
+        assert_eq!(e.unexplained, -10);
+        assert_eq!(e.recorded_label, "quarantine");
+        // A first sighting is not also counted as an unknown version.
+        assert!(names(&e.not_applied).contains(&"known_sha"));
+    }
+
+    #[test]
+    fn unknown_version_and_large_requests() {
+        let e = explain(&decision(json!({
+            "threat_score": 35, "known_sha": false, "runtime_request_ms": 20_000,
+            "wasm_size_bytes": 6 * 1024 * 1024, "policy_exists": false,
+        })));
+        assert_eq!(names(&e.factors), vec!["known_sha", "wasm_size_bytes", "policy_exists", "runtime_request_ms"]);
+        assert_eq!(e.explained_score, 45);
+        assert_eq!(e.unexplained, -10);
+    }
+
+    #[test]
+    fn static_prediction_uses_static_signals_only() {
+        let p = predict_static(&StaticSignals {
+            first_seen: true,
+            wasi_fs_access: false,
+            wasi_net_access: true,
+            wasm_size_bytes: 10,
+            policy_exists: true,
+            trusted_signer: true,
+        });
+        assert_eq!(p.score, 45);
+        assert_eq!(p.label, "elevated");
+        assert_eq!(names(&p.factors), vec!["first_seen", "wasi_net_access"]);
+    }
+}
@@ -0,0 +1,352 @@
+use serde::{Serialize, Deserialize};
+use std::{fs, path::Path};
+use serde_json::Value;
+use chrono::{DateTime, Utc};
+
+use crate::audit::AuditEntry;
+use crate::decisions;
+use crate::quarantine;
+use crate::retention;
+use crate::GuardianDecisionLite;
+
+/// Largest page a caller can ask for.
+pub const MAX_PAGE: usize = 500;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Import,
+    Approval,
+    Decision,
+    Quarantine,
+    Audit,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimelineEvent {
+    pub timestamp: String,
+    #[serde(default)]
+    pub tenant: Option<String>,
+    pub kind: EventKind,
+    /// One-line summary for the list view.
+    pub event: String,
+    /// ok | warn | error
+    pub status: String,
+    #[serde(default)]
+    pub detail: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TimelineQuery {
+    #[serde(default)]
+    pub tenant: Option<String>,
+    /// Empty means every kind.
+    #[serde(default)]
+    pub kinds: Vec<EventKind>,
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimelinePage {
+    /// Matching events before paging.
+    pub total: usize,
+    pub offset: usize,
+    pub events: Vec<TimelineEvent>,
+}
+
+fn decision_event(d: GuardianDecisionLite, quarantine_rules: Vec<String>) -> TimelineEvent {
+    let is_quarantine = !quarantine_rules.is_empty();
+
+    let status = match (d.decision.as_str(), d.threat_score) {
+        ("deny", _) => "error",
+        (_, s) if s >= 65 => "warn",
+        _ => "ok",
+    };
+
+    TimelineEvent {
+        kind: if is_quarantine { EventKind::Quarantine } else { EventKind::Decision },
+        event: format!(
+            "{} on {} (score {}): {}",
+            d.decision, d.backend, d.threat_score, d.reason
+        ),
+        status: if is_quarantine { "error".into() } else { status.into() },
+        detail: serde_json::json!({
+            "sha256": d.sha256,
+            "backend": d.backend,
+            "decision": d.decision,
+            "threat_score": d.threat_score,
+            "quarantine_rules": quarantine_rules,
+        }),
+        timestamp: d.timestamp,
+        tenant: Some(d.tenant),
+    }
+}
+
+fn manifest_events(worker_root: &Path) -> Vec<TimelineEvent> {
+    let Ok(entries) = fs::read_dir(worker_root.join("modules")) else {
+        return vec![];
+    };
+
+    let mut out = vec![];
+    for e in entries.flatten() {
+        let tenant = e.file_name().to_string_lossy().to_string();
+        let Some(m) = fs::read_to_string(e.path().join("manifest.json"))
+            .ok()
+            .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+        else {
+            continue;
+        };
+        let s = |p: &str| m.pointer(p).and_then(|v| v.as_str()).map(|v| v.to_string());
+
+        if let Some(ts) = s("/ingestion/timestamp") {
+            let channel = s("/ingestion/channel").unwrap_or_else(|| "unknown".into());
+            let source = s("/ingestion/source").unwrap_or_else(|| "unknown".into());
+            out.push(TimelineEvent {
+                timestamp: ts,
+                tenant: Some(tenant.clone()),
+                kind: EventKind::Import,
+                event: format!("imported via {channel} ({source})"),
+                status: "ok".into(),
+                detail: serde_json::json!({ "channel": channel, "source": source }),
+            });
+        }
+
+        if let Some(ts) = s("/approved_at") {
+            let by = s("/approved_by").unwrap_or_else(|| "unknown".into());
+            let still = m.get("approved").and_then(|b| b.as_bool()).unwrap_or(false);
+            out.push(TimelineEvent {
+                timestamp: ts,
+                tenant: Some(tenant.clone()),
+                kind: EventKind::Approval,
+                event: format!("approved by {by}"),
+                // Approval since withdrawn (new revision, rejection).
+                status: if still { "ok".into() } else { "warn".into() },
+                detail: serde_json::json!({
+                    "approved_by": by,
+                    "approved_sha256": s("/approved_sha256"),
+                }),
+            });
+        }
+    }
+
+    out
+}
+
+fn audit_event(a: AuditEntry) -> TimelineEvent {
+    let kind = if a.action.starts_with("quarantine_") {
+        EventKind::Quarantine
+    } else {
+        EventKind::Audit
+    };
+
+    let failed = a.detail.get("result").and_then(|r| r.as_str()) == Some("disabled");
+
+    TimelineEvent {
+        kind,
+        event: format!("{} by {}", a.action.replace('_', " "), a.actor),
+        status: if failed { "warn".into() } else { "ok".into() },
+        detail: serde_json::json!({
+            "action": a.action,
+            "actor": a.actor,
+            "actor_fingerprint": a.actor_fingerprint,
+            "detail": a.detail,
+        }),
+        timestamp: a.timestamp,
+        tenant: a.tenant,
+    }
+}
+
+/// Every event from manifests, decisions and the console audit log,
+/// newest first.
+pub fn collect(worker_root: &Path) -> Vec<TimelineEvent> {
+    let mut events = manifest_events(worker_root);
+
+    // A malformed rule set falls back to the defaults rather than hiding
+    // every decision.
+    let rules = quarantine::load_rules(worker_root).unwrap_or_default();
+    let ds = decisions::load_all(worker_root);
+    let hits = rules.evaluate(&ds);
+    events.extend(ds.into_iter().zip(hits).map(|(d, h)| decision_event(d, h)));
+
+    events.extend(
+        retention::read_lines(worker_root, retention::CONSOLE_AUDIT)
+            .iter()
+            .filter_map(|l| serde_json::from_str::<AuditEntry>(l).ok())
+            .map(audit_event),
+    );
+
+    // Sort on the instant, not the string: sources differ in offsets and
+    // fractional digits.
+    let key = |e: &TimelineEvent| DateTime::parse_from_rfc3339(&e.timestamp).ok().map(|t| t.with_timezone(&Utc));
+    events.sort_by(|a, b| key(b).cmp(&key(a)).then_with(|| b.timestamp.cmp(&a.timestamp)));
+
+    events
+}
+
+pub fn query(worker_root: &Path, q: &TimelineQuery) -> TimelinePage {
+    let matching: Vec<TimelineEvent> = collect(worker_root)
+        .into_iter()
+        .filter(|e| q.tenant.is_none() || e.tenant == q.tenant)
+        .filter(|e| q.kinds.is_empty() || q.kinds.contains(&e.kind))
+        .collect();
+
+    let limit = q.limit.unwrap_or(100).min(MAX_PAGE);
+
+    TimelinePage {
+        total: matching.len(),
+        offset: q.offset,
+        events: matching.into_iter().skip(q.offset).take(limit).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atomic_fs;
+
+    fn decision(ts: &str, tenant: &str, verdict: &str, score: u8) -> String {
+        decisions::decision_json(serde_json::json!({
+            "timestamp": ts, "tenant": tenant, "decision": verdict, "reason": "test", "threat_score": score,
+        }))
+        .to_string()
+    }
+
+    fn audit(ts: &str, action: &str, tenant: Option<&str>) -> String {
+        serde_json::json!({ "timestamp": ts, "action": action, "tenant": tenant, "actor": "ops" }).to_string()
+    }
+
+    /// Two tenants with one import, approval, decision and audit entry
+    /// each, plus a quarantining decision and a console-wide entry.
+    fn setup() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        for (t, imported, approved) in [
+            ("t1", "2026-03-01T09:00:00Z", "2026-03-01T09:30:00Z"),
+            ("t2", "2026-03-01T08:00:00+00:00", "2026-03-01T08:30:00.5Z"),
+        ] {
+            let d = root.join("modules").join(t);
+            fs::create_dir_all(&d).unwrap();
+            fs::write(
+                d.join("manifest.json"),
+                serde_json::json!({
+                    "ingestion": { "channel": "manual", "source": "gui", "timestamp": imported },
+                    "approved": t == "t1", "approved_at": approved, "approved_by": "alice",
+                })
+                .to_string(),
+            )
+            .unwrap();
+        }
+
+        let decisions = retention::active_path(root, retention::GUARDIAN_DECISIONS);
+        for line in [
+            decision("2026-03-01T10:00:00Z", "t1", "allow", 10),
+            decision("2026-03-01T11:00:00Z", "t2", "deny", 40),
+            decision("2026-03-01T12:00:00Z", "t1", "allow", 90),
+        ] {
+            atomic_fs::append_line(&decisions, &line).unwrap();
+        }
+
+        let log = retention::active_path(root, retention::CONSOLE_AUDIT);
+        for line in [
+            audit("2026-03-01T10:30:00Z", "tenant_stopped", Some("t2")),
+            // Same instant as the first decision, written in another offset.
+            audit("2026-03-01T12:00:00+02:00", "quarantine_restored", Some("t1")),
+            audit("2026-03-01T13:00:00Z", "kill_switch", None),
+            "not json".into(),
+        ] {
+            atomic_fs::append_line(&log, &line).unwrap();
+        }
+
+        dir
+    }
+
+    #[test]
+    fn collect_merges_every_source_newest_first() {
+        let dir = setup();
+        let events = collect(dir.path());
+
+        let seen: Vec<_> = events.iter().map(|e| (e.kind, e.tenant.as_deref(), e.status.as_str())).collect();
+        assert_eq!(
+            seen,
+            vec![
+                (EventKind::Audit, None, "ok"),
+                (EventKind::Quarantine, Some("t1"), "error"),
+                (EventKind::Decision, Some("t2"), "error"),
+                (EventKind::Audit, Some("t2"), "ok"),
+                (EventKind::Quarantine, Some("t1"), "ok"),
+                (EventKind::Decision, Some("t1"), "ok"),
+                (EventKind::Approval, Some("t1"), "ok"),
+                (EventKind::Import, Some("t1"), "ok"),
+                (EventKind::Approval, Some("t2"), "warn"),
+                (EventKind::Import, Some("t2"), "ok"),
+            ]
+        );
+
+        // Sorted on the instant; 12:00+02:00 ties with 10:00Z.
+        assert_eq!(events[4].timestamp, "2026-03-01T12:00:00+02:00");
+        assert_eq!(events[5].timestamp, "2026-03-01T10:00:00Z");
+
+        assert_eq!(events[1].detail["quarantine_rules"][0], "score 90 >= 85");
+        assert_eq!(events[6].detail["approved_by"], "alice");
+        assert_eq!(events[0].event, "kill switch by ops");
+    }
+
+    #[test]
+    fn query_filters_by_tenant_and_kind() {
+        let dir = setup();
+        let root = dir.path();
+
+        let t1 = query(root, &TimelineQuery { tenant: Some("t1".into()), ..Default::default() });
+        assert_eq!(t1.total, 5);
+        assert!(t1.events.iter().all(|e| e.tenant.as_deref() == Some("t1")));
+
+        let kinds = vec![EventKind::Decision, EventKind::Quarantine];
+        let risky = query(root, &TimelineQuery { kinds, ..Default::default() });
+        assert_eq!(risky.total, 4);
+        assert!(risky.events.iter().all(|e| matches!(e.kind, EventKind::Decision | EventKind::Quarantine)));
+
+        let both = query(root, &TimelineQuery {
+            tenant: Some("t2".into()),
+            kinds: vec![EventKind::Import, EventKind::Audit],
+            ..Default::default()
+        });
+        let seen: Vec<_> = both.events.iter().map(|e| e.kind).collect();
+        assert_eq!(seen, vec![EventKind::Audit, EventKind::Import]);
+
+        let none = query(root, &TimelineQuery { tenant: Some("t3".into()), ..Default::default() });
+        assert_eq!((none.total, none.events.len()), (0, 0));
+    }
+
+    #[test]
+    fn query_pages_through_the_matches() {
+        let dir = setup();
+        let root = dir.path();
+        let all = collect(root);
+
+        let first = query(root, &TimelineQuery { limit: Some(4), ..Default::default() });
+        let second = query(root, &TimelineQuery { offset: 4, limit: Some(4), ..Default::default() });
+        let last = query(root, &TimelineQuery { offset: 8, limit: Some(4), ..Default::default() });
+        assert_eq!((first.total, second.total, last.total), (10, 10, 10));
+        assert_eq!((first.events.len(), second.events.len(), last.events.len()), (4, 4, 2));
+        assert_eq!(second.offset, 4);
+
+        let paged: Vec<_> = [first, second, last]
+            .into_iter()
+            .flat_map(|p| p.events)
+            .map(|e| (e.timestamp, e.event))
+            .collect();
+        let whole: Vec<_> = all.into_iter().map(|e| (e.timestamp, e.event)).collect();
+        assert_eq!(paged, whole);
+
+        let past = query(root, &TimelineQuery { offset: 50, ..Default::default() });
+        assert_eq!((past.total, past.events.len()), (10, 0));
+
+        // The page size is capped.
+        let big = query(root, &TimelineQuery { limit: Some(MAX_PAGE + 1), ..Default::default() });
+        assert_eq!(big.events.len(), 10);
+    }
+}
@@ -0,0 +1,227 @@
+use std::{fs, path::Path};
+use anyhow::{bail, Context, Result};
+
+use crate::submission::sha256_hex;
+
+/// Static facts about a module, read without instantiating it.
+#[derive(Debug, Clone)]
+pub struct ModuleFacts {
+    pub size_bytes: u64,
+    pub sha256: String,
+    pub imports: Vec<String>,
+}
+
+pub fn inspect_module(path: &Path) -> Result<ModuleFacts> {
+    let bytes = fs::read(path)
+        .with_context(|| format!("reading {}", path.display()))?;
+
+    Ok(ModuleFacts {
+        size_bytes: bytes.len() as u64,
+        sha256: sha256_hex(&bytes),
+        imports: parse_imports(&bytes)?,
+    })
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8> {
+        let Some(b) = self.buf.get(self.pos) else {
+            bail!("unexpected end of module");
+        };
+        self.pos += 1;
+        Ok(*b)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let mut out: u32 = 0;
+        for shift in (0..35).step_by(7) {
+            let b = self.byte()?;
+            out |= ((b & 0x7f) as u32) << shift;
+            if b & 0x80 == 0 {
+                return Ok(out);
+            }
+        }
+        bail!("malformed LEB128 integer")
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(n).filter(|e| *e <= self.buf.len());
+        let Some(end) = end else {
+            bail!("unexpected end of module");
+        };
+        let out = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn name(&mut self) -> Result<String> {
+        let n = self.u32()? as usize;
+        Ok(String::from_utf8_lossy(self.bytes(n)?).to_string())
+    }
+
+    fn limits(&mut self) -> Result<()> {
+        let flags = self.byte()?;
+        self.u32()?;
+        if flags & 0x01 != 0 {
+            self.u32()?;
+        }
+        Ok(())
+    }
+}
+
+/// Imports as `module::name`, in declaration order.
+///
+/// Only the import section is decoded; everything else is skipped by
+/// section length, so this stays cheap on large modules.
+pub fn parse_imports(bytes: &[u8]) -> Result<Vec<String>> {
+    if bytes.len() < 8 || &bytes[..4] != b"\0asm" {
+        bail!("not a WebAssembly module");
+    }
+
+    let mut r = Reader { buf: bytes, pos: 8 };
+    let mut out = vec![];
+
+    while r.pos < r.buf.len() {
+        let id = r.byte()?;
+        let len = r.u32()? as usize;
+        let body = r.bytes(len)?;
+
+        if id != 2 {
+            continue;
+        }
+
+        let mut s = Reader { buf: body, pos: 0 };
+        let count = s.u32()?;
+
+        for _ in 0..count {
+            let module = s.name()?;
+            let field = s.name()?;
+
+            match s.byte()? {
+                0x00 => {
+                    s.u32()?;
+                }
+                0x01 => {
+                    s.byte()?;
+                    s.limits()?;
+                }
+                0x02 => s.limits()?,
+                0x03 => {
+                    s.byte()?;
+                    s.byte()?;
+                }
+                0x04 => {
+                    s.byte()?;
+                    s.u32()?;
+                }
+                k => bail!("unknown import kind {k:#x}"),
+            }
+
+            out.push(format!("{module}::{field}"));
+        }
+
+        break;
+    }
+
+    Ok(out)
+}
+
+fn wasi_fn(import: &str) -> Option<&str> {
+    let (module, field) = import.split_once("::")?;
+    module.starts_with("wasi").then_some(field)
+}
+
+pub fn wasi_fs_access(imports: &[String]) -> bool {
+    imports
+        .iter()
+        .filter_map(|i| wasi_fn(i))
+        .any(|f| f.starts_with("path_") || f == "fd_readdir")
+}
+
+pub fn wasi_net_access(imports: &[String]) -> bool {
+    imports
+        .iter()
+        .filter_map(|i| wasi_fn(i))
+        .any(|f| f.starts_with("sock_"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(out: &mut Vec<u8>, s: &str) {
+        out.push(s.len() as u8);
+        out.extend_from_slice(s.as_bytes());
+    }
+
+    fn section(out: &mut Vec<u8>, id: u8, body: &[u8]) {
+        out.push(id);
+        out.push(body.len() as u8);
+        out.extend_from_slice(body);
+    }
+
+    fn module() -> Vec<u8> {
+        let mut m = b"\0asm\x01\0\0\0".to_vec();
+
+        // type section: one `() -> ()`
+        section(&mut m, 1, &[0x01, 0x60, 0x00, 0x00]);
+
+        let mut imports = vec![3];
+        name(&mut imports, "wasi_snapshot_preview1");
+        name(&mut imports, "path_open");
+        imports.extend_from_slice(&[0x00, 0x00]);
+        name(&mut imports, "env");
+        name(&mut imports, "memory");
+        imports.extend_from_slice(&[0x02, 0x01, 0x01, 0x02]);
+        name(&mut imports, "wasi_snapshot_preview1");
+        name(&mut imports, "sock_accept");
+        imports.extend_from_slice(&[0x00, 0x00]);
+        section(&mut m, 2, &imports);
+
+        // custom section after the imports is never decoded
+        section(&mut m, 0, &[0x01, b'x', 0xff, 0xff]);
+        m
+    }
+
+    #[test]
+    fn parses_imports_in_order() {
+        assert_eq!(
+            parse_imports(&module()).unwrap(),
+            vec![
+                "wasi_snapshot_preview1::path_open",
+                "env::memory",
+                "wasi_snapshot_preview1::sock_accept",
+            ]
+        );
+    }
+
+    #[test]
+    fn module_without_imports() {
+        let mut m = b"\0asm\x01\0\0\0".to_vec();
+        section(&mut m, 1, &[0x01, 0x60, 0x00, 0x00]);
+        assert!(parse_imports(&m).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_non_wasm_and_truncated_input() {
+        assert!(parse_imports(b"not wasm at all").is_err());
+
+        let m = module();
+        assert!(parse_imports(&m[..20]).is_err());
+    }
+
+    #[test]
+    fn classifies_wasi_capabilities() {
+        let imports = parse_imports(&module()).unwrap();
+        assert!(wasi_fs_access(&imports));
+        assert!(wasi_net_access(&imports));
+
+        let plain = vec!["env::memory".to_string(), "env::path_open".to_string()];
+        assert!(!wasi_fs_access(&plain));
+        assert!(!wasi_net_access(&plain));
+    }
+}
@@ -5,6 +5,7 @@ import Dashboard from "./pages/Dashboard";
 import Tenants from "./pages/Tenants";
 import ProofLogs from "./pages/ProofLogs";
 import Timeline from "./pages/Timeline";
+import Schedules from "./pages/Schedules";
 import Backends from "./pages/Backends";
 import Settings from "./pages/Settings";
 import About from "./pages/About";
@@ -25,6 +26,7 @@ export default function App() {
           <Route path="/tenants" element={<Tenants />} />
           <Route path="/proof-logs" element={<ProofLogs />} />
           <Route path="/timeline" element={<Timeline />} />
+          <Route path="/schedules" element={<Schedules />} />
           <Route path="/backends" element={<Backends />} />
           <Route path="/policies" element={<Policies />} />
           <Route path="/quarantine" element={<Quarantine />} />
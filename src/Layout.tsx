@@ -57,6 +57,15 @@ const IconTimeline = () => (
   </svg>
 );
 
+const IconSchedules = () => (
+  <svg className="nc-icon-svg" width="18" height="18" viewBox="0 0 24 24" fill="none" strokeWidth="2">
+    <rect x="3" y="5" width="18" height="16" rx="2" />
+    <line x1="3" y1="10" x2="21" y2="10" />
+    <line x1="8" y1="3" x2="8" y2="7" />
+    <line x1="16" y1="3" x2="16" y2="7" />
+  </svg>
+);
+
 const IconAnomaly = () => (
   <svg className="nc-icon-svg" width="18" height="18" viewBox="0 0 24 24" fill="none" stroke="#FF4D67" strokeWidth="2">
     <path d="M10.29 3.86L1.82 18a2 2 0 0 0 1.71 3h17a2 2 0 0 0 1.71-3L13.71 3.86a2 2 0 0 0-3.42 0z" />
@@ -204,6 +213,11 @@ export default function Layout() {
             Timeline
           </Link>
 
+          <Link className={isActive("/schedules")} to="/schedules">
+            <span className="nc-icon"><IconSchedules /></span>
+            Schedules
+          </Link>
+
           <div className="nc-nav-section">Security</div>
 
           <Link className={isActive("/guardian")} to="/guardian">
@@ -252,8 +266,17 @@ export default function Layout() {
                 className="nc-nav-btn"
                 onClick={async () => {
                   try {
-                    await invoke("pro_kill_all_running");
-                    alert("🛑 All sandbox runtimes requested to terminate (wasmtime + firecracker).");
+                    const reports = await invoke<
+                      { pid: number; tenant: string | null; outcome: string }[]
+                    >("pro_kill_all_running");
+                    if (reports.length === 0) {
+                      alert("🛑 No tracked sandbox runs were active.");
+                    } else {
+                      const lines = reports.map(
+                        (r) => `PID ${r.pid}${r.tenant ? ` (${r.tenant})` : ""}: ${r.outcome.replace(/_/g, " ")}`
+                      );
+                      alert("🛑 Kill Switch\n\n" + lines.join("\n"));
+                    }
                   } catch (e: any) {
                     alert("❌ Kill Switch failed: " + e.toString());
                   }
@@ -25,7 +25,7 @@ export default function ApprovePanel({
     setMessage("Approving…");
 
     try {
-      await invoke("approve_agent_tenant", { tenant: tenant.name });
+      await invoke("approve_agent_tenant", { tenant: tenant.id });
       setMessage("Approved and signed");
       onApproved();
       onClose();
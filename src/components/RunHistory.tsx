@@ -0,0 +1,134 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import "./runHistory.css";
+
+interface CapturedOutput {
+  text: string;
+  bytes: number;
+  sha256: string;
+  truncated: boolean;
+}
+
+interface LinkedDecision {
+  timestamp: string;
+  tenant: string;
+  decision: string;
+  reason: string;
+  threat_score: number;
+}
+
+interface RunRecord {
+  run_id: string;
+  tenant: string | null;
+  backend: string | null;
+  args: string[];
+  trigger: string;
+  started_at: string;
+  finished_at: string;
+  duration_ms: number;
+  exit_code: number | null;
+  success: boolean;
+  error: string | null;
+  stdout: CapturedOutput;
+  stderr: CapturedOutput;
+  decisions: LinkedDecision[];
+}
+
+interface RunPage {
+  total: number;
+  offset: number;
+  runs: RunRecord[];
+}
+
+const PAGE_SIZE = 20;
+
+function Stream({ label, out }: { label: string; out: CapturedOutput }) {
+  if (out.bytes === 0) return null;
+
+  return (
+    <div className="run-stream">
+      <div className="run-stream-head">
+        {label} · {out.bytes} bytes{out.truncated ? " (truncated)" : ""} ·
+        sha256 <code>{out.sha256.slice(0, 16)}…</code>
+      </div>
+      <pre>{out.text}</pre>
+    </div>
+  );
+}
+
+/** Recorded worker runs of one tenant, newest first. */
+export default function RunHistory({ tenant }: { tenant: string }) {
+  const [page, setPage] = useState<RunPage | null>(null);
+  const [offset, setOffset] = useState(0);
+  const [open, setOpen] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<RunPage>("list_runs", { tenant, offset, limit: PAGE_SIZE })
+      .then(setPage)
+      .catch((e) => console.error("Failed to load runs:", e));
+  }, [tenant, offset]);
+
+  if (!page) return null;
+  if (page.total === 0) return <p className="run-empty">No recorded runs</p>;
+
+  return (
+    <div className="run-history">
+      {page.runs.map((r) => {
+        const d = r.decisions.find((x) => x.tenant === tenant);
+
+        return (
+          <div className="run-row" key={r.run_id}>
+            <div
+              className="run-summary"
+              onClick={() => setOpen(open === r.run_id ? null : r.run_id)}
+            >
+              <span className={`run-result ${r.success ? "ok" : "bad"}`}>
+                {r.success ? "ok" : `exit ${r.exit_code ?? "—"}`}
+              </span>
+              <span>{r.started_at}</span>
+              <span>{r.backend ?? "default"}</span>
+              <span>{r.trigger}</span>
+              <span>
+                {d ? `${d.decision} (${d.threat_score})` : "no decision"}
+              </span>
+            </div>
+
+            {open === r.run_id && (
+              <div className="run-body">
+                <div>
+                  Run <code>{r.run_id}</code> · {r.duration_ms} ms ·{" "}
+                  <code>{r.args.join(" ")}</code>
+                </div>
+                {r.error && <div className="run-error">{r.error}</div>}
+                {d && <div>Decision: {d.reason}</div>}
+                <Stream label="stdout" out={r.stdout} />
+                <Stream label="stderr" out={r.stderr} />
+              </div>
+            )}
+          </div>
+        );
+      })}
+
+      {page.total > PAGE_SIZE && (
+        <div className="run-pager">
+          <button
+            disabled={offset === 0}
+            onClick={() => setOffset(Math.max(0, offset - PAGE_SIZE))}
+          >
+            Newer
+          </button>
+          <span>
+            {offset + 1}–{Math.min(offset + PAGE_SIZE, page.total)} of{" "}
+            {page.total}
+          </span>
+          <button
+            disabled={offset + PAGE_SIZE >= page.total}
+            onClick={() => setOffset(offset + PAGE_SIZE)}
+          >
+            Older
+          </button>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -7,6 +7,42 @@ interface TauriFile extends File {
   path: string;
 }
 
+interface ImportOutcome {
+  status: "created" | "reused" | "duplicate";
+  tenant: string;
+  sha256: string;
+  bundle?: {
+    tenant: string;
+    exported_by: string;
+    signer_fingerprint: string;
+    trusted_signer: boolean;
+  } | null;
+}
+
+function describe(res: ImportOutcome): string {
+  if (res.status === "reused") return `✔ Reusing tenant ${res.tenant}`;
+  if (res.bundle) {
+    const signer = res.bundle.trusted_signer ? "trusted" : "unknown";
+    return `✔ Bundle from ${res.bundle.exported_by} (${signer} signer) imported as ${res.tenant}`;
+  }
+  return `✔ Tenant ${res.tenant} submitted`;
+}
+
+// Same SHA-256 already imported: ask once, then reuse or import anyway.
+async function importWithDedup(path: string): Promise<ImportOutcome> {
+  const first = await invoke<ImportOutcome>("import_tenant_from_file", { path });
+  if (first.status !== "duplicate") return first;
+
+  const reuse = window.confirm(
+    `This module is already imported as ${first.tenant}. Reuse the existing tenant?`
+  );
+
+  return invoke<ImportOutcome>("import_tenant_from_file", {
+    path,
+    reuseExisting: reuse,
+  });
+}
+
 export default function TenantDropZone({ onImported }: { onImported: () => void }) {
   const [hover, setHover] = useState(false);
   const [importing, setImporting] = useState(false);
@@ -30,8 +66,8 @@ export default function TenantDropZone({ onImported }: { onImported: () => void
 
     try {
       setImporting(true);
-      const tenant = await invoke<string>("import_tenant_from_file", { path: selected });
-      setSuccess(`✔ Tenant ${tenant} submitted`);
+      const res = await importWithDedup(selected as string);
+      setSuccess(describe(res));
       onImported();
     } catch (err: any) {
       console.error(err);
@@ -57,8 +93,8 @@ export default function TenantDropZone({ onImported }: { onImported: () => void
 
     try {
       setImporting(true);
-      const tenant = await invoke<string>("import_tenant_from_file", { path: file.path });
-      setSuccess(`✔ Tenant ${tenant} submitted`);
+      const res = await importWithDedup(file.path);
+      setSuccess(describe(res));
       onImported();
     } catch (err: any) {
       console.error(err);
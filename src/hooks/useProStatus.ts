@@ -5,18 +5,36 @@ export interface ProStatus {
   is_pro: boolean;
   tier: string;
   activated_at: string | null;
+  license_id: string | null;
+  licensee: string | null;
+  expires_at: string | null;
+  seats: number | null;
+  refreshed_at: string | null;
+  /** Why a stored license does not unlock PRO. */
+  detail: string | null;
+  /** Sent to the vendor to bind a license to this device. */
+  device_fingerprint: string;
 }
 
+const OPEN_CORE: ProStatus = {
+  is_pro: false,
+  tier: "Open Core",
+  activated_at: null,
+  license_id: null,
+  licensee: null,
+  expires_at: null,
+  seats: null,
+  refreshed_at: null,
+  detail: null,
+  device_fingerprint: "",
+};
+
 /**
  * Guardian PRO status hook.
  * Reads from Tauri command `tauri_get_pro_status`.
  */
 export default function useProStatus() {
-  const [status, setStatus] = useState<ProStatus>({
-    is_pro: false,
-    tier: "Open Core",
-    activated_at: null,
-  });
+  const [status, setStatus] = useState<ProStatus>(OPEN_CORE);
 
   const refresh = useCallback(async () => {
     try {
@@ -25,11 +43,7 @@ export default function useProStatus() {
       setStatus(res);
     } catch (err) {
       console.error("tauri_get_pro_status failed:", err);
-      setStatus({
-        is_pro: false,
-        tier: "Open Core",
-        activated_at: null,
-      });
+      setStatus(OPEN_CORE);
     }
   }, []);
 
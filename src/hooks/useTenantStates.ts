@@ -15,16 +15,34 @@ export interface TenantState {
     approved: boolean;
     approved_at: string | null;
     approved_by: string | null;
+    approvers: string[];
+    expires_at: string | null;
+    max_executions: number | null;
+    executions_since_approval: number;
+    expired: boolean;
+    suspension: {
+      suspended_at: string;
+      suspended_by: string;
+      reason: string | null;
+    } | null;
   };
 
   execution: {
     has_executed: boolean;
     last_execution_time: string | null;
+    recorded_runs: number;
+    last_run_id: string | null;
   };
 
   observation: {
     current_threat_score: number | null;
-    state: "pending_approval" | "blocked" | "cleared" | "observed";
+    state:
+      | "pending_approval"
+      | "blocked"
+      | "suspended"
+      | "expired"
+      | "cleared"
+      | "observed";
   };
 }
 
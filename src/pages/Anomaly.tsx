@@ -10,25 +10,43 @@ interface DriftRecord {
   timestamp: string;
 }
 
+interface AnomalyFinding {
+  kind: "z_score" | "constant_break" | "new_wasi_import";
+  tenant: string;
+  sha256: string;
+  timestamp: string;
+  metric?: string | null;
+  value?: number | null;
+  baseline_mean?: number | null;
+  z_score?: number | null;
+  imports: string[];
+}
+
 interface AnomalyReport {
+  version: number;
+  generated_at: string;
   drift: DriftRecord[];
+  anomalies: AnomalyFinding[];
 }
 
-interface TimelineEntry {
+interface TimelineEvent {
   timestamp: string;
-  tenant: string;
+  tenant: string | null;
+  kind: string;
   event: string;
   status: string;
 }
 
-interface TimelineRun {
-  run_id: string;
-  entries: TimelineEntry[];
+interface TimelinePage {
+  total: number;
+  offset: number;
+  events: TimelineEvent[];
 }
 
 export default function Anomaly() {
   const [drift, setDrift] = useState<DriftRecord[]>([]);
-  const [timelineRuns, setTimelineRuns] = useState<TimelineRun[]>([]);
+  const [findings, setFindings] = useState<AnomalyFinding[]>([]);
+  const [traceEntries, setTraceEntries] = useState<TimelineEvent[]>([]);
   const [firecrackerLog, setFirecrackerLog] = useState<string>("");
 
   const [loading, setLoading] = useState(true);
@@ -38,31 +56,27 @@ export default function Anomaly() {
     (async () => {
       try {
         // ✅ Read runtime logs (authoritative)
-        const [driftRaw, timelineRaw, firecrackerRaw] = await Promise.all([
-          invoke<string>("read_runtime_file", { rel: "logs/anomaly_drift.json" }).catch(() => null),
-          invoke<string>("read_runtime_file", { rel: "logs/timeline.json" }).catch(() => null),
+        const [report, trace, firecrackerRaw] = await Promise.all([
+          invoke<AnomalyReport>("run_anomaly_engine").catch((e) => {
+            console.error("Anomaly engine failed:", e);
+            setErrorMsg(`Anomaly engine failed: ${e}`);
+            return null;
+          }),
+          invoke<TimelinePage>("get_timeline", {
+            kinds: ["decision", "quarantine"],
+            limit: 30,
+          }).catch(() => null),
           invoke<string>("read_runtime_file", { rel: "logs/firecracker_boot.log" }).catch(() => null),
         ]);
 
-        // Parse Drift File
-        if (driftRaw) {
-          try {
-            const parsed = JSON.parse(driftRaw) as AnomalyReport;
-            setDrift(parsed.drift || []);
-          } catch (e) {
-            console.error("Failed to parse anomaly_drift.json:", e);
-            setErrorMsg("Invalid anomaly_drift.json format.");
-          }
+        if (report) {
+          setDrift(report.drift || []);
+          setFindings(report.anomalies || []);
         }
 
-        // Parse Timeline
-        if (timelineRaw) {
-          try {
-            const parsed = JSON.parse(timelineRaw) as { runs: TimelineRun[] };
-            setTimelineRuns(parsed.runs || []);
-          } catch (e) {
-            console.error("Failed to parse timeline.json:", e);
-          }
+        // Latest decisions, newest first
+        if (trace) {
+          setTraceEntries(trace.events || []);
         }
 
         // Parse Firecracker log
@@ -90,15 +104,6 @@ export default function Anomaly() {
   const uniqueTenants = Object.keys(tenantStats).length;
   const lastDrift = drift.length ? drift[drift.length - 1].timestamp : "n/a";
 
-  // Timeline entries (flattened)
-  const recentTraceEntries: TimelineEntry[] = useMemo(() => {
-    const all: TimelineEntry[] = [];
-    for (const run of timelineRuns) {
-      for (const e of run.entries || []) all.push(e);
-    }
-    return all.slice(-30).reverse();
-  }, [timelineRuns]);
-
   const hasTimeout = useMemo(() => {
     if (!firecrackerLog) return false;
     return firecrackerLog.includes("Timeout") || firecrackerLog.includes("timeout");
@@ -201,15 +206,49 @@ export default function Anomaly() {
         </div>
       </div>
 
+      <div className="panel">
+        <h3>Baseline Anomalies</h3>
+        {findings.length === 0 ? (
+          <p className="muted">No tenant has left its baseline.</p>
+        ) : (
+          <table className="drift-table">
+            <thead>
+              <tr>
+                <th>Timestamp</th>
+                <th>Tenant</th>
+                <th>Kind</th>
+                <th>Detail</th>
+              </tr>
+            </thead>
+            <tbody>
+              {findings.slice(-50).reverse().map((a, i) => (
+                <tr key={i}>
+                  <td>{a.timestamp}</td>
+                  <td>{a.tenant}</td>
+                  <td>{a.kind.replace(/_/g, " ")}</td>
+                  <td>
+                    {a.kind === "new_wasi_import"
+                      ? a.imports.join(", ")
+                      : `${a.metric}: ${a.value} (baseline ${a.baseline_mean?.toFixed(1)}${
+                          a.z_score != null ? `, z=${a.z_score.toFixed(1)}` : ""
+                        })`}
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+      </div>
+
       <div className="layout-grid">
         <div className="panel">
           <h3>Verification Trace</h3>
 
-          {recentTraceEntries.length === 0 ? (
+          {traceEntries.length === 0 ? (
             <p className="muted">No trace entries.</p>
           ) : (
             <div className="trace-list">
-              {recentTraceEntries.map((e, i) => (
+              {traceEntries.map((e, i) => (
                 <div key={i} className="trace-row">
                   <span
                     className={`trace-status-dot ${
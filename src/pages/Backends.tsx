@@ -1,10 +1,33 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import "./backends.css";
 
+interface Check {
+  name: string;
+  status: "pass" | "fail" | "skip";
+  reason: string;
+  duration_ms: number | null;
+}
+
 interface Result {
   ok: boolean;
   message: string;
+  checks?: Check[];
+}
+
+interface PolicyKnob {
+  name: string;
+  kind: "bool" | "integer";
+  description: string;
+}
+
+interface BackendInfo {
+  name: string;
+  label: string;
+  description: string;
+  installed: boolean;
+  process: { image: string };
+  knobs: PolicyKnob[];
 }
 
 interface BackendState {
@@ -13,59 +36,56 @@ interface BackendState {
   lastChecked: string | null;
 }
 
+const IDLE: BackendState = { loading: false, result: null, lastChecked: null };
+
 function nowLabel() {
   return new Date().toLocaleString();
 }
 
+function CheckList({ checks }: { checks?: Check[] }) {
+  if (!checks || checks.length === 0) return null;
+
+  return (
+    <ul className="backend-checks">
+      {checks.map((c) => (
+        <li key={c.name} className={`check-row ${c.status}`}>
+          <span className="check-status">{c.status}</span>
+          <span className="check-name">{c.name}</span>
+          <span className="check-reason">{c.reason}</span>
+        </li>
+      ))}
+    </ul>
+  );
+}
+
 export default function Backends() {
-  const [wasmtime, setWasmtime] = useState<BackendState>({
-    loading: false,
-    result: null,
-    lastChecked: null,
-  });
-
-  const [firecracker, setFirecracker] = useState<BackendState>({
-    loading: false,
-    result: null,
-    lastChecked: null,
-  });
-
-  async function testWasmtime() {
-    setWasmtime((prev) => ({ ...prev, loading: true }));
+  const [backends, setBackends] = useState<BackendInfo[]>([]);
+  const [states, setStates] = useState<Record<string, BackendState>>({});
+
+  useEffect(() => {
+    invoke<BackendInfo[]>("list_backends")
+      .then(setBackends)
+      .catch((e) => console.error("Failed to list backends:", e));
+  }, []);
+
+  async function runDiagnostics(b: BackendInfo) {
+    setStates((prev) => ({
+      ...prev,
+      [b.name]: { ...(prev[b.name] ?? IDLE), loading: true },
+    }));
+
+    let result: Result;
     try {
-      const res = (await invoke("test_wasmtime_backend")) as Result;
-      setWasmtime({
-        loading: false,
-        result: res,
-        lastChecked: nowLabel(),
-      });
+      result = await invoke<Result>("test_backend", { name: b.name });
     } catch (e) {
-      console.error("Wasmtime test error:", e);
-      setWasmtime({
-        loading: false,
-        result: { ok: false, message: "Wasmtime check failed (see logs)" },
-        lastChecked: nowLabel(),
-      });
+      console.error(`${b.label} test error:`, e);
+      result = { ok: false, message: `${b.label} check failed (see logs)` };
     }
-  }
 
-  async function testFirecracker() {
-    setFirecracker((prev) => ({ ...prev, loading: true }));
-    try {
-      const res = (await invoke("test_firecracker_backend")) as Result;
-      setFirecracker({
-        loading: false,
-        result: res,
-        lastChecked: nowLabel(),
-      });
-    } catch (e) {
-      console.error("Firecracker test error:", e);
-      setFirecracker({
-        loading: false,
-        result: { ok: false, message: "Firecracker check failed (see logs)" },
-        lastChecked: nowLabel(),
-      });
-    }
+    setStates((prev) => ({
+      ...prev,
+      [b.name]: { loading: false, result, lastChecked: nowLabel() },
+    }));
   }
 
   return (
@@ -77,95 +97,63 @@ export default function Backends() {
       </p>
 
       <div className="backend-grid">
-        {/* WASMTIME CARD */}
-        <div className="backend-card">
-          <div className="backend-header">
-            <div className="backend-icon wasmtime-icon" />
-            <div>
-              <h3>Wasmtime Backend</h3>
-              <div className="backend-subtitle">Local WASM runtime</div>
-            </div>
-          </div>
-
-          <div className="backend-status-row">
-            <span
-              className={
-                wasmtime.result?.ok ? "status-pill ok" : "status-pill bad"
-              }
-            >
-              {wasmtime.result
-                ? wasmtime.result.ok
-                  ? "Online"
-                  : "Unavailable"
-                : "Not tested"}
-            </span>
-            {wasmtime.lastChecked && (
-              <span className="status-meta">
-                Last checked: {wasmtime.lastChecked}
-              </span>
-            )}
-          </div>
-
-          {wasmtime.result && (
-            <p className="backend-message">{wasmtime.result.message}</p>
-          )}
-
-          <div className="backend-actions">
-            <button onClick={testWasmtime} disabled={wasmtime.loading}>
-              {wasmtime.loading ? "Running diagnostics…" : "Run diagnostics"}
-            </button>
-          </div>
-
-          <ul className="backend-meta-list">
-            <li>Used for: local WASM execution</li>
-            <li>Night Core mode: Wasmtime + AUFS</li>
-          </ul>
-        </div>
-
-        {/* FIRECRACKER CARD */}
-        <div className="backend-card">
-          <div className="backend-header">
-            <div className="backend-icon firecracker-icon" />
-            <div>
-              <h3>Firecracker Backend</h3>
-              <div className="backend-subtitle">MicroVM sandbox</div>
+        {backends.map((b) => {
+          const s = states[b.name] ?? IDLE;
+
+          return (
+            <div className="backend-card" key={b.name}>
+              <div className="backend-header">
+                <div className={`backend-icon ${b.name}-icon`} />
+                <div>
+                  <h3>{b.label} Backend</h3>
+                  <div className="backend-subtitle">{b.description}</div>
+                </div>
+              </div>
+
+              <div className="backend-status-row">
+                <span
+                  className={s.result?.ok ? "status-pill ok" : "status-pill bad"}
+                >
+                  {s.result
+                    ? s.result.ok
+                      ? "Online"
+                      : "Unavailable"
+                    : b.installed
+                    ? "Detected"
+                    : "Not tested"}
+                </span>
+                {s.lastChecked && (
+                  <span className="status-meta">
+                    Last checked: {s.lastChecked}
+                  </span>
+                )}
+              </div>
+
+              {s.result && (
+                <p className="backend-message">{s.result.message}</p>
+              )}
+              <CheckList checks={s.result?.checks} />
+
+              <div className="backend-actions">
+                <button onClick={() => runDiagnostics(b)} disabled={s.loading}>
+                  {s.loading ? "Running diagnostics…" : "Run diagnostics"}
+                </button>
+              </div>
+
+              <ul className="backend-meta-list">
+                <li>
+                  Worker flag: <code>--backend {b.name}</code>
+                </li>
+                <li>Process image: {b.process.image}</li>
+                {b.knobs.map((k) => (
+                  <li key={k.name}>
+                    Policy option <code>{k.name}</code> ({k.kind}): {k.description}
+                  </li>
+                ))}
+              </ul>
             </div>
-          </div>
-
-          <div className="backend-status-row">
-            <span
-              className={
-                firecracker.result?.ok ? "status-pill ok" : "status-pill bad"
-              }
-            >
-              {firecracker.result
-                ? firecracker.result.ok
-                  ? "Online"
-                  : "Unavailable"
-                : "Not tested"}
-            </span>
-            {firecracker.lastChecked && (
-              <span className="status-meta">
-                Last checked: {firecracker.lastChecked}
-              </span>
-            )}
-          </div>
-
-          {firecracker.result && (
-            <p className="backend-message">{firecracker.result.message}</p>
-          )}
-
-          <div className="backend-actions">
-            <button onClick={testFirecracker} disabled={firecracker.loading}>
-              {firecracker.loading ? "Running diagnostics…" : "Run diagnostics"}
-            </button>
-          </div>
-
-          <ul className="backend-meta-list">
-            <li>Used for: microVM isolation</li>
-            <li>Night Core mode: Firecracker + Wasmtime</li>
-          </ul>
-        </div>
+          );
+        })}
       </div>
     </div>
   );
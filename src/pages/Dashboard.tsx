@@ -32,11 +32,20 @@ interface LogStatus {
   orchestration_json: boolean;
 }
 
+interface BackendInfo {
+  name: string;
+  label: string;
+  description: string;
+  installed: boolean;
+}
+
 interface FullSystemStatus {
   worker_root: string;
   tenants: TenantInfo[];
   logs: LogStatus;
   firecracker_installed: boolean;
+  backends: BackendInfo[];
+  policy_warnings: string[];
   worker_version: string;
   sdk_version: string;
 }
@@ -124,10 +133,14 @@ export default function Dashboard() {
       ? "ok"
       : "warn";
 
+  const installedBackends = (scan?.backends ?? []).filter((b) => b.installed);
+  const allBackendsInstalled =
+    !!scan && installedBackends.length === scan.backends.length;
+
   const backendSummaryText = scan
-    ? scan.firecracker_installed
-      ? "Wasmtime + Firecracker"
-      : "Wasmtime only"
+    ? installedBackends.length
+      ? installedBackends.map((b) => b.label).join(" + ")
+      : "None detected"
     : "loading";
 
   // Tenant risk heatmap input (Guardian decisions per-tenant)
@@ -222,13 +235,17 @@ export default function Dashboard() {
         {/* Backend Summary */}
         <div
           className={`status-card ${
-            data.firecracker_installed ? "ok" : "warn"
+            allBackendsInstalled && data.policy_warnings.length === 0
+              ? "ok"
+              : "warn"
           }`}
         >
           <span className="label">Backends</span>
           <span className="value">{backendSummaryText}</span>
           <div className="status-sub">
-            Wasmtime always available • Firecracker optional
+            {data.policy_warnings.length
+              ? `${data.policy_warnings.length} policy warning(s)`
+              : `${installedBackends.length} of ${data.backends.length} registered backends detected`}
           </div>
         </div>
       </div>
@@ -386,17 +403,19 @@ export default function Dashboard() {
         <div className="dash-card">
           <h3>Backend Summary</h3>
           <ul className="card-list">
-            <li>Wasmtime backend: active</li>
-            <li>
-              Firecracker backend:{" "}
-              <span
-                className={
-                  data.firecracker_installed ? "ok-text" : "bad-text"
-                }
-              >
-                {data.firecracker_installed ? "Available" : "Not installed"}
-              </span>
-            </li>
+            {data.backends.map((b) => (
+              <li key={b.name}>
+                {b.label} backend:{" "}
+                <span className={b.installed ? "ok-text" : "bad-text"}>
+                  {b.installed ? "Available" : "Not installed"}
+                </span>
+              </li>
+            ))}
+            {data.policy_warnings.map((w) => (
+              <li key={w} className="bad-text">
+                {w}
+              </li>
+            ))}
           </ul>
           <p className="card-hint">
             Use <code>--backend firecracker</code> for microVM isolation when
@@ -47,7 +47,8 @@ export default function Guardian() {
     return tenants.filter((t: TenantState) =>
       t.observation.state === "pending_approval" ||
       t.observation.state === "cleared" ||
-      t.observation.state === "blocked"
+      t.observation.state === "blocked" ||
+      t.observation.state === "expired"
     );
   }, [tenants]);
 
@@ -1,4 +1,4 @@
-import { useEffect, useMemo, useState } from "react";
+import { Fragment, useEffect, useMemo, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { useSearchParams } from "react-router-dom";
 import "./guardianlogs.css";
@@ -16,8 +16,72 @@ interface GuardianDecision {
   threat_level?: string;
 }
 
+interface RiskFactor {
+  factor: string;
+  weight: number;
+  detail: string;
+}
+
+interface ScoreExplanation {
+  recorded_score: number;
+  explained_score: number;
+  unexplained: number;
+  factors: RiskFactor[];
+  not_applied: RiskFactor[];
+}
+
 type ViewMode = "table" | "cards";
 
+const decisionKey = (e: GuardianDecision) => `${e.tenant}|${e.timestamp}`;
+
+/* Weighted factors behind one score, loaded on demand. */
+function ScoreBreakdown({ e }: { e: GuardianDecision }) {
+  const [x, setX] = useState<ScoreExplanation | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<ScoreExplanation>("explain_guardian_decision", {
+      tenant: e.tenant,
+      timestamp: e.timestamp,
+    })
+      .then(setX)
+      .catch((err) => setError(String(err)));
+  }, [e.tenant, e.timestamp]);
+
+  if (error) return <div className="breakdown breakdown-error">{error}</div>;
+  if (!x) return <div className="breakdown">Loading breakdown…</div>;
+
+  return (
+    <div className="breakdown">
+      <ul className="breakdown-factors">
+        {x.factors.map((f) => (
+          <li key={f.factor}>
+            <span className="breakdown-weight">+{f.weight}</span>
+            <code>{f.factor}</code> {f.detail}
+          </li>
+        ))}
+        {x.factors.length === 0 && <li>No risk factors applied.</li>}
+      </ul>
+
+      <div className="breakdown-total">
+        Factors add up to {x.explained_score}; the worker recorded{" "}
+        {x.recorded_score}.
+        {x.unexplained !== 0 &&
+          ` ${Math.abs(x.unexplained)} point(s) ${
+            x.unexplained > 0 ? "come from" : "were discounted by"
+          } worker scoring not captured in the decision fields.`}
+      </div>
+
+      {x.not_applied.length > 0 && (
+        <div className="breakdown-skipped">
+          Not applied:{" "}
+          {x.not_applied.map((f) => `${f.factor} (+${f.weight})`).join(", ")}
+        </div>
+      )}
+    </div>
+  );
+}
+
 export default function GuardianLogs() {
   const [events, setEvents] = useState<GuardianDecision[]>([]);
   const [loading, setLoading] = useState(true);
@@ -28,6 +92,10 @@ export default function GuardianLogs() {
   const [filterDecision, setFilterDecision] = useState("all");
   const [limit, setLimit] = useState("50");
   const [viewMode, setViewMode] = useState<ViewMode>("table");
+  const [expanded, setExpanded] = useState<string | null>(null);
+
+  const toggle = (e: GuardianDecision) =>
+    setExpanded((k) => (k === decisionKey(e) ? null : decisionKey(e)));
 
   // support ?tenant= deep link
   const [params] = useSearchParams();
@@ -187,23 +255,36 @@ export default function GuardianLogs() {
                 const label = threatLabel(score);
 
                 return (
-                  <tr key={idx} className={`row-${label.className}`}>
-                    <td>
-                      <span className={`score-pill ${label.className}`}>
-                        {score}
-                      </span>
-                    </td>
-                    <td>{e.tenant}</td>
-                    <td>
-                      <span className={e.decision === "allow" ? "pill pill-allow" : "pill pill-deny"}>
-                        {e.decision}
-                      </span>
-                    </td>
-                    <td>{e.backend}</td>
-                    <td>{e.proof_mode ? "yes" : "no"}</td>
-                    <td className="mono">{e.timestamp}</td>
-                    <td className="reason-cell">{e.reason}</td>
-                  </tr>
+                  <Fragment key={idx}>
+                    <tr
+                      className={`row-${label.className} clickable`}
+                      title="Show score breakdown"
+                      onClick={() => toggle(e)}
+                    >
+                      <td>
+                        <span className={`score-pill ${label.className}`}>
+                          {score}
+                        </span>
+                      </td>
+                      <td>{e.tenant}</td>
+                      <td>
+                        <span className={e.decision === "allow" ? "pill pill-allow" : "pill pill-deny"}>
+                          {e.decision}
+                        </span>
+                      </td>
+                      <td>{e.backend}</td>
+                      <td>{e.proof_mode ? "yes" : "no"}</td>
+                      <td className="mono">{e.timestamp}</td>
+                      <td className="reason-cell">{e.reason}</td>
+                    </tr>
+                    {expanded === decisionKey(e) && (
+                      <tr className="breakdown-row">
+                        <td colSpan={7}>
+                          <ScoreBreakdown e={e} />
+                        </td>
+                      </tr>
+                    )}
+                  </Fragment>
                 );
               })}
             </tbody>
@@ -238,6 +319,11 @@ export default function GuardianLogs() {
                 <div className="gl-reason">
                   <strong>Reason:</strong> {e.reason}
                 </div>
+
+                <button className="mode-btn" onClick={() => toggle(e)}>
+                  {expanded === decisionKey(e) ? "Hide breakdown" : "Why this score?"}
+                </button>
+                {expanded === decisionKey(e) && <ScoreBreakdown e={e} />}
               </div>
             );
           })}
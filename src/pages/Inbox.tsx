@@ -3,11 +3,36 @@ import { invoke } from "@tauri-apps/api/core";
 import useProStatus from "../hooks/useProStatus";
 import "./inbox.css";
 
+interface AgentProvenance {
+  agent_id: string;
+  model: string;
+  prompt_hash: string;
+  parent_task: string | null;
+  sha256: string;
+  submitted_at: string;
+  signature_verified: boolean;
+}
+
+interface InboxTriage {
+  size_bytes: number;
+  sha256: string;
+  wasi_imports: string[];
+  predicted_risk: { score: number; label: string };
+  seen_before: boolean;
+  rejected_before: boolean;
+  previous_tenant: string | null;
+  imports_added: string[];
+  imports_removed: string[];
+}
+
 interface InboxEntry {
   tenant: string;
   timestamp: string;
   signed: boolean;
   path: string;
+  provenance: AgentProvenance | null;
+  triage: InboxTriage | null;
+  suspended: boolean;
 }
 
 export default function Inbox() {
@@ -99,17 +124,74 @@ export default function Inbox() {
 
             <p className="q-field">
               <strong>Source:</strong> Agent
+              {e.provenance && <> — {e.provenance.agent_id}</>}
             </p>
 
+            {e.provenance && (
+              <>
+                <p className="q-field">
+                  <strong>Model:</strong> {e.provenance.model}
+                </p>
+                <p className="q-field small mono">
+                  <strong>Prompt:</strong> {e.provenance.prompt_hash}
+                </p>
+                {e.provenance.parent_task && (
+                  <p className="q-field small">
+                    <strong>Parent task:</strong> {e.provenance.parent_task}
+                  </p>
+                )}
+                <p className="q-field small">
+                  <strong>Agent signature:</strong>{" "}
+                  {e.provenance.signature_verified ? (
+                    <span className="status-approved">Verified</span>
+                  ) : (
+                    <span className="status-pending">Unsigned</span>
+                  )}
+                </p>
+              </>
+            )}
+
             <p className="q-field">
               <strong>Status:</strong>{" "}
-              {e.signed ? (
+              {e.suspended ? (
+                <span className="status-pending">Suspended</span>
+              ) : e.signed ? (
                 <span className="status-approved">Approved</span>
               ) : (
                 <span className="status-pending">Approval Required</span>
               )}
             </p>
 
+            {e.triage && (
+              <>
+                <p className="q-field">
+                  <strong>Predicted risk:</strong> {e.triage.predicted_risk.score}/100
+                  {" "}({e.triage.predicted_risk.label})
+                </p>
+                <p className="q-field small">
+                  <strong>Size:</strong> {(e.triage.size_bytes / 1024).toFixed(1)} KiB
+                </p>
+                <p className="q-field small mono">{e.triage.sha256}</p>
+                {e.triage.rejected_before && (
+                  <p className="q-field status-pending">Same SHA-256 was rejected before</p>
+                )}
+                {!e.triage.rejected_before && e.triage.seen_before && (
+                  <p className="q-field small">Same SHA-256 seen before</p>
+                )}
+                <p className="q-field small">
+                  <strong>Imports:</strong>{" "}
+                  {e.triage.wasi_imports.length ? e.triage.wasi_imports.join(", ") : "none"}
+                </p>
+                {e.triage.previous_tenant && (
+                  <p className="q-field small">
+                    <strong>Since {e.triage.previous_tenant}:</strong>{" "}
+                    +{e.triage.imports_added.length} / −{e.triage.imports_removed.length} imports
+                    {e.triage.imports_added.length > 0 && <> ({e.triage.imports_added.join(", ")})</>}
+                  </p>
+                )}
+              </>
+            )}
+
             <p className="q-field small">{e.timestamp}</p>
             <p className="q-field small mono">{e.path}</p>
 
@@ -117,6 +199,8 @@ export default function Inbox() {
               <div className="policy-buttons">
                 <button
                   className="btn-allow"
+                  disabled={e.suspended}
+                  title={e.suspended ? "Resume the tenant before approving" : undefined}
                   onClick={() => approve(e.tenant)}
                 >
                   Approve
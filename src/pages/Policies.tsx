@@ -5,9 +5,32 @@ import useProStatus from "../hooks/useProStatus";
 
 
 
+interface QuarantineRules {
+  score_threshold: number | null;
+  reasons: string[];
+  untrusted_signer_with_net: boolean;
+  repeated_denies: { count: number; window_minutes: number } | null;
+  auto_quarantine: boolean;
+}
+
 interface PolicyFile {
   allow: string[];
   block: string[];
+  quarantine: QuarantineRules;
+}
+
+const DEFAULT_RULES: QuarantineRules = {
+  score_threshold: 85,
+  reasons: ["quarantine"],
+  untrusted_signer_with_net: false,
+  repeated_denies: null,
+  auto_quarantine: false,
+};
+
+/* Optional whole number from a text field; blank turns the rule off. */
+function optionalNumber(v: string): number | null {
+  const n = parseInt(v, 10);
+  return v.trim() === "" || isNaN(n) ? null : n;
 }
 
 
@@ -18,7 +41,10 @@ export default function Policies() {
   const [policies, setPolicies] = useState<PolicyFile>({
     allow: [],
     block: [],
+    quarantine: DEFAULT_RULES,
   });
+  const [rules, setRules] = useState<QuarantineRules>(DEFAULT_RULES);
+  const [rulesMsg, setRulesMsg] = useState("");
 
   const [newEntry, setNewEntry] = useState("");
 
@@ -27,6 +53,7 @@ export default function Policies() {
     try {
       const data = await invoke<PolicyFile>("pro_load_policies");
       setPolicies(data);
+      setRules(data.quarantine ?? DEFAULT_RULES);
     } catch (err) {
       console.error("Failed to load policies:", err);
     } finally {
@@ -42,6 +69,15 @@ export default function Policies() {
 
   
 
+  async function saveRules() {
+    try {
+      await savePolicies({ ...policies, quarantine: rules });
+      setRulesMsg("✔ Quarantine rules saved");
+    } catch (err) {
+      setRulesMsg("❌ " + err);
+    }
+  }
+
   function addAllow() {
     if (!newEntry.trim()) return;
     const updated = {
@@ -153,6 +189,114 @@ export default function Policies() {
           ))}
         </div>
       </div>
+
+      {/* Quarantine rules */}
+      <div className="policy-card quarantine-rules">
+        <h3>Quarantine Rules</h3>
+        <p className="subtitle">
+          A decision is quarantined when any rule matches. Used by the
+          Quarantine Vault, the Timeline, schedules and auto-quarantine.
+        </p>
+
+        <label>
+          Score at or above
+          <input
+            type="number"
+            min={0}
+            max={100}
+            placeholder="off"
+            value={rules.score_threshold ?? ""}
+            onChange={(e) =>
+              setRules({ ...rules, score_threshold: optionalNumber(e.target.value) })
+            }
+          />
+        </label>
+
+        <label>
+          Reason mentions (comma separated)
+          <input
+            type="text"
+            value={rules.reasons.join(", ")}
+            onChange={(e) =>
+              setRules({
+                ...rules,
+                reasons: e.target.value
+                  .split(",")
+                  .map((r) => r.trim())
+                  .filter((r) => r !== ""),
+              })
+            }
+          />
+        </label>
+
+        <label className="rule-check">
+          <input
+            type="checkbox"
+            checked={rules.untrusted_signer_with_net}
+            onChange={(e) =>
+              setRules({ ...rules, untrusted_signer_with_net: e.target.checked })
+            }
+          />
+          Untrusted signer with network access
+        </label>
+
+        <label>
+          Denies per tenant
+          <input
+            type="number"
+            min={1}
+            placeholder="off"
+            value={rules.repeated_denies?.count ?? ""}
+            onChange={(e) => {
+              const count = optionalNumber(e.target.value);
+              setRules({
+                ...rules,
+                repeated_denies:
+                  count === null
+                    ? null
+                    : {
+                        count,
+                        window_minutes: rules.repeated_denies?.window_minutes ?? 60,
+                      },
+              });
+            }}
+          />
+          within minutes
+          <input
+            type="number"
+            min={1}
+            max={527040}
+            disabled={!rules.repeated_denies}
+            value={rules.repeated_denies?.window_minutes ?? 60}
+            onChange={(e) =>
+              rules.repeated_denies &&
+              setRules({
+                ...rules,
+                repeated_denies: {
+                  ...rules.repeated_denies,
+                  window_minutes: optionalNumber(e.target.value) ?? 1,
+                },
+              })
+            }
+          />
+        </label>
+
+        <label className="rule-check">
+          <input
+            type="checkbox"
+            checked={rules.auto_quarantine}
+            onChange={(e) =>
+              setRules({ ...rules, auto_quarantine: e.target.checked })
+            }
+          />
+          Auto-quarantine: suspend a tenant when one of its runs matches
+        </label>
+
+        <button className="btn-allow" onClick={saveRules}>
+          Save rules
+        </button>
+        {rulesMsg && <p className="rules-msg">{rulesMsg}</p>}
+      </div>
     </div>
   );
 }
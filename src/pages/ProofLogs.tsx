@@ -58,11 +58,11 @@ export default function ProofLogs() {
 
   async function exportProof(paths: string[]) {
     try {
-      const ok = await invoke("export_zip", { paths });
-      if (!ok) return;
-      alert("ZIP export coming soon.");
+      const written = await invoke<string>("export_zip", { paths });
+      alert(`Exported with signed manifest to ${written}`);
     } catch (e) {
       console.error("ZIP ERR:", e);
+      alert(`Export failed: ${e}`);
     }
   }
 
@@ -95,7 +95,7 @@ export default function ProofLogs() {
             <button
               disabled={!scan.logs.dashboard_html}
               onClick={() =>
-                openProof(scan.worker_root + "/logs/nightcore_dashboard.html")
+                openProof("worker://logs/nightcore_dashboard.html")
               }
             >
               Open
@@ -105,7 +105,7 @@ export default function ProofLogs() {
               disabled={!scan.logs.dashboard_html}
               onClick={() =>
                 exportProof([
-                  scan.worker_root + "/logs/nightcore_dashboard.html",
+                  "worker://logs/nightcore_dashboard.html",
                 ])
               }
             >
@@ -144,9 +144,7 @@ export default function ProofLogs() {
             <button
               disabled={!scan.logs.history_html}
               onClick={() =>
-                openProof(
-                  scan.worker_root + "/logs/nightcore_history_dashboard.html"
-                )
+                openProof("worker://logs/nightcore_history_dashboard.html")
               }
             >
               Open
@@ -156,7 +154,7 @@ export default function ProofLogs() {
               disabled={!scan.logs.history_html}
               onClick={() =>
                 exportProof([
-                  scan.worker_root + "/logs/nightcore_history_dashboard.html",
+                  "worker://logs/nightcore_history_dashboard.html",
                 ])
               }
             >
@@ -199,9 +197,7 @@ export default function ProofLogs() {
             <button
               disabled={!scan.logs.orchestration_html}
               onClick={() =>
-                openProof(
-                  scan.worker_root + "/logs/orchestration_dashboard.html"
-                )
+                openProof("worker://logs/orchestration_dashboard.html")
               }
             >
               Open
@@ -211,7 +207,7 @@ export default function ProofLogs() {
               disabled={!scan.logs.orchestration_html}
               onClick={() =>
                 exportProof([
-                  scan.worker_root + "/logs/orchestration_dashboard.html",
+                  "worker://logs/orchestration_dashboard.html",
                 ])
               }
             >
@@ -254,9 +250,7 @@ export default function ProofLogs() {
             <button
               disabled={!scan.logs.orchestration_json}
               onClick={() =>
-                openProof(
-                  scan.worker_root + "/logs/orchestration_report.json"
-                )
+                openProof("worker://logs/orchestration_report.json")
               }
             >
               Open
@@ -266,7 +260,7 @@ export default function ProofLogs() {
               disabled={!scan.logs.orchestration_json}
               onClick={() =>
                 exportProof([
-                  scan.worker_root + "/logs/orchestration_report.json",
+                  "worker://logs/orchestration_report.json",
                 ])
               }
             >
@@ -307,7 +301,7 @@ export default function ProofLogs() {
             <button
               disabled={!scan.logs.firecracker_html}
               onClick={() =>
-                openProof(scan.worker_root + "/logs/nightcore_proof.html")
+                openProof("worker://logs/nightcore_proof.html")
               }
             >
               Open
@@ -317,7 +311,7 @@ export default function ProofLogs() {
               disabled={!scan.logs.firecracker_html}
               onClick={() =>
                 exportProof([
-                  scan.worker_root + "/logs/nightcore_proof.html",
+                  "worker://logs/nightcore_proof.html",
                 ])
               }
             >
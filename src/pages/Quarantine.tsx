@@ -8,7 +8,9 @@ interface QuarantineEntry {
   tenant: string;     // tenant module name
   path: string;       // full quarantined directory
   timestamp: string;  // ISO time
-  reason?: string;    // optional
+  threat_score: number;
+  reason: string;
+  rules: string[];    // quarantine rules the decision matched
 }
 
 export default function Quarantine() {
@@ -90,6 +92,8 @@ export default function Quarantine() {
             <h3>{e.tenant}</h3>
 
             {e.reason && <p className="q-field"><strong>Reason:</strong> {e.reason}</p>}
+            <p className="q-field"><strong>Score:</strong> {e.threat_score}</p>
+            <p className="q-field"><strong>Matched:</strong> {e.rules.join("; ")}</p>
 
             <p className="q-field small">{e.timestamp}</p>
             <p className="q-field small mono">{e.path}</p>
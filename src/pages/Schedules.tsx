@@ -0,0 +1,181 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useTenantStates } from "../hooks/useTenantStates";
+import "./schedules.css";
+
+interface RunResult {
+  at: string;
+  outcome: "ok" | "failed" | "skipped";
+  detail: string;
+  exit_code: number | null;
+  duration_ms: number | null;
+}
+
+interface Schedule {
+  id: string;
+  tenant: string;
+  cron: string;
+  backend: string;
+  enabled: boolean;
+  created_at: string;
+  created_by: string;
+  next_run: string | null;
+  last_run: RunResult | null;
+}
+
+interface BackendInfo {
+  name: string;
+  label: string;
+}
+
+export default function Schedules() {
+  const { tenants } = useTenantStates();
+  const [schedules, setSchedules] = useState<Schedule[]>([]);
+  const [backends, setBackends] = useState<BackendInfo[]>([]);
+
+  const [tenant, setTenant] = useState("");
+  const [cron, setCron] = useState("0 * * * *");
+  const [backend, setBackend] = useState(
+    localStorage.getItem("nc-backend") || "wasmtime"
+  );
+  const [error, setError] = useState<string | null>(null);
+
+  async function load() {
+    try {
+      setSchedules(await invoke<Schedule[]>("list_schedules"));
+    } catch (e) {
+      console.error("Failed to load schedules:", e);
+    }
+  }
+
+  useEffect(() => {
+    load();
+    invoke<BackendInfo[]>("list_backends")
+      .then(setBackends)
+      .catch((e) => console.error("Failed to list backends:", e));
+
+    // Runs happen in the background; keep last/next run current.
+    const id = setInterval(load, 30000);
+    return () => clearInterval(id);
+  }, []);
+
+  async function create() {
+    setError(null);
+    try {
+      await invoke("create_schedule", { tenant, cron, backend });
+      load();
+    } catch (e) {
+      setError(String(e));
+    }
+  }
+
+  async function toggle(s: Schedule) {
+    try {
+      await invoke("set_schedule_enabled", { id: s.id, enabled: !s.enabled });
+      load();
+    } catch (e) {
+      alert(`Update failed: ${e}`);
+    }
+  }
+
+  async function remove(s: Schedule) {
+    if (!confirm(`Delete schedule "${s.cron}" for ${s.tenant}?`)) return;
+    try {
+      await invoke("delete_schedule", { id: s.id });
+      load();
+    } catch (e) {
+      alert(`Delete failed: ${e}`);
+    }
+  }
+
+  return (
+    <div className="schedules-page">
+      <h2>Schedules</h2>
+      <p className="schedules-intro">
+        Recurring tenant runs. Expressions use five cron fields in UTC
+        (minute hour day month weekday). A run is skipped while its tenant is
+        not cleared, suspended or quarantined, or when its policy does not
+        allow it.
+      </p>
+
+      <div className="schedule-form">
+        <select value={tenant} onChange={(e) => setTenant(e.target.value)}>
+          <option value="">Select tenant…</option>
+          {tenants.map((t) => (
+            <option key={t.id} value={t.id}>
+              {t.name}
+            </option>
+          ))}
+        </select>
+
+        <input
+          value={cron}
+          onChange={(e) => setCron(e.target.value)}
+          placeholder="*/30 * * * *"
+        />
+
+        <select value={backend} onChange={(e) => setBackend(e.target.value)}>
+          {backends.map((b) => (
+            <option key={b.name} value={b.name}>
+              {b.label}
+            </option>
+          ))}
+        </select>
+
+        <button disabled={!tenant || !cron.trim()} onClick={create}>
+          Add schedule
+        </button>
+      </div>
+
+      {error && <p className="schedule-error">{error}</p>}
+
+      <table className="schedule-table">
+        <thead>
+          <tr>
+            <th>Tenant</th>
+            <th>Schedule</th>
+            <th>Backend</th>
+            <th>Next run</th>
+            <th>Last run</th>
+            <th />
+          </tr>
+        </thead>
+        <tbody>
+          {schedules.map((s) => (
+            <tr key={s.id} className={s.enabled ? "" : "disabled"}>
+              <td>{s.tenant}</td>
+              <td>
+                <code>{s.cron}</code>
+              </td>
+              <td>{s.backend}</td>
+              <td>{s.enabled ? s.next_run ?? "—" : "Disabled"}</td>
+              <td>
+                {s.last_run ? (
+                  <>
+                    <span className={`run-outcome ${s.last_run.outcome}`}>
+                      {s.last_run.outcome}
+                    </span>{" "}
+                    {s.last_run.at}
+                    {s.last_run.detail && (
+                      <div className="run-detail">{s.last_run.detail}</div>
+                    )}
+                  </>
+                ) : (
+                  "Never"
+                )}
+              </td>
+              <td className="schedule-actions">
+                <button onClick={() => toggle(s)}>
+                  {s.enabled ? "Disable" : "Enable"}
+                </button>
+                <button onClick={() => remove(s)}>Delete</button>
+              </td>
+            </tr>
+          ))}
+        </tbody>
+      </table>
+
+      {schedules.length === 0 && <p className="empty">No schedules yet</p>}
+    </div>
+  );
+}
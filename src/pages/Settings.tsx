@@ -9,6 +9,7 @@ interface FullSystemStatus {
   sdk_version: string;
   worker_version: string;
   firecracker_installed: boolean;
+  backends: { name: string; label: string; installed: boolean }[];
 }
 
 export default function Settings() {
@@ -54,7 +55,7 @@ export default function Settings() {
   // Open folder via Rust
   async function openRootFolder() {
     if (!scan) return;
-    await invoke("open_path_universal", { path: scan.worker_root });
+    await invoke("open_path_universal", { path: "worker://" });
   }
 
   async function applyLicense() {
@@ -83,12 +84,23 @@ export default function Settings() {
         }, 800);
       }
     } catch (err: any) {
-      setApplyMsg("❌ License activation failed.");
+      setApplyMsg(`❌ License activation failed: ${err}`);
     } finally {
       setApplyLoading(false);
     }
   }
 
+  async function refreshLicense() {
+    setApplyMsg("");
+    try {
+      await invoke("pro_refresh_license");
+      setApplyMsg("✔ License refreshed");
+    } catch (err: any) {
+      setApplyMsg(`❌ ${err}`);
+    }
+    await pro.refresh();
+  }
+
   async function deactivate() {
     try {
       await invoke("pro_deactivate");
@@ -132,10 +144,17 @@ export default function Settings() {
             value={backend}
             onChange={(e) => setBackend(e.target.value)}
           >
-            <option value="wasmtime">Wasmtime</option>
-            <option value="firecracker" disabled={!scan?.firecracker_installed}>
-              Firecracker
-            </option>
+            {(scan?.backends ?? [{ name: backend, label: backend, installed: true }]).map(
+              (b) => (
+                <option
+                  key={b.name}
+                  value={b.name}
+                  disabled={!b.installed && b.name !== backend}
+                >
+                  {b.label}
+                </option>
+              )
+            )}
           </select>
         </div>
 
@@ -179,17 +198,26 @@ export default function Settings() {
           {!pro.is_pro && (
             <>
               <p className="card-hint">
-                Enter your Guardian PRO license key.
+                Paste your Guardian PRO license token. Licenses are verified
+                offline and bound to this device's fingerprint:
               </p>
+              <p className="card-hint small">
+                <code className="device-fingerprint">
+                  {pro.device_fingerprint}
+                </code>
+              </p>
+              {pro.detail && (
+                <p className="license-msg">Stored license: {pro.detail}</p>
+              )}
               <p className="card-hint small">
                 During public beta, a quick app refresh may be required to fully
                 apply PRO features.
               </p>
 
-              <input
-                type="text"
+              <textarea
                 className="license-input"
-                placeholder="Enter PRO license key…"
+                placeholder="NCL1.…"
+                rows={3}
                 value={licenseKey}
                 onChange={(e) => setLicenseKey(e.target.value)}
               />
@@ -211,6 +239,16 @@ export default function Settings() {
               <p className="card-hint green">
                 ✔ Guardian PRO active — {pro.tier}
               </p>
+              <p className="card-hint small">
+                {pro.licensee} · {pro.license_id} · {pro.seats} seat(s) ·{" "}
+                {pro.expires_at ? `expires ${pro.expires_at}` : "perpetual"}
+                <br />
+                Last online refresh: {pro.refreshed_at ?? "never"}
+              </p>
+
+              <button className="btn-activate-pro" onClick={refreshLicense}>
+                Refresh online
+              </button>
 
               <button className="btn-deactivate-pro" onClick={deactivate}>
                 Deactivate PRO
@@ -1,5 +1,8 @@
 import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { save } from "@tauri-apps/plugin-dialog";
+import { useTenantStates } from "../hooks/useTenantStates";
+import RunHistory from "../components/RunHistory";
 import "./tenants.css";
 
 interface TenantInfo {
@@ -15,8 +18,15 @@ interface FullSystemStatus {
   tenants: TenantInfo[];
 }
 
+interface StopOutcome {
+  stopped: { pid: number; outcome: string }[];
+  shared_runs: { pid: number }[];
+}
+
 export default function Tenants() {
   const [scan, setScan] = useState<FullSystemStatus | null>(null);
+  const { tenants: states, refresh: refreshStates } = useTenantStates();
+  const [history, setHistory] = useState<string | null>(null);
 
   useEffect(() => {
     invoke("get_full_system_scan")
@@ -24,6 +34,54 @@ export default function Tenants() {
       .catch((err) => console.error(err));
   }, []);
 
+  async function exportBundle(tenant: string) {
+    const dest = await save({
+      defaultPath: `${tenant}.zip`,
+      filters: [{ name: "Night Core Tenant Bundle", extensions: ["zip"] }],
+    });
+    if (!dest) return;
+
+    try {
+      const path = await invoke<string>("export_tenant_bundle", { tenant, dest });
+      alert(`Bundle written to ${path}`);
+    } catch (err) {
+      alert(`Export failed: ${err}`);
+    }
+  }
+
+  async function stopTenant(tenant: string) {
+    try {
+      const res = await invoke<StopOutcome>("stop_tenant", { tenant });
+      const lines = res.stopped.map(
+        (r) => `PID ${r.pid}: ${r.outcome.replace(/_/g, " ")}`
+      );
+      if (lines.length === 0) lines.push("No runs of this tenant were active.");
+      if (res.shared_runs.length > 0) {
+        lines.push(
+          `${res.shared_runs.length} run(s) covering all tenants left running; use the Kill Switch to stop them.`
+        );
+      }
+      alert(lines.join("\n"));
+    } catch (err) {
+      alert(`Stop failed: ${err}`);
+    }
+  }
+
+  async function toggleSuspend(tenant: string, suspended: boolean) {
+    try {
+      if (suspended) {
+        await invoke("resume_tenant", { tenant });
+      } else {
+        const reason = prompt(`Reason for suspending ${tenant} (optional):`);
+        if (reason === null) return;
+        await invoke("suspend_tenant", { tenant, reason });
+      }
+      refreshStates();
+    } catch (err) {
+      alert(`${suspended ? "Resume" : "Suspend"} failed: ${err}`);
+    }
+  }
+
   if (!scan) {
     return <div className="tenants-page">Loading tenants...</div>;
   }
@@ -45,6 +103,9 @@ export default function Tenants() {
               t.has_pubkey &&
               t.manifest;
 
+            const suspension = states.find((s) => s.id === t.name)
+              ?.authorization.suspension;
+
             return (
               <div className="tenant-card" key={t.name}>
                 {/* Header */}
@@ -58,6 +119,13 @@ export default function Tenants() {
                   </span>
                 </div>
 
+                {suspension && (
+                  <p className="tenant-suspended">
+                    Suspended by {suspension.suspended_by}
+                    {suspension.reason ? `: ${suspension.reason}` : ""}
+                  </p>
+                )}
+
                 {/* File status */}
                 <div className="tenant-status">
                   <p className={t.has_wasm ? "good" : "bad"}>module.wasm</p>
@@ -66,6 +134,42 @@ export default function Tenants() {
                   <p className={t.has_pubkey ? "good" : "bad"}>pubkey.b64</p>
                   <p className={t.manifest ? "good" : "bad"}>manifest.json</p>
                 </div>
+
+                <div className="tenant-actions">
+                  <button
+                    className="tenant-export"
+                    disabled={!t.has_wasm || !t.manifest}
+                    onClick={() => exportBundle(t.name)}
+                  >
+                    Export bundle
+                  </button>
+
+                  <button
+                    className="tenant-export"
+                    onClick={() => stopTenant(t.name)}
+                  >
+                    Stop
+                  </button>
+
+                  <button
+                    className="tenant-export"
+                    disabled={!t.manifest}
+                    onClick={() => toggleSuspend(t.name, !!suspension)}
+                  >
+                    {suspension ? "Resume" : "Suspend"}
+                  </button>
+
+                  <button
+                    className="tenant-export"
+                    onClick={() =>
+                      setHistory(history === t.name ? null : t.name)
+                    }
+                  >
+                    {history === t.name ? "Hide runs" : "Runs"}
+                  </button>
+                </div>
+
+                {history === t.name && <RunHistory tenant={t.name} />}
               </div>
             );
           })}
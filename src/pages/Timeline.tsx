@@ -1,17 +1,19 @@
-import { useEffect, useState } from "react";
+import { useEffect, useMemo, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import "./timeline.css";
 
-interface TimelineEntry {
+interface TimelineEvent {
   timestamp: string;
-  tenant: string;
+  tenant: string | null;
+  kind: string;
   event: string;
   status: string;
 }
 
-interface TimelineRun {
-  run_id: string;
-  entries: TimelineEntry[];
+interface TimelinePage {
+  total: number;
+  offset: number;
+  events: TimelineEvent[];
 }
 
 interface FullSystemStatus {
@@ -19,6 +21,16 @@ interface FullSystemStatus {
   tenants: { name: string }[];
 }
 
+const PAGE_SIZE = 100;
+
+const KINDS: { value: string; label: string }[] = [
+  { value: "import", label: "Imports" },
+  { value: "approval", label: "Approvals" },
+  { value: "decision", label: "Decisions" },
+  { value: "quarantine", label: "Quarantine" },
+  { value: "audit", label: "Console Audit" },
+];
+
 /* SVG STATUS DOTS (never show squares again) */
 const StatusDot = ({ status }: { status: string }) => {
   const color =
@@ -36,44 +48,54 @@ const StatusDot = ({ status }: { status: string }) => {
 };
 
 export default function Timeline() {
-  const [runs, setRuns] = useState<TimelineRun[]>([]);
+  const [page, setPage] = useState<TimelinePage | null>(null);
   const [tenants, setTenants] = useState<string[]>([]);
   const [collapsed, setCollapsed] = useState<Record<string, boolean>>({});
   const [filterTenant, setFilterTenant] = useState("all");
+  const [filterKind, setFilterKind] = useState("all");
   const [filterStatus, setFilterStatus] = useState("all");
+  const [offset, setOffset] = useState(0);
 
   useEffect(() => {
-    invoke("get_full_system_scan")
-      .then(async (res) => {
-        const scan = res as FullSystemStatus;
-
-        // Keep tenant list exactly as before
+    invoke<FullSystemStatus>("get_full_system_scan")
+      .then((scan) => {
         if (scan.tenants) {
           setTenants(scan.tenants.map((t) => t.name));
         }
-
-        try {
-          // ✅ Read runtime timeline log
-          const raw = await invoke<string>("read_runtime_file", {
-            rel: "logs/timeline.json",
-          });
-
-          if (!raw) return;
-
-          const json = JSON.parse(raw);
-          if (Array.isArray(json.runs)) {
-            setRuns(json.runs);
-          }
-        } catch (e) {
-          console.error("Failed to load timeline:", e);
-        }
       })
       .catch(console.error);
   }, []);
 
+  // Tenant and kind filter on the backend; status only narrows the page.
+  useEffect(() => {
+    invoke<TimelinePage>("get_timeline", {
+      tenant: filterTenant === "all" ? null : filterTenant,
+      kinds: filterKind === "all" ? null : [filterKind],
+      offset,
+      limit: PAGE_SIZE,
+    })
+      .then(setPage)
+      .catch((e) => console.error("Failed to load timeline:", e));
+  }, [filterTenant, filterKind, offset]);
+
+  // Group the page by day, newest first (the backend already sorts).
+  const days = useMemo(() => {
+    const groups: { day: string; events: TimelineEvent[] }[] = [];
+    for (const e of page?.events ?? []) {
+      if (filterStatus !== "all" && e.status !== filterStatus) continue;
+      const day = e.timestamp.slice(0, 10);
+      const last = groups[groups.length - 1];
+      if (last && last.day === day) last.events.push(e);
+      else groups.push({ day, events: [e] });
+    }
+    return groups;
+  }, [page, filterStatus]);
+
   const toggle = (id: string) =>
     setCollapsed((p) => ({ ...p, [id]: !p[id] }));
 
+  const total = page?.total ?? 0;
+
   return (
     <div className="timeline-page">
       <h2>Timeline</h2>
@@ -82,7 +104,10 @@ export default function Timeline() {
       <div className="filters">
         <select
           value={filterTenant}
-          onChange={(e) => setFilterTenant(e.target.value)}
+          onChange={(e) => {
+            setFilterTenant(e.target.value);
+            setOffset(0);
+          }}
         >
           <option value="all">All Tenants</option>
           {tenants.map((t) => (
@@ -90,6 +115,21 @@ export default function Timeline() {
           ))}
         </select>
 
+        <select
+          value={filterKind}
+          onChange={(e) => {
+            setFilterKind(e.target.value);
+            setOffset(0);
+          }}
+        >
+          <option value="all">All Sources</option>
+          {KINDS.map((k) => (
+            <option key={k.value} value={k.value}>
+              {k.label}
+            </option>
+          ))}
+        </select>
+
         <select
           value={filterStatus}
           onChange={(e) => setFilterStatus(e.target.value)}
@@ -101,49 +141,61 @@ export default function Timeline() {
         </select>
       </div>
 
-      {/* Runs */}
-      {runs.map((run) => {
-        const isClosed = collapsed[run.run_id] ?? false;
+      {/* Days */}
+      {days.map((d) => {
+        const isClosed = collapsed[d.day] ?? false;
 
         return (
-          <div className="timeline-run" key={run.run_id}>
-            <div className="run-header" onClick={() => toggle(run.run_id)}>
+          <div className="timeline-run" key={d.day}>
+            <div className="run-header" onClick={() => toggle(d.day)}>
               <span className="chevron">{isClosed ? "▶" : "▼"}</span>
-              <span className="run-id">{run.run_id}</span>
+              <span className="run-id">{d.day}</span>
             </div>
 
             {!isClosed && (
               <div className="run-entries">
-                {run.entries
-                  .filter(
-                    (e) =>
-                      (filterTenant === "all" || e.tenant === filterTenant) &&
-                      (filterStatus === "all" || e.status === filterStatus)
-                  )
-                  .map((e, i) => (
-                    <div className="timeline-entry" key={i}>
-                      <div className="icon">
-                        <StatusDot status={e.status} />
-                      </div>
-
-                      <div className="timestamp">{e.timestamp}</div>
-                      <div className="tenant">{e.tenant}</div>
-                      <div className="msg">{e.event}</div>
+                {d.events.map((e, i) => (
+                  <div className="timeline-entry" key={i}>
+                    <div className="icon">
+                      <StatusDot status={e.status} />
+                    </div>
+
+                    <div className="timestamp">{e.timestamp}</div>
+                    <div className="tenant">{e.tenant ?? "console"}</div>
+                    <div className="msg">
+                      <span className="event-kind">{e.kind}</span>
+                      {e.event}
                     </div>
-                  ))}
-
-                {run.entries.filter(
-                  (e) =>
-                    (filterTenant === "all" || e.tenant === filterTenant) &&
-                    (filterStatus === "all" || e.status === filterStatus)
-                ).length === 0 && (
-                  <p className="empty">No matching entries</p>
-                )}
+                  </div>
+                ))}
               </div>
             )}
           </div>
         );
       })}
+
+      {days.length === 0 && <p className="empty">No matching entries</p>}
+
+      {/* Paging */}
+      {total > PAGE_SIZE && (
+        <div className="pager">
+          <button
+            disabled={offset === 0}
+            onClick={() => setOffset(Math.max(0, offset - PAGE_SIZE))}
+          >
+            Newer
+          </button>
+          <span>
+            {offset + 1}–{Math.min(offset + PAGE_SIZE, total)} of {total}
+          </span>
+          <button
+            disabled={offset + PAGE_SIZE >= total}
+            onClick={() => setOffset(offset + PAGE_SIZE)}
+          >
+            Older
+          </button>
+        </div>
+      )}
     </div>
   );
 }